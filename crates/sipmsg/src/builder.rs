@@ -0,0 +1,475 @@
+use crate::{
+    common::bnfcore::is_token_char, SipMethod, SipRFCHeader, SipResponseStatusCode, SipVersion, Uri,
+};
+use alloc::{
+    borrow::Cow,
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::fmt::{self, Write};
+
+/// Header name and value as they are written to the message
+type HeaderNameValue<'a> = (Cow<'a, str>, String);
+
+#[derive(PartialEq, Debug)]
+pub enum BuildError {
+    /// Request method is empty or contains not token characters
+    InvalidMethod,
+    /// Extension header name is empty or contains not token characters
+    InvalidHeaderName,
+    /// Header value contains CR or LF, so it would be written as several lines
+    InvalidHeaderValue,
+    /// Reason phrase contains CR or LF
+    InvalidReasonPhrase,
+}
+
+impl fmt::Display for BuildError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            BuildError::InvalidMethod => f.write_str("Method must be a token"),
+            BuildError::InvalidHeaderName => f.write_str("Header name must be a token"),
+            BuildError::InvalidHeaderValue => f.write_str("Header value contains CR or LF"),
+            BuildError::InvalidReasonPhrase => f.write_str("Reason phrase contains CR or LF"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for BuildError {}
+
+fn has_line_break(value: &str) -> bool {
+    value.bytes().any(|c| c == b'\r' || c == b'\n')
+}
+
+/// Builder of SIP request.
+///
+/// Headers are written in the order they were added.
+/// Header value is a string or a typed header, e.g. [`CSeqHeader`](crate::CSeqHeader),
+/// it is written with `Display`.
+/// Content-Length is always computed from the body,
+/// Content-Length headers added by the caller are ignored.
+/// `build` fails if a value contains CR or LF or the method or an extension header name
+/// is not a token, so a value can't inject other headers.
+///
+/// ```rust
+/// use sipmsg::*;
+///
+/// let (_, uri) = SipUri::parse(b"sip:bob@biloxi.com").unwrap();
+/// let msg_buf = SipRequestBuilder::new(SipMethod::MESSAGE, uri)
+///     .header(SipRFCHeader::Via, "SIP/2.0/UDP pc33.atlanta.com;branch=z9hG4bK776sgdkse")
+///     .header(SipRFCHeader::MaxForwards, MaxForwards(70))
+///     .header(SipRFCHeader::CallID, "asd88asd77a@1.2.3.4")
+///     .header(
+///         SipRFCHeader::CSeq,
+///         CSeqHeader {
+///             seq: 1,
+///             method: SipMethod::MESSAGE,
+///         },
+///     )
+///     .ext_header("X-Custom", "value")
+///     .body(b"Watson, come here.")
+///     .build()
+///     .unwrap();
+///
+/// let (_, msg) = SipMessage::parse(&msg_buf).unwrap();
+/// let request = msg.request().unwrap();
+/// assert_eq!(request.rl.method, SipMethod::MESSAGE);
+/// assert_eq!(request.rl.uri.sip().unwrap().hostport.host, "biloxi.com");
+/// assert_eq!(
+///     request.headers.get_rfc_s(SipRFCHeader::ContentLength).unwrap().value.vstr,
+///     "18"
+/// );
+/// assert_eq!(request.body.as_deref().unwrap(), b"Watson, come here.");
+///
+/// let injected = SipRequestBuilder::new(SipMethod::MESSAGE, request.rl.uri.clone())
+///     .header(SipRFCHeader::Subject, "x\r\nVia: SIP/2.0/UDP evil.com")
+///     .build();
+/// assert_eq!(injected, Err(SipBuildError::InvalidHeaderValue));
+/// ```
+pub struct RequestBuilder<'a> {
+    method: SipMethod<'a>,
+    uri: Uri<'a>,
+    sip_version: SipVersion,
+    headers: Vec<HeaderNameValue<'a>>,
+    body: Option<Cow<'a, [u8]>>,
+}
+
+impl<'a> RequestBuilder<'a> {
+    /// `uri` is `SipUri`, `TelUri` or `Uri`
+    pub fn new<U>(method: SipMethod<'a>, uri: U) -> RequestBuilder<'a>
+    where
+        U: Into<Uri<'a>>,
+    {
+        RequestBuilder {
+            method,
+            uri: uri.into(),
+            sip_version: SipVersion(2, 0),
+            headers: Vec::new(),
+            body: None,
+        }
+    }
+
+    /// SIP/2.0 by default
+    pub fn sip_version(mut self, sip_version: SipVersion) -> RequestBuilder<'a> {
+        self.sip_version = sip_version;
+        self
+    }
+
+    /// Add header defined in rfc. Value is a string or a typed header
+    pub fn header<V>(mut self, hdr: SipRFCHeader, value: V) -> RequestBuilder<'a>
+    where
+        V: fmt::Display,
+    {
+        self.headers
+            .push((Cow::Borrowed(hdr.as_str()), value.to_string()));
+        self
+    }
+
+    /// Add extension header. Value is a string or a typed header
+    pub fn ext_header<N, V>(mut self, name: N, value: V) -> RequestBuilder<'a>
+    where
+        N: Into<Cow<'a, str>>,
+        V: fmt::Display,
+    {
+        self.headers.push((name.into(), value.to_string()));
+        self
+    }
+
+    pub fn body<B>(mut self, body: B) -> RequestBuilder<'a>
+    where
+        B: Into<Cow<'a, [u8]>>,
+    {
+        self.body = Some(body.into());
+        self
+    }
+
+    /// Returns bytes of message that are ready to be sent
+    pub fn build(&self) -> Result<Vec<u8>, BuildError> {
+        let method = self.method.as_str();
+        if method.is_empty() || !method.bytes().all(is_token_char) {
+            return Err(BuildError::InvalidMethod);
+        }
+        let mut head = String::new();
+        // Writing to String never fails
+        let _ = write!(head, "{} {} {}\r\n", method, self.uri, self.sip_version);
+        write_message_tail(head, &self.headers, self.body.as_deref())
+    }
+}
+
+/// Builder of SIP response.
+///
+/// Reason phrase is taken from status code if it is not set.
+/// Headers and Content-Length are handled the same way as in [`RequestBuilder`].
+///
+/// ```rust
+/// use sipmsg::{SipMessage, SipRFCHeader, SipResponseBuilder, SipResponseStatusCode};
+///
+/// let msg_buf = SipResponseBuilder::new(SipResponseStatusCode::Ringing)
+///     .header(SipRFCHeader::Via, "SIP/2.0/UDP pc33.atlanta.com;branch=z9hG4bKnashds8")
+///     .header(SipRFCHeader::To, "Bob <sip:bob@biloxi.com>;tag=a6c85cf")
+///     .header(SipRFCHeader::CSeq, "314159 INVITE")
+///     .build()
+///     .unwrap();
+///
+/// let (_, msg) = SipMessage::parse(&msg_buf).unwrap();
+/// let response = msg.response().unwrap();
+/// assert_eq!(response.sl.status_code, SipResponseStatusCode::Ringing);
+/// assert_eq!(response.sl.reason_phrase, "Ringing");
+/// assert_eq!(
+///     response.headers.get_rfc_s(SipRFCHeader::ContentLength).unwrap().value.vstr,
+///     "0"
+/// );
+/// ```
+pub struct ResponseBuilder<'a> {
+    status_code: SipResponseStatusCode,
+    reason_phrase: Option<Cow<'a, str>>,
+    sip_version: SipVersion,
+    headers: Vec<HeaderNameValue<'a>>,
+    body: Option<Cow<'a, [u8]>>,
+}
+
+impl<'a> ResponseBuilder<'a> {
+    pub fn new(status_code: SipResponseStatusCode) -> ResponseBuilder<'a> {
+        ResponseBuilder {
+            status_code,
+            reason_phrase: None,
+            sip_version: SipVersion(2, 0),
+            headers: Vec::new(),
+            body: None,
+        }
+    }
+
+    /// SIP/2.0 by default
+    pub fn sip_version(mut self, sip_version: SipVersion) -> ResponseBuilder<'a> {
+        self.sip_version = sip_version;
+        self
+    }
+
+    pub fn reason_phrase<R>(mut self, reason_phrase: R) -> ResponseBuilder<'a>
+    where
+        R: Into<Cow<'a, str>>,
+    {
+        self.reason_phrase = Some(reason_phrase.into());
+        self
+    }
+
+    /// Add header defined in rfc. Value is a string or a typed header
+    pub fn header<V>(mut self, hdr: SipRFCHeader, value: V) -> ResponseBuilder<'a>
+    where
+        V: fmt::Display,
+    {
+        self.headers
+            .push((Cow::Borrowed(hdr.as_str()), value.to_string()));
+        self
+    }
+
+    /// Add extension header. Value is a string or a typed header
+    pub fn ext_header<N, V>(mut self, name: N, value: V) -> ResponseBuilder<'a>
+    where
+        N: Into<Cow<'a, str>>,
+        V: fmt::Display,
+    {
+        self.headers.push((name.into(), value.to_string()));
+        self
+    }
+
+    pub fn body<B>(mut self, body: B) -> ResponseBuilder<'a>
+    where
+        B: Into<Cow<'a, [u8]>>,
+    {
+        self.body = Some(body.into());
+        self
+    }
+
+    /// Returns bytes of message that are ready to be sent
+    pub fn build(&self) -> Result<Vec<u8>, BuildError> {
+        let reason_phrase = match &self.reason_phrase {
+            Some(reason_phrase) => reason_phrase,
+            None => self.status_code.reason_phrase(),
+        };
+        if has_line_break(reason_phrase) {
+            return Err(BuildError::InvalidReasonPhrase);
+        }
+        let mut head = String::new();
+        // Writing to String never fails
+        let _ = write!(
+            head,
+            "{} {} {}\r\n",
+            self.sip_version,
+            self.status_code.code(),
+            reason_phrase
+        );
+        write_message_tail(head, &self.headers, self.body.as_deref())
+    }
+}
+
+/// Appends headers, computed Content-Length and body to the start line
+fn write_message_tail(
+    mut head: String,
+    headers: &[HeaderNameValue],
+    body: Option<&[u8]>,
+) -> Result<Vec<u8>, BuildError> {
+    let body = body.unwrap_or(&[]);
+    for (name, value) in headers {
+        if name.is_empty() || !name.bytes().all(is_token_char) {
+            return Err(BuildError::InvalidHeaderName);
+        }
+        if has_line_break(value) {
+            return Err(BuildError::InvalidHeaderValue);
+        }
+        if SipRFCHeader::from_str(name) == Some(SipRFCHeader::ContentLength) {
+            continue;
+        }
+        let _ = write!(head, "{}: {}\r\n", name, value);
+    }
+    let _ = write!(
+        head,
+        "{}: {}\r\n\r\n",
+        SipRFCHeader::ContentLength.as_str(),
+        body.len()
+    );
+
+    let mut result = head.into_bytes();
+    result.extend_from_slice(body);
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        CSeqHeader, ContentType, Expires, MaxForwards, NameAddrHeader, SipHeader as Header,
+        SipMessage, SipRequestUriScheme, SipUri, ViaHeader,
+    };
+
+    #[test]
+    fn request_builder_round_trip() {
+        let (_, uri) =
+            SipUri::parse(b"sips:alice:pass@[2001:db8::10]:5061;transport=tls;lr?subject=call")
+                .unwrap();
+        let msg_buf = RequestBuilder::new(SipMethod::INVITE, uri)
+            .header(SipRFCHeader::Via, "SIP/2.0/TLS 10.0.0.1;branch=z9hG4bK1")
+            .header(SipRFCHeader::Via, "SIP/2.0/TLS 10.0.0.2;branch=z9hG4bK2")
+            .header(SipRFCHeader::ContentLength, "100")
+            .ext_header(String::from("X-Owned"), String::from("owned value"))
+            .body(Vec::from(&b"v=0\r\n"[..]))
+            .build()
+            .unwrap();
+
+        let (rest, msg) = SipMessage::parse(&msg_buf).unwrap();
+        assert_eq!(rest.len(), 0);
+        let request = msg.request().unwrap();
+        assert_eq!(request.rl.method, SipMethod::INVITE);
+        assert_eq!(request.rl.sip_version, SipVersion(2, 0));
+        assert_eq!(
+            request.rl.uri.sip().unwrap().scheme,
+            SipRequestUriScheme::SIPS
+        );
+        assert_eq!(
+            request.rl.uri.sip().unwrap().user_info().unwrap().value,
+            "alice"
+        );
+        assert_eq!(request.rl.uri.sip().unwrap().hostport.host, "2001:db8::10");
+        assert_eq!(request.rl.uri.sip().unwrap().hostport.port, Some(5061));
+        assert_eq!(
+            request
+                .rl
+                .uri
+                .sip()
+                .unwrap()
+                .params()
+                .unwrap()
+                .get("transport"),
+            Some(Some("tls"))
+        );
+        assert_eq!(
+            request.rl.uri.sip().unwrap().params().unwrap().get("lr"),
+            Some(None)
+        );
+        assert_eq!(
+            request.rl.uri.sip().unwrap().headers().unwrap()["subject"],
+            "call"
+        );
+
+        let vias = request.headers.get_rfc(SipRFCHeader::Via).unwrap();
+        assert_eq!(vias.len(), 2);
+        assert_eq!(vias[1].value.vstr, "SIP/2.0/TLS 10.0.0.2");
+        assert_eq!(
+            request
+                .headers
+                .get_rfc_s(SipRFCHeader::ContentLength)
+                .unwrap()
+                .value
+                .vstr,
+            "5"
+        );
+        assert_eq!(
+            request.headers.get_ext_s("x-owned").unwrap().value.vstr,
+            "owned value"
+        );
+        assert_eq!(request.body.as_deref().unwrap(), b"v=0\r\n");
+    }
+
+    #[test]
+    fn response_builder_reason_phrase() {
+        let msg_buf = ResponseBuilder::new(SipResponseStatusCode::from_code(599).unwrap())
+            .reason_phrase("Custom Reason")
+            .build()
+            .unwrap();
+        assert_eq!(
+            msg_buf,
+            b"SIP/2.0 599 Custom Reason\r\nContent-Length: 0\r\n\r\n".to_vec()
+        );
+
+        let msg_buf = ResponseBuilder::new(SipResponseStatusCode::BusyHere)
+            .build()
+            .unwrap();
+        assert_eq!(
+            msg_buf,
+            b"SIP/2.0 486 Busy Here\r\nContent-Length: 0\r\n\r\n".to_vec()
+        );
+
+        let msg_buf = ResponseBuilder::new(SipResponseStatusCode::OK)
+            .reason_phrase("OK\r\nVia: SIP/2.0/UDP evil.com")
+            .build();
+        assert_eq!(msg_buf, Err(BuildError::InvalidReasonPhrase));
+    }
+
+    #[test]
+    fn request_builder_typed_headers() {
+        let (_, uri) = SipUri::parse(b"sip:bob@biloxi.com").unwrap();
+        let via = Header::from_name_value(
+            "Via",
+            "SIP/2.0/tcp [2001:db8::9]:5061;branch=z9hG4bK1;received=[2001:db8::1];rport;maddr=224.2.0.1;ttl=16",
+        )
+        .unwrap();
+        let via = via.typed::<ViaHeader>().unwrap().into_owned();
+        let from =
+            Header::from_name_value("f", "\"A \\\"B\\\"\" <sip:alice@atlanta.com>;tag=88sja8x")
+                .unwrap();
+        let from = from.typed::<NameAddrHeader>().unwrap().into_owned();
+        let content_type = Header::from_name_value("c", "text/plain;charset=utf-8").unwrap();
+        let content_type = content_type.typed::<ContentType>().unwrap().into_owned();
+        let cseq = CSeqHeader {
+            seq: 9,
+            method: SipMethod::MESSAGE,
+        };
+
+        let msg_buf = RequestBuilder::new(SipMethod::MESSAGE, uri.clone())
+            .header(SipRFCHeader::Via, &via)
+            .header(SipRFCHeader::From, &from)
+            .header(SipRFCHeader::To, &from)
+            .header(SipRFCHeader::CSeq, &cseq)
+            .header(SipRFCHeader::MaxForwards, MaxForwards(70))
+            .header(SipRFCHeader::Expires, Expires(30))
+            .header(SipRFCHeader::ContentType, &content_type)
+            .build()
+            .unwrap();
+        let (_, msg) = SipMessage::parse(&msg_buf).unwrap();
+        let hdrs = &msg.request().unwrap().headers;
+        let typed_via: ViaHeader = hdrs.get_typed(SipRFCHeader::Via).unwrap().unwrap();
+        assert_eq!(typed_via, via);
+        let typed_from: NameAddrHeader = hdrs.get_typed(SipRFCHeader::From).unwrap().unwrap();
+        assert_eq!(typed_from, from);
+        let typed_cseq: CSeqHeader = hdrs.get_typed(SipRFCHeader::CSeq).unwrap().unwrap();
+        assert_eq!(typed_cseq, cseq);
+        let max_forwards: MaxForwards = hdrs.get_typed(SipRFCHeader::MaxForwards).unwrap().unwrap();
+        assert_eq!(max_forwards, MaxForwards(70));
+        let expires: Expires = hdrs.get_typed(SipRFCHeader::Expires).unwrap().unwrap();
+        assert_eq!(expires, Expires(30));
+        let typed_content_type: ContentType =
+            hdrs.get_typed(SipRFCHeader::ContentType).unwrap().unwrap();
+        assert_eq!(typed_content_type, content_type);
+    }
+
+    #[test]
+    fn builder_rejects_injection() {
+        let (_, uri) = SipUri::parse(b"sip:bob@biloxi.com").unwrap();
+        let builder = || RequestBuilder::new(SipMethod::OPTIONS, uri.clone());
+        assert_eq!(
+            builder()
+                .header(SipRFCHeader::Subject, "x\r\nVia: SIP/2.0/UDP evil.com")
+                .build(),
+            Err(BuildError::InvalidHeaderValue)
+        );
+        assert_eq!(
+            builder().ext_header("X-Value", "a\nb").build(),
+            Err(BuildError::InvalidHeaderValue)
+        );
+        assert_eq!(
+            builder().ext_header("Via: x\r\nX", "1").build(),
+            Err(BuildError::InvalidHeaderName)
+        );
+        assert_eq!(
+            builder().ext_header("", "1").build(),
+            Err(BuildError::InvalidHeaderName)
+        );
+        // extension method that is not a token can't be created
+        assert_eq!(SipMethod::extension("X sip:a SIP/2.0\r\nVia: evil"), None);
+        assert_eq!(SipMethod::extension(""), None);
+        assert!(builder()
+            .ext_header("X-Value", "a b\t\"c\"")
+            .build()
+            .is_ok());
+    }
+}
@@ -1,94 +1,217 @@
 use core::convert::From;
-use core::str;
+use core::{fmt, str};
 use nom;
 use nom::error::{ErrorKind, ParseError};
 
+/// What part of message is invalid
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum ParseErrorKind {
+    /// Type of message can't be detected by the first line
+    UnknownMessageType,
+    InvalidRequestLine,
+    InvalidStatusLine,
+    InvalidMethod,
+    InvalidStatusCode,
+    InvalidUri,
+    InvalidHostPort,
+    InvalidUserInfo,
+    InvalidHeaderName,
+    InvalidHeaderValue,
+    InvalidParameter,
+    InvalidQuotedString,
+    InvalidContentLength,
+    MultipleContentLength,
+    /// Content-Length is greater than count of bytes after the header section
+    BodyTruncated {
+        /// Value of Content-Length
+        declared: usize,
+        /// Count of bytes after the header section
+        available: usize,
+    },
+    InvalidUtf8,
+    /// Line of start line or headers is terminated by bare LF
+    InvalidLineEnding,
+    /// Start line and headers of streamed message exceed the configured limit
+    HeaderSectionTooLarge,
+    /// Content-Length of streamed message exceeds the configured limit
+    BodyTooLarge,
+    /// Error of the low level parser
+    Nom(ErrorKind),
+}
+
+impl ParseErrorKind {
+    pub fn description(&self) -> &str {
+        match self {
+            ParseErrorKind::UnknownMessageType => "Unknown message type",
+            ParseErrorKind::InvalidRequestLine => "Invalid request line",
+            ParseErrorKind::InvalidStatusLine => "Invalid status line",
+            ParseErrorKind::InvalidMethod => "Invalid method",
+            ParseErrorKind::InvalidStatusCode => "Invalid status code",
+            ParseErrorKind::InvalidUri => "Invalid URI",
+            ParseErrorKind::InvalidHostPort => "Invalid host or port",
+            ParseErrorKind::InvalidUserInfo => "Invalid userinfo",
+            ParseErrorKind::InvalidHeaderName => "Invalid header name",
+            ParseErrorKind::InvalidHeaderValue => "Invalid header value",
+            ParseErrorKind::InvalidParameter => "Invalid parameter",
+            ParseErrorKind::InvalidQuotedString => "Invalid quoted string",
+            ParseErrorKind::InvalidContentLength => "Invalid Content-Length",
+            ParseErrorKind::MultipleContentLength => "Multiple Content-Length headers",
+            ParseErrorKind::BodyTruncated { .. } => "Body is shorter than Content-Length",
+            ParseErrorKind::InvalidUtf8 => "Invalid UTF-8",
+            ParseErrorKind::InvalidLineEnding => "Line is not terminated by CRLF",
+            ParseErrorKind::HeaderSectionTooLarge => "Header section is too large",
+            ParseErrorKind::BodyTooLarge => "Body is too large",
+            ParseErrorKind::Nom(kind) => kind.description(),
+        }
+    }
+}
+
+impl fmt::Display for ParseErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.description())?;
+        if let ParseErrorKind::BodyTruncated {
+            declared,
+            available,
+        } = self
+        {
+            write!(f, " ({} of {} bytes)", available, declared)?;
+        }
+        Ok(())
+    }
+}
+
 #[derive(Debug)]
 pub struct SipParseError<'a> {
-    pub code: u32,
-    pub message: Option<&'a str>,
+    pub kind: ParseErrorKind,
+    /// Details of error
+    pub message: Option<&'static str>,
+    /// Byte offset of the failure from the beginning of parsed input
+    pub offset: Option<usize>,
+    /// Line number of the failure, starts from 1
+    pub line: Option<usize>,
+    /// Name of header that was parsed
+    pub header: Option<&'a str>,
+    /// Rest of input where the failure is detected
+    input: Option<&'a [u8]>,
 }
 
 impl<'a> From<(&'a str, ErrorKind)> for SipParseError<'a> {
     fn from(error: (&'a str, ErrorKind)) -> Self {
-        SipParseError {
-            code: error.1 as u32,
-            message: Some(error.0),
-        }
+        SipParseError::from_error_kind(error.0.as_bytes(), error.1)
     }
 }
 
 impl<'a> ParseError<&'a str> for SipParseError<'a> {
     fn from_error_kind(error: &'a str, kind: ErrorKind) -> Self {
-        SipParseError {
-            code: kind as u32,
-            message: Some(error),
-        }
+        SipParseError::from_error_kind(error.as_bytes(), kind)
     }
 
     fn append(error: &'a str, kind: ErrorKind, _other: SipParseError) -> Self {
-        SipParseError {
-            code: kind as u32,
-            message: Some(error),
-        }
+        SipParseError::from_error_kind(error.as_bytes(), kind)
     }
 }
 
 #[macro_export]
 macro_rules! sip_parse_error {
-    // error with message
-    ($error_code:expr) => {
-        Err(nom::Err::Error(SipParseError::new($error_code, None)))
+    // error without message
+    ($error_kind:ident) => {
+        Err(nom::Err::Error(SipParseError::new(
+            $crate::errorparse::ParseErrorKind::$error_kind,
+            None,
+        )))
     };
 
-    // error without message
-    ($error_code:expr, $message:expr) => {
+    // error with message
+    ($error_kind:ident, $message:expr) => {
         Err(nom::Err::Error(SipParseError::new(
-            $error_code,
+            $crate::errorparse::ParseErrorKind::$error_kind,
             Some($message),
         )))
     };
 }
 
 impl<'a> SipParseError<'a> {
-    pub fn new(code: u32, message: Option<&'a str>) -> SipParseError {
+    pub fn new(kind: ParseErrorKind, message: Option<&'static str>) -> SipParseError<'a> {
         SipParseError {
-            code: code,
-            message: message,
+            kind,
+            message,
+            offset: None,
+            line: None,
+            header: None,
+            input: None,
         }
     }
-}
 
-impl<'a> ParseError<&'a [u8]> for SipParseError<'a> {
-    fn from_error_kind(error: &'a [u8], kind: ErrorKind) -> Self {
-        let error_str: &str;
-        match str::from_utf8(error) {
-            Ok(err_str) => {
-                error_str = err_str;
-            }
-            Err(_) => {
-                error_str = "Internal error of parser. Can't cast error string to to utf8";
-            }
+    /// Sets the rest of input where the failure is detected, if it is not set yet
+    pub(crate) fn at(mut self, input: &'a [u8]) -> SipParseError<'a> {
+        if self.input.is_none() {
+            self.input = Some(input);
         }
-        SipParseError {
-            code: kind as u32,
-            message: Some(error_str),
+        self
+    }
+
+    /// Replaces error of low level parser by `kind`
+    pub(crate) fn or_kind(mut self, kind: ParseErrorKind) -> SipParseError<'a> {
+        if let ParseErrorKind::Nom(_) = self.kind {
+            self.kind = kind;
         }
+        self
     }
 
-    fn append(error: &'a [u8], kind: ErrorKind, _other: SipParseError) -> Self {
-        let error_str: &str;
-        match str::from_utf8(error) {
-            Ok(err_str) => {
-                error_str = err_str;
-            }
-            Err(_) => {
-                error_str = "Internal error of parser. Can't cast error string to to utf8";
-            }
+    /// Marks error as the error of header value
+    pub(crate) fn in_header(mut self, name: &'a str, value: &'a [u8]) -> SipParseError<'a> {
+        if self.header.is_none() {
+            self.header = Some(name);
         }
-        SipParseError {
-            code: kind as u32,
-            message: Some(error_str),
+        self.or_kind(ParseErrorKind::InvalidHeaderValue).at(value)
+    }
+
+    /// Calculates offset and line of the failure relative to `base`
+    pub(crate) fn locate(mut self, base: &[u8]) -> SipParseError<'a> {
+        let input = match self.input {
+            Some(input) => input,
+            None => return self,
+        };
+        let base_start = base.as_ptr() as usize;
+        let input_start = input.as_ptr() as usize;
+        if input_start < base_start || input_start > base_start + base.len() {
+            return self;
+        }
+        let offset = input_start - base_start;
+        self.offset = Some(offset);
+        self.line = Some(base[..offset].iter().filter(|c| **c == b'\n').count() + 1);
+        self
+    }
+}
+
+impl<'a> fmt::Display for SipParseError<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.kind)?;
+        if let Some(header) = self.header {
+            write!(f, " in {} header", header)?;
         }
+        if let Some(line) = self.line {
+            write!(f, " at line {}", line)?;
+        }
+        if let Some(offset) = self.offset {
+            write!(f, " (offset {})", offset)?;
+        }
+        if let Some(message) = self.message {
+            write!(f, ": {}", message)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "std")]
+impl<'a> std::error::Error for SipParseError<'a> {}
+
+impl<'a> ParseError<&'a [u8]> for SipParseError<'a> {
+    fn from_error_kind(error: &'a [u8], kind: ErrorKind) -> Self {
+        SipParseError::new(ParseErrorKind::Nom(kind), None).at(error)
+    }
+
+    fn append(error: &'a [u8], kind: ErrorKind, _other: SipParseError) -> Self {
+        SipParseError::new(ParseErrorKind::Nom(kind), None).at(error)
     }
 }
@@ -0,0 +1,236 @@
+//! Percent-encoding of URI components:
+//! `escaped = "%" HEXDIG HEXDIG` ([rfc3261 section-25.1](https://tools.ietf.org/html/rfc3261#section-25.1)),
+//! and quoted-pairs of quoted-string: `quoted-pair = "\\" (%x00-09 / %x0B-0C / %x0E-7F)`
+//!
+//! ```rust
+//! use sipmsg::common::escape;
+//!
+//! assert_eq!(escape::unescape_str("alice%40atlanta.com"), "alice@atlanta.com");
+//! assert_eq!(escape::escape_user("alice@home"), "alice%40home");
+//! assert_eq!(escape::escape_hvalue("project x"), "project%20x");
+//! assert_eq!(escape::unquote(r#"\"Bob\" \\ Alice"#), r#""Bob" \ Alice"#);
+//! ```
+use crate::common::bnfcore::{
+    is_hexdig, is_hnv_char, is_param_char, is_password_char, is_unreserved, is_user_unreserved_char,
+};
+use alloc::{
+    borrow::Cow,
+    string::{String, ToString},
+    vec::Vec,
+};
+
+fn hex_value(c: u8) -> u8 {
+    match c {
+        b'0'..=b'9' => c - b'0',
+        b'a'..=b'f' => c - b'a' + 10,
+        _ => c - b'A' + 10,
+    }
+}
+
+/// Replaces `escaped = "%" HEXDIG HEXDIG` by the octet.
+/// `%` that is not followed by two hex digits is kept as is.
+/// Input is borrowed if it does not contain escapes
+pub fn unescape(input: &str) -> Cow<'_, [u8]> {
+    let bytes = input.as_bytes();
+    if !bytes.contains(&b'%') {
+        return Cow::Borrowed(bytes);
+    }
+    let mut result = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%'
+            && i + 2 < bytes.len()
+            && is_hexdig(bytes[i + 1])
+            && is_hexdig(bytes[i + 2])
+        {
+            result.push(hex_value(bytes[i + 1]) << 4 | hex_value(bytes[i + 2]));
+            i += 3;
+        } else {
+            result.push(bytes[i]);
+            i += 1;
+        }
+    }
+    Cow::Owned(result)
+}
+
+/// Same as [`unescape`], but returns string.
+/// Octets that are not valid UTF-8 after unescaping are replaced by U+FFFD
+pub fn unescape_str(input: &str) -> Cow<'_, str> {
+    match unescape(input) {
+        Cow::Borrowed(_) => Cow::Borrowed(input),
+        Cow::Owned(bytes) => match String::from_utf8(bytes) {
+            Ok(s) => Cow::Owned(s),
+            Err(e) => Cow::Owned(String::from_utf8_lossy(e.as_bytes()).to_string()),
+        },
+    }
+}
+
+/// Form of URI component that is used for comparison
+/// ([rfc3261 section-19.1.4](https://tools.ietf.org/html/rfc3261#section-19.1.4)):
+/// escaped unreserved characters are replaced by the character,
+/// other escapes are kept with uppercase hex digits.
+/// Input is borrowed if it does not contain escapes
+pub fn unescape_unreserved(input: &str) -> Cow<'_, str> {
+    let bytes = input.as_bytes();
+    if !bytes.contains(&b'%') {
+        return Cow::Borrowed(input);
+    }
+    let mut result = String::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%'
+            && i + 2 < bytes.len()
+            && is_hexdig(bytes[i + 1])
+            && is_hexdig(bytes[i + 2])
+        {
+            let c = hex_value(bytes[i + 1]) << 4 | hex_value(bytes[i + 2]);
+            if is_unreserved(c) {
+                result.push(c as char);
+            } else {
+                result.push('%');
+                result.push(bytes[i + 1].to_ascii_uppercase() as char);
+                result.push(bytes[i + 2].to_ascii_uppercase() as char);
+            }
+            i += 3;
+        } else {
+            let len = utf8_len(bytes[i]);
+            result.push_str(&input[i..i + len]);
+            i += len;
+        }
+    }
+    Cow::Owned(result)
+}
+
+/// Length of UTF-8 sequence by its first byte
+fn utf8_len(c: u8) -> usize {
+    match c {
+        0xF0..=0xFF => 4,
+        0xE0..=0xEF => 3,
+        0xC0..=0xDF => 2,
+        _ => 1,
+    }
+}
+
+/// Resolves quoted-pairs of quoted-string content (without surrounding quotes).
+/// Input is borrowed if it does not contain backslash
+pub fn unquote(input: &str) -> Cow<'_, str> {
+    if !input.contains('\\') {
+        return Cow::Borrowed(input);
+    }
+    let mut result = String::with_capacity(input.len());
+    let mut chars = input.chars();
+    while let Some(c) = chars.next() {
+        if c == '\\' {
+            if let Some(escaped) = chars.next() {
+                result.push(escaped);
+            }
+        } else {
+            result.push(c);
+        }
+    }
+    Cow::Owned(result)
+}
+
+/// Escapes every octet that is not allowed by `is_allowed`.
+/// `%` is always escaped. Input is borrowed if nothing is escaped
+pub fn escape(input: &str, is_allowed: fn(u8) -> bool) -> Cow<'_, str> {
+    let need_escape = |c: u8| c == b'%' || !is_allowed(c);
+    if !input.bytes().any(need_escape) {
+        return Cow::Borrowed(input);
+    }
+    const HEX: &[u8; 16] = b"0123456789ABCDEF";
+    let mut result = String::with_capacity(input.len() + 8);
+    for c in input.bytes() {
+        if need_escape(c) {
+            result.push('%');
+            result.push(HEX[(c >> 4) as usize] as char);
+            result.push(HEX[(c & 0x0F) as usize] as char);
+        } else {
+            result.push(c as char);
+        }
+    }
+    Cow::Owned(result)
+}
+
+/// user = 1*( unreserved / escaped / user-unreserved )
+pub fn escape_user(input: &str) -> Cow<'_, str> {
+    escape(input, |c| is_unreserved(c) || is_user_unreserved_char(c))
+}
+
+/// password = *( unreserved / escaped / "&" / "=" / "+" / "$" / "," )
+pub fn escape_password(input: &str) -> Cow<'_, str> {
+    escape(input, is_password_char)
+}
+
+/// pname / pvalue = 1*paramchar
+pub fn escape_param(input: &str) -> Cow<'_, str> {
+    escape(input, is_param_char)
+}
+
+/// hname = 1*( hnv-unreserved / unreserved / escaped )
+pub fn escape_hname(input: &str) -> Cow<'_, str> {
+    escape(input, is_hnv_char)
+}
+
+/// hvalue = *( hnv-unreserved / unreserved / escaped )
+pub fn escape_hvalue(input: &str) -> Cow<'_, str> {
+    escape(input, is_hnv_char)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unescape_test() {
+        assert!(matches!(unescape("alice"), Cow::Borrowed(b"alice")));
+        assert_eq!(unescape("%61lice%40atlanta.com"), &b"alice@atlanta.com"[..]);
+        assert_eq!(unescape("100%"), &b"100%"[..]);
+        assert_eq!(unescape("%zz%2"), &b"%zz%2"[..]);
+        assert_eq!(unescape("%E2%82%AC"), "€".as_bytes());
+    }
+
+    #[test]
+    fn unescape_str_test() {
+        assert!(matches!(unescape_str("alice"), Cow::Borrowed("alice")));
+        assert_eq!(unescape_str("project%20x"), "project x");
+        assert_eq!(unescape_str("%E2%82%AC%FF"), "€\u{FFFD}");
+    }
+
+    #[test]
+    fn unescape_unreserved_test() {
+        assert!(matches!(
+            unescape_unreserved("alice"),
+            Cow::Borrowed("alice")
+        ));
+        assert_eq!(unescape_unreserved("%61lice%2e"), "alice.");
+        assert_eq!(
+            unescape_unreserved("alice%3bday=tuesday"),
+            "alice%3Bday=tuesday"
+        );
+        assert_eq!(unescape_unreserved("a%20b%zz"), "a%20b%zz");
+        assert_eq!(unescape_unreserved("€%E2%82%AC"), "€%E2%82%AC");
+    }
+
+    #[test]
+    fn escape_test() {
+        assert!(matches!(escape_user("alice"), Cow::Borrowed("alice")));
+        assert_eq!(escape_user("al ice;x=1@%"), "al%20ice;x=1%40%25");
+        assert_eq!(escape_password("p@ss;word"), "p%40ss%3Bword");
+        assert_eq!(escape_param("a=b;c[1]"), "a%3Db%3Bc[1]");
+        assert_eq!(escape_hname("x&y"), "x%26y");
+        assert_eq!(escape_hvalue("sip:bob@biloxi.com"), "sip:bob%40biloxi.com");
+        assert_eq!(escape_hvalue("€"), "%E2%82%AC");
+        for s in ["al ice@%", "€ ;?", "plain"].iter() {
+            assert_eq!(unescape_str(&escape_user(s)), *s);
+            assert_eq!(unescape_str(&escape_hvalue(s)), *s);
+        }
+    }
+
+    #[test]
+    fn unquote_test() {
+        assert!(matches!(unquote("Bob"), Cow::Borrowed("Bob")));
+        assert_eq!(unquote(r#"a \"b\" c"#), r#"a "b" c"#);
+        assert_eq!(unquote(r"a\\b\"), r"a\b");
+    }
+}
@@ -1,15 +1,155 @@
 use crate::common::{bnfcore::*, errorparse::SipParseError, nom_wrappers::from_utf8_nom};
-use core::str;
+use alloc::borrow::Cow;
+use core::{
+    fmt,
+    net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr},
+    str,
+};
 use nom::bytes::complete::{take, take_until, take_while1};
 
+/// host = hostname / IPv4address / IPv6reference
+///
+/// ```rust
+/// use sipmsg::SipHost;
+/// use core::net::{IpAddr, Ipv4Addr};
+///
+/// assert_eq!(SipHost::from_str("atlanta.com"), Some(SipHost::Domain("atlanta.com".into())));
+/// assert_eq!(
+///     SipHost::from_str("192.0.2.4").unwrap().ip_addr(),
+///     Some(IpAddr::V4(Ipv4Addr::new(192, 0, 2, 4)))
+/// );
+/// assert_eq!(SipHost::from_str("-atlanta.com"), None);
+/// ```
+#[derive(Clone, PartialEq, Debug)]
+pub enum Host<'a> {
+    /// hostname, case is kept as is
+    Domain(Cow<'a, str>),
+    Ipv4(Ipv4Addr),
+    /// IPv6 address of IPv6reference
+    Ipv6(Ipv6Addr),
+}
+
 // domainlabel      =  alphanum / alphanum *( alphanum / "-" ) alphanum
 // toplabel         =  ALPHA / ALPHA *( alphanum / "-" ) alphanum
 // hostname         =  *( domainlabel "." ) toplabel [ "." ]
-// host             =  hostname / IPv4address / IPv6reference
+fn is_hostname(s: &str) -> bool {
+    let s = s.strip_suffix('.').unwrap_or(s);
+    let is_label = |label: &[u8]| {
+        !label.is_empty()
+            && is_alphanum(label[0])
+            && is_alphanum(label[label.len() - 1])
+            && label.iter().all(|c| is_alphanum(*c) || *c == b'-')
+    };
+    let mut labels = s.split('.').rev();
+    match labels.next() {
+        Some(toplabel) if is_label(toplabel.as_bytes()) && is_alpha(toplabel.as_bytes()[0]) => {}
+        _ => return false,
+    }
+    labels.all(|label| is_label(label.as_bytes()))
+}
+
+// IPv4address    =  1*3DIGIT "." 1*3DIGIT "." 1*3DIGIT "." 1*3DIGIT
+fn parse_ipv4(s: &str) -> Option<Ipv4Addr> {
+    let mut octets = [0u8; 4];
+    let mut parts = s.split('.');
+    for octet in octets.iter_mut() {
+        let part = parts.next()?;
+        if part.is_empty() || part.len() > 3 || !part.bytes().all(is_digit) {
+            return None;
+        }
+        *octet = part.parse().ok()?;
+    }
+    if parts.next().is_some() {
+        return None;
+    }
+    Some(Ipv4Addr::from(octets))
+}
+
+impl<'a> Host<'a> {
+    /// Validates host against rfc3261 grammar.
+    /// IPv6 address must be without brackets
+    pub fn from_str(s: &'a str) -> Option<Host<'a>> {
+        if s.contains(':') {
+            return s.parse().ok().map(Host::Ipv6);
+        }
+        if s.bytes().all(|c| is_digit(c) || c == b'.') {
+            return parse_ipv4(s).map(Host::Ipv4);
+        }
+        if is_hostname(s) {
+            return Some(Host::Domain(Cow::Borrowed(s)));
+        }
+        None
+    }
+
+    pub fn ip_addr(&self) -> Option<IpAddr> {
+        match self {
+            Host::Domain(_) => None,
+            Host::Ipv4(addr) => Some(IpAddr::V4(*addr)),
+            Host::Ipv6(addr) => Some(IpAddr::V6(*addr)),
+        }
+    }
+
+    pub fn is_domain(&self) -> bool {
+        matches!(self, Host::Domain(_))
+    }
+
+    /// Domains are compared case-insensitively, addresses by value
+    pub fn equivalent(&self, other: &Host) -> bool {
+        match (self, other) {
+            (Host::Domain(a), Host::Domain(b)) => a.eq_ignore_ascii_case(b),
+            _ => self.ip_addr().is_some() && self.ip_addr() == other.ip_addr(),
+        }
+    }
+
+    pub fn into_owned(self) -> Host<'static> {
+        match self {
+            Host::Domain(domain) => Host::Domain(Cow::Owned(domain.into_owned())),
+            Host::Ipv4(addr) => Host::Ipv4(addr),
+            Host::Ipv6(addr) => Host::Ipv6(addr),
+        }
+    }
+}
+
+impl<'a> From<IpAddr> for Host<'a> {
+    fn from(addr: IpAddr) -> Host<'a> {
+        match addr {
+            IpAddr::V4(addr) => Host::Ipv4(addr),
+            IpAddr::V6(addr) => Host::Ipv6(addr),
+        }
+    }
+}
+
+/// Writes host without brackets of IPv6reference
+impl<'a> fmt::Display for Host<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Host::Domain(domain) => f.write_str(domain),
+            Host::Ipv4(addr) => write!(f, "{}", addr),
+            Host::Ipv6(addr) => write!(f, "{}", addr),
+        }
+    }
+}
+
+/// Host is equal to string if it has the same textual form or the same address
+impl<'a> PartialEq<str> for Host<'a> {
+    fn eq(&self, other: &str) -> bool {
+        match self {
+            Host::Domain(domain) => domain == other,
+            _ => matches!(Host::from_str(other), Some(host) if host == *self),
+        }
+    }
+}
+
+impl<'a, 'b> PartialEq<&'b str> for Host<'a> {
+    fn eq(&self, other: &&'b str) -> bool {
+        *self == **other
+    }
+}
+
 // hostport         =  host [ ":" port ]
-#[derive(PartialEq, Debug)]
+#[derive(Clone, PartialEq, Debug)]
 pub struct HostPort<'a> {
-    pub host: &'a str, // hostname / IPv4address / IPv6reference
+    pub host: Host<'a>,
     pub port: Option<u16>,
 }
 
@@ -18,7 +158,21 @@ fn host_char_allowed(c: u8) -> bool {
 }
 
 impl<'a> HostPort<'a> {
-    pub fn take_ipv6_host(input: &'a [u8]) -> nom::IResult<&[u8], &[u8], SipParseError> {
+    pub fn into_owned(self) -> HostPort<'static> {
+        HostPort {
+            host: self.host.into_owned(),
+            port: self.port,
+        }
+    }
+
+    /// Socket address of IPv4 or IPv6 host, `default_port` is used if port is absent.
+    /// `None` for domain name
+    pub fn socket_addr(&self, default_port: u16) -> Option<SocketAddr> {
+        let ip = self.host.ip_addr()?;
+        Some(SocketAddr::new(ip, self.port.unwrap_or(default_port)))
+    }
+
+    pub fn take_ipv6_host(input: &'a [u8]) -> nom::IResult<&'a [u8], &'a [u8], SipParseError<'a>> {
         let (input, _) = take(1usize)(input)?; // skip '['
         let (input, ipv6_host) = take_until("]")(input)?;
         let (input, _) = take(1usize)(input)?; // skip ']'
@@ -27,14 +181,14 @@ impl<'a> HostPort<'a> {
 
     pub fn take_hostport(
         input: &'a [u8],
-    ) -> nom::IResult<&[u8], (&[u8], Option<&[u8]>), SipParseError> {
+    ) -> nom::IResult<&'a [u8], (&'a [u8], Option<&'a [u8]>), SipParseError<'a>> {
         let (input, host) = if input[0] != b'[' {
             take_while1(host_char_allowed)(input)?
         } else {
             HostPort::take_ipv6_host(input)?
         };
 
-        let (input, port) = if input.len() > 3 && input[0] == b':' {
+        let (input, port) = if !input.is_empty() && input[0] == b':' {
             let (input, port) = take_while1(is_digit)(&input[1..])?;
             (input, Some(port))
         } else {
@@ -44,43 +198,47 @@ impl<'a> HostPort<'a> {
         Ok((input, (host, port)))
     }
 
-    pub fn parse(input: &'a [u8]) -> nom::IResult<&[u8], HostPort<'a>, SipParseError> {
+    pub fn parse(input: &'a [u8]) -> nom::IResult<&'a [u8], HostPort<'a>, SipParseError<'a>> {
         if input.is_empty() {
-            return sip_parse_error!(1);
+            return sip_parse_error!(InvalidHostPort, "Host is empty");
         }
 
         let (rest, (host, port)) = HostPort::take_hostport(input)?;
+        let (_, host_str) = from_utf8_nom(host)?;
+        let host = match Host::from_str(host_str) {
+            Some(host) => host,
+            None => return sip_parse_error!(InvalidHostPort, "Invalid host"),
+        };
+
+        let port = match port {
+            Some(port) => match str::from_utf8(port).ok().and_then(|p| p.parse().ok()) {
+                Some(port) => Some(port),
+                None => return sip_parse_error!(InvalidHostPort, "Invalid port"),
+            },
+            None => None,
+        };
+        Ok((rest, HostPort { host, port }))
+    }
+}
 
-        if port == None {
-            let (_, host_str) = from_utf8_nom(host)?;
-            return Ok((
-                rest,
-                HostPort {
-                    host: host_str,
-                    port: None,
-                },
-            ));
+impl<'a> From<SocketAddr> for HostPort<'a> {
+    fn from(addr: SocketAddr) -> HostPort<'a> {
+        HostPort {
+            host: Host::from(addr.ip()),
+            port: Some(addr.port()),
         }
+    }
+}
 
-        match str::from_utf8(port.unwrap()) {
-            Ok(port_str) => match u16::from_str_radix(port_str, 10) {
-                Ok(port) => {
-                    let (_, host_str) = from_utf8_nom(host)?;
-                    return Ok((
-                        rest,
-                        HostPort {
-                            host: host_str,
-                            port: Some(port),
-                        },
-                    ));
-                }
-                Err(_) => {
-                    return sip_parse_error!(2);
-                }
-            },
-            Err(_) => {
-                return sip_parse_error!(3, "Convert bytes to utf8 is failed");
-            }
+impl<'a> fmt::Display for HostPort<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.host {
+            Host::Ipv6(_) => write!(f, "[{}]", self.host)?,
+            _ => write!(f, "{}", self.host)?,
+        }
+        match self.port {
+            Some(port) => write!(f, ":{}", port),
+            None => Ok(()),
         }
     }
 }
@@ -88,6 +246,7 @@ impl<'a> HostPort<'a> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use alloc::string::ToString;
 
     fn host_port_test_case(
         input: &str,
@@ -139,4 +298,59 @@ mod tests {
             ";transport=tcp",
         );
     }
+
+    #[test]
+    fn host_parse_port_at_end() {
+        host_port_test_case("a.com:80", "a.com", Some(80), "");
+        host_port_test_case("a:5", "a", Some(5), "");
+        host_port_test_case("[::1]:5", "::1", Some(5), "");
+        assert!(HostPort::parse(b"a.com:").is_err());
+        assert!(HostPort::parse(b"a.com:65536").is_err());
+    }
+
+    #[test]
+    fn host_typed() {
+        let (_, hostport) = HostPort::parse(b"192.0.2.4:5061").unwrap();
+        assert_eq!(hostport.host, Host::Ipv4(Ipv4Addr::new(192, 0, 2, 4)));
+        assert_eq!(
+            hostport.socket_addr(5060),
+            Some(SocketAddr::new(
+                IpAddr::V4(Ipv4Addr::new(192, 0, 2, 4)),
+                5061
+            ))
+        );
+
+        let (_, hostport) = HostPort::parse(b"[2001:DB8:0::10]").unwrap();
+        assert_eq!(
+            hostport.host,
+            Host::Ipv6(Ipv6Addr::new(0x2001, 0xdb8, 0, 0, 0, 0, 0, 0x10))
+        );
+        assert_eq!(hostport.host, "2001:db8::10");
+        assert_eq!(hostport.socket_addr(5060).unwrap().port(), 5060);
+        assert_eq!(hostport.to_string(), "[2001:db8::10]");
+
+        let (_, hostport) = HostPort::parse(b"Atlanta.COM.").unwrap();
+        assert!(hostport.host.is_domain());
+        assert_eq!(hostport.host.ip_addr(), None);
+        assert_eq!(hostport.socket_addr(5060), None);
+        assert!(hostport
+            .host
+            .equivalent(&Host::Domain(Cow::Borrowed("atlanta.com."))));
+
+        assert_eq!(
+            Host::from_str("010.000.0.1"),
+            Some(Host::Ipv4(Ipv4Addr::new(10, 0, 0, 1)))
+        );
+        assert_eq!(Host::from_str("1.2.3.256"), None);
+        assert_eq!(Host::from_str("1.2.3"), None);
+        assert_eq!(Host::from_str("example.123"), None);
+        assert_eq!(Host::from_str("a-.com"), None);
+        assert_eq!(Host::from_str("a..com"), None);
+        assert_eq!(Host::from_str("[::1]"), None);
+        assert!(HostPort::parse(b"-a.com").is_err());
+        assert!(HostPort::parse(b"[::g]").is_err());
+
+        let hostport = HostPort::from(SocketAddr::new(IpAddr::V6(Ipv6Addr::LOCALHOST), 5060));
+        assert_eq!(hostport.to_string(), "[::1]:5060");
+    }
 }
@@ -5,6 +5,7 @@ pub mod hostport;
 pub mod nom_wrappers;
 
 pub mod bnfcore;
+pub mod escape;
 pub mod take_sws_token;
 
 pub mod sip_method;
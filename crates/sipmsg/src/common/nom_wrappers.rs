@@ -47,7 +47,7 @@ fn take_until_nonescaped_quote(
         idx += 1;
     }
 
-    sip_parse_error!(1, "take_until_nonescaped_quote error!")
+    sip_parse_error!(InvalidQuotedString, "Closing quote is not found")
 }
 
 pub fn take_quoted_string(
@@ -62,7 +62,7 @@ pub fn take_quoted_string(
 /// LWS  =  [*WSP CRLF] 1*WSP ; linear whitespace
 pub fn take_lws(source_input: &[u8]) -> nom::IResult<&[u8], &[u8], SipParseError> {
     if source_input.is_empty() || (!is_wsp(source_input[0]) && !is_cr(source_input[0])) {
-        return sip_parse_error!(1, "take_lws failed");
+        return sip_parse_error!(InvalidHeaderValue, "Linear whitespace is expected");
     }
     take_sws(source_input)
 }
@@ -104,7 +104,7 @@ pub fn take_while_trim_sws(
 pub fn from_utf8_nom(v: &[u8]) -> nom::IResult<&str, &str, SipParseError> {
     match from_utf8(v) {
         Ok(res_str) => Ok(("", res_str)),
-        Err(_) => sip_parse_error!(1, "Error: from_utf8_nom failed"),
+        Err(_) => sip_parse_error!(InvalidUtf8),
     }
 }
 
@@ -1,6 +1,8 @@
-use unicase::Ascii;
-#[derive(Copy, Clone, PartialEq, Debug)]
-pub enum SipMethod {
+use crate::common::bnfcore::is_token_char;
+use alloc::borrow::Cow;
+use core::fmt;
+#[derive(Clone, PartialEq, Debug)]
+pub enum SipMethod<'a> {
     ACK,
     BYE,
     CANCEL,
@@ -15,9 +17,23 @@ pub enum SipMethod {
     REGISTER,
     SUBSCRIBE,
     UPDATE,
+    /// Method that is not listed above, e.g. vendor `PING`.
+    /// Created by [`SipMethod::extension`] or [`SipMethod::from_str`]
+    Extension(ExtensionMethod<'a>),
 }
 
-impl SipMethod {
+/// Name of extension method, contains token characters only
+/// and is never one of the methods listed in [`SipMethod`]
+#[derive(Clone, PartialEq, Debug)]
+pub struct ExtensionMethod<'a>(Cow<'a, str>);
+
+impl<'a> ExtensionMethod<'a> {
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl<'a> SipMethod<'a> {
     pub fn as_str(&self) -> &str {
         match self {
             &SipMethod::ACK => "ACK",
@@ -34,32 +50,76 @@ impl SipMethod {
             &SipMethod::REGISTER => "REGISTER",
             &SipMethod::SUBSCRIBE => "SUBSCRIBE",
             &SipMethod::UPDATE => "UPDATE",
+            SipMethod::Extension(method) => method.as_str(),
+        }
+    }
+
+    /// Returns `SipMethod::Extension` for unknown method
+    /// and None if `s` is not a token.
+    /// Method is case-sensitive
+    /// ([rfc3261 section-7.1](https://tools.ietf.org/html/rfc3261#section-7.1)),
+    /// so `invite` is an extension method
+    pub fn from_str(s: &'a str) -> Option<SipMethod<'a>> {
+        SipMethod::extension(s)
+    }
+
+    /// Same as [`SipMethod::from_str`]: known method name gives its own variant,
+    /// other token gives `SipMethod::Extension`
+    pub fn extension(method: &'a str) -> Option<SipMethod<'a>> {
+        if let Some(known) = SipMethod::known(method) {
+            return Some(known);
+        }
+        if method.is_empty() || !method.bytes().all(is_token_char) {
+            return None;
         }
+        Some(SipMethod::Extension(ExtensionMethod(Cow::Borrowed(method))))
     }
 
-    pub fn from_str(s: &str) -> Option<SipMethod> {
-        let s = Ascii::new(s);
-        macro_rules! match_str {
-            ($input_str:expr, $enum_result:expr) => {
-                if s == $input_str {
-                    return Some($enum_result);
-                }
-            };
+    fn known(s: &str) -> Option<SipMethod<'static>> {
+        match s {
+            "ACK" => Some(SipMethod::ACK),
+            "BYE" => Some(SipMethod::BYE),
+            "CANCEL" => Some(SipMethod::CANCEL),
+            "INFO" => Some(SipMethod::INFO),
+            "INVITE" => Some(SipMethod::INVITE),
+            "MESSAGE" => Some(SipMethod::MESSAGE),
+            "NOTIFY" => Some(SipMethod::NOTIFY),
+            "OPTIONS" => Some(SipMethod::OPTIONS),
+            "PRACK" => Some(SipMethod::PRACK),
+            "PUBLISH" => Some(SipMethod::PUBLISH),
+            "REFER" => Some(SipMethod::REFER),
+            "REGISTER" => Some(SipMethod::REGISTER),
+            "SUBSCRIBE" => Some(SipMethod::SUBSCRIBE),
+            "UPDATE" => Some(SipMethod::UPDATE),
+            _ => None,
         }
-        match_str!("ACK", SipMethod::ACK);
-        match_str!("BYE", SipMethod::BYE);
-        match_str!("CANCEL", SipMethod::CANCEL);
-        match_str!("INFO", SipMethod::INFO);
-        match_str!("INVITE", SipMethod::INVITE);
-        match_str!("MESSAGE", SipMethod::MESSAGE);
-        match_str!("NOTIFY", SipMethod::NOTIFY);
-        match_str!("OPTIONS", SipMethod::OPTIONS);
-        match_str!("PRACK", SipMethod::PRACK);
-        match_str!("PUBLISH", SipMethod::PUBLISH);
-        match_str!("REFER", SipMethod::REFER);
-        match_str!("REGISTER", SipMethod::REGISTER);
-        match_str!("SUBSCRIBE", SipMethod::SUBSCRIBE);
-        match_str!("UPDATE", SipMethod::UPDATE);
-        None
+    }
+
+    pub fn into_owned(self) -> SipMethod<'static> {
+        match self {
+            SipMethod::ACK => SipMethod::ACK,
+            SipMethod::BYE => SipMethod::BYE,
+            SipMethod::CANCEL => SipMethod::CANCEL,
+            SipMethod::INFO => SipMethod::INFO,
+            SipMethod::INVITE => SipMethod::INVITE,
+            SipMethod::MESSAGE => SipMethod::MESSAGE,
+            SipMethod::NOTIFY => SipMethod::NOTIFY,
+            SipMethod::OPTIONS => SipMethod::OPTIONS,
+            SipMethod::PRACK => SipMethod::PRACK,
+            SipMethod::PUBLISH => SipMethod::PUBLISH,
+            SipMethod::REFER => SipMethod::REFER,
+            SipMethod::REGISTER => SipMethod::REGISTER,
+            SipMethod::SUBSCRIBE => SipMethod::SUBSCRIBE,
+            SipMethod::UPDATE => SipMethod::UPDATE,
+            SipMethod::Extension(method) => {
+                SipMethod::Extension(ExtensionMethod(Cow::Owned(method.0.into_owned())))
+            }
+        }
+    }
+}
+
+impl<'a> fmt::Display for SipMethod<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.as_str())
     }
 }
@@ -0,0 +1,220 @@
+use crate::common::{
+    bnfcore::{is_alpha, is_alphanum, is_hexdig},
+    errorparse::SipParseError,
+    nom_wrappers::from_utf8_nom,
+};
+use alloc::borrow::Cow;
+use core::fmt;
+
+/// scheme = ALPHA *( ALPHA / DIGIT / "+" / "-" / "." )
+#[inline]
+pub(crate) fn is_scheme_char(c: u8) -> bool {
+    is_alphanum(c) || c == b'+' || c == b'-' || c == b'.'
+}
+
+/// unreserved / reserved / "%" of rfc3986
+#[inline]
+fn is_uri_char(c: u8) -> bool {
+    is_alphanum(c)
+        || b"-._~".contains(&c)
+        || b":/?#[]@".contains(&c)
+        || b"!$&'()*+,;=".contains(&c)
+        || c == b'%'
+}
+
+/// Returns length of scheme if input starts with `scheme ":"`
+pub(crate) fn scheme_len(input: &[u8]) -> Option<usize> {
+    if input.is_empty() || !is_alpha(input[0]) {
+        return None;
+    }
+    let len = input.iter().take_while(|c| is_scheme_char(**c)).count();
+    if input.get(len) == Some(&b':') {
+        Some(len)
+    } else {
+        None
+    }
+}
+
+/// URI in generic form of [rfc3986 section-3](https://tools.ietf.org/html/rfc3986#section-3):
+/// `scheme ":" ["//" authority] path ["?" query] ["#" fragment]`.
+/// Components are not unescaped.
+///
+/// ```rust
+/// use sipmsg::AbsoluteUri;
+///
+/// let (_, uri) = AbsoluteUri::parse(b"http://user@example.com:8080/a/b?x=1#top").unwrap();
+/// assert_eq!(uri.scheme, "http");
+/// assert_eq!(uri.authority.as_deref(), Some("user@example.com:8080"));
+/// assert_eq!(uri.host(), Some("example.com"));
+/// assert_eq!(uri.port(), Some(8080));
+/// assert_eq!(uri.path, "/a/b");
+/// assert_eq!(uri.query.as_deref(), Some("x=1"));
+/// assert_eq!(uri.fragment.as_deref(), Some("top"));
+/// ```
+#[derive(Clone, PartialEq, Debug)]
+pub struct AbsoluteUri<'a> {
+    pub scheme: Cow<'a, str>,
+    pub authority: Option<Cow<'a, str>>,
+    pub path: Cow<'a, str>,
+    pub query: Option<Cow<'a, str>>,
+    pub fragment: Option<Cow<'a, str>>,
+}
+
+impl<'a> AbsoluteUri<'a> {
+    /// Host of authority without userinfo, port and brackets of IPv6 reference
+    pub fn host(&self) -> Option<&str> {
+        let authority = self.authority.as_deref()?;
+        let host_port = match authority.rfind('@') {
+            Some(pos) => &authority[pos + 1..],
+            None => authority,
+        };
+        if let Some(ipv6) = host_port.strip_prefix('[') {
+            return ipv6.split(']').next();
+        }
+        host_port.split(':').next()
+    }
+
+    /// Port of authority
+    pub fn port(&self) -> Option<u16> {
+        let authority = self.authority.as_deref()?;
+        let host_port = match authority.rfind('@') {
+            Some(pos) => &authority[pos + 1..],
+            None => authority,
+        };
+        let port_pos = match host_port.rfind(']') {
+            Some(pos) => host_port[pos..].find(':')? + pos,
+            None => host_port.find(':')?,
+        };
+        host_port[port_pos + 1..].parse().ok()
+    }
+
+    pub fn into_owned(self) -> AbsoluteUri<'static> {
+        AbsoluteUri {
+            scheme: Cow::Owned(self.scheme.into_owned()),
+            authority: self.authority.map(|a| Cow::Owned(a.into_owned())),
+            path: Cow::Owned(self.path.into_owned()),
+            query: self.query.map(|q| Cow::Owned(q.into_owned())),
+            fragment: self.fragment.map(|f| Cow::Owned(f.into_owned())),
+        }
+    }
+
+    /// URI ends on the first character that is not allowed by rfc3986 (`>`, SP, CRLF...).
+    /// If `parse_with_parameters` is false it also ends on `;` and `,`,
+    /// it is needed for name-addr without "<>" where parameters belong to header
+    pub fn parse_ext(
+        input: &'a [u8],
+        parse_with_parameters: bool,
+    ) -> nom::IResult<&'a [u8], AbsoluteUri<'a>, SipParseError<'a>> {
+        let scheme_len = match scheme_len(input) {
+            Some(len) => len,
+            None => return sip_parse_error!(InvalidUri, "Invalid URI scheme"),
+        };
+        let uri_len = input[scheme_len + 1..]
+            .iter()
+            .take_while(|c| is_uri_char(**c) && (parse_with_parameters || !b";,".contains(*c)))
+            .count();
+        let rest = &input[scheme_len + 1 + uri_len..];
+        let (_, scheme) = from_utf8_nom(&input[..scheme_len])?;
+        let (_, uri) = from_utf8_nom(&input[scheme_len + 1..scheme_len + 1 + uri_len])?;
+
+        let bytes = uri.as_bytes();
+        for (i, c) in bytes.iter().enumerate() {
+            if *c == b'%'
+                && (i + 2 >= bytes.len() || !is_hexdig(bytes[i + 1]) || !is_hexdig(bytes[i + 2]))
+            {
+                return sip_parse_error!(InvalidUri, "Invalid percent-encoding in URI");
+            }
+        }
+
+        let (uri, fragment) = match uri.find('#') {
+            Some(pos) => (&uri[..pos], Some(Cow::Borrowed(&uri[pos + 1..]))),
+            None => (uri, None),
+        };
+        let (hier_part, query) = match uri.find('?') {
+            Some(pos) => (&uri[..pos], Some(Cow::Borrowed(&uri[pos + 1..]))),
+            None => (uri, None),
+        };
+        let (authority, path) = if let Some(hier_part) = hier_part.strip_prefix("//") {
+            let path_pos = hier_part.find('/').unwrap_or(hier_part.len());
+            (
+                Some(Cow::Borrowed(&hier_part[..path_pos])),
+                &hier_part[path_pos..],
+            )
+        } else {
+            (None, hier_part)
+        };
+
+        Ok((
+            rest,
+            AbsoluteUri {
+                scheme: Cow::Borrowed(scheme),
+                authority,
+                path: Cow::Borrowed(path),
+                query,
+                fragment,
+            },
+        ))
+    }
+
+    pub fn parse(input: &'a [u8]) -> nom::IResult<&'a [u8], AbsoluteUri<'a>, SipParseError<'a>> {
+        AbsoluteUri::parse_ext(input, true)
+    }
+}
+
+impl<'a> fmt::Display for AbsoluteUri<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}:", self.scheme)?;
+        if let Some(authority) = &self.authority {
+            write!(f, "//{}", authority)?;
+        }
+        write!(f, "{}", self.path)?;
+        if let Some(query) = &self.query {
+            write!(f, "?{}", query)?;
+        }
+        if let Some(fragment) = &self.fragment {
+            write!(f, "#{}", fragment)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::ToString;
+
+    #[test]
+    fn absolute_uri_parse() {
+        let (rest, uri) = AbsoluteUri::parse(b"urn:service:sos>;tag=1").unwrap();
+        assert_eq!(rest, b">;tag=1");
+        assert_eq!(uri.scheme, "urn");
+        assert_eq!(uri.authority, None);
+        assert_eq!(uri.path, "service:sos");
+        assert_eq!(uri.host(), None);
+
+        let (rest, uri) = AbsoluteUri::parse_ext(b"mailto:alice@atlanta.com;tag=1", false).unwrap();
+        assert_eq!(rest, b";tag=1");
+        assert_eq!(uri.path, "alice@atlanta.com");
+
+        let (rest, uri) = AbsoluteUri::parse(b"http://[2001:db8::1]:80/x%20y?a=b;c \r\n").unwrap();
+        assert_eq!(rest, b" \r\n");
+        assert_eq!(uri.host(), Some("2001:db8::1"));
+        assert_eq!(uri.port(), Some(80));
+        assert_eq!(uri.path, "/x%20y");
+        assert_eq!(uri.query.as_deref(), Some("a=b;c"));
+        assert_eq!(uri.to_string(), "http://[2001:db8::1]:80/x%20y?a=b;c");
+
+        let (_, uri) = AbsoluteUri::parse(b"http://example.com").unwrap();
+        assert_eq!(uri.host(), Some("example.com"));
+        assert_eq!(uri.port(), None);
+        assert_eq!(uri.path, "");
+    }
+
+    #[test]
+    fn absolute_uri_parse_fail() {
+        assert!(AbsoluteUri::parse(b"1http://example.com").is_err());
+        assert!(AbsoluteUri::parse(b"example.com").is_err());
+        assert!(AbsoluteUri::parse(b"http://example.com/%2").is_err());
+        assert!(AbsoluteUri::parse(b"http://example.com/%zz").is_err());
+    }
+}
@@ -1,11 +1,17 @@
 use crate::common::nom_wrappers::take_sws;
 use crate::headers::header::HeaderTags;
 use crate::{
-    common::{bnfcore::is_token_char, errorparse::SipParseError, nom_wrappers, take_sws_token},
+    common::{
+        bnfcore::{is_alphanum, is_crlf, is_token_char},
+        errorparse::SipParseError,
+        nom_wrappers, take_sws_token,
+    },
     headers::header::HeaderTagType,
 };
+use alloc::borrow::Cow;
 use alloc::str::from_utf8;
-use nom::bytes::complete::take_while1;
+use alloc::vec::Vec;
+use nom::bytes::complete::{take_while, take_while1};
 use unicase::Ascii;
 
 pub fn param_name_to_tag(value: &[u8]) -> Option<HeaderTagType> {
@@ -34,72 +40,207 @@ pub fn param_name_to_tag(value: &[u8]) -> Option<HeaderTagType> {
     None
 }
 
-pub fn take<'a>(
+/// token68 = 1*( ALPHA / DIGIT / "-" / "." / "_" / "~" / "+" / "/" ) *"="
+#[inline]
+fn is_token68_char(c: u8) -> bool {
+    is_alphanum(c) || b"-._~+/".contains(&c)
+}
+
+/// auth-param of credentials or challenge
+pub(crate) struct AuthParam<'a> {
+    pub name: &'a [u8],
+    /// Value without quotes, quoted-pairs are not resolved
+    pub value: &'a [u8],
+    pub quoted: bool,
+}
+
+/// `auth-scheme [ 1*SP ( token68 / #auth-param ) ]` of
+/// [rfc7235](https://tools.ietf.org/html/rfc7235#section-2.1)
+pub(crate) struct AuthValue<'a> {
+    pub scheme: &'a [u8],
+    pub token68: Option<&'a [u8]>,
+    pub params: Vec<AuthParam<'a>>,
+}
+
+fn take_token68<'a>(input: &'a [u8]) -> nom::IResult<&'a [u8], &'a [u8], SipParseError<'a>> {
+    let (rest, _) = take_while1(is_token68_char)(input)?;
+    let (rest, _) = take_while(|c| c == b'=')(rest)?;
+    Ok((rest, &input[..input.len() - rest.len()]))
+}
+
+/// auth-param = token EQUAL ( token / quoted-string ).
+/// Returns param and count of whitespaces taken after closing quote
+fn take_param<'a>(
+    input: &'a [u8],
+) -> nom::IResult<&'a [u8], (AuthParam<'a>, usize), SipParseError<'a>> {
+    let (input, name) = take_while1(is_token_char)(input)?;
+    let (input, _) = take_sws_token::equal(input)?;
+    if input.first() == Some(&b'"') {
+        let (input, (_, value, wsps)) = nom_wrappers::take_quoted_string(input)?;
+        let param = AuthParam {
+            name,
+            value,
+            quoted: true,
+        };
+        return Ok((input, (param, wsps.len())));
+    }
+    let (input, value) = take_while1(is_token_char)(input)?;
+    let param = AuthParam {
+        name,
+        value,
+        quoted: false,
+    };
+    Ok((input, (param, 0)))
+}
+
+/// Returns `true` if input starts with auth-scheme of the next challenge, not with auth-param
+fn is_next_scheme(input: &[u8]) -> bool {
+    let name_len = input.iter().take_while(|c| is_token_char(**c)).count();
+    if name_len == 0 {
+        return false;
+    }
+    match take_sws(&input[name_len..]) {
+        Ok((rest, _)) => rest.first() != Some(&b'='),
+        Err(_) => true,
+    }
+}
+
+/// Takes credentials or challenge. Parameters of challenge end before comma
+/// that is followed by auth-scheme of the next challenge
+pub(crate) fn take_auth_value<'a>(
     source_input: &'a [u8],
-) -> nom::IResult<&[u8], (&[u8] /*vstr*/, HeaderTags<'a>), SipParseError> {
-    let (input, auth_schema) = take_while1(is_token_char)(source_input)?;
-    let mut tags = HeaderTags::new();
-    tags.insert(HeaderTagType::AuthSchema, auth_schema);
-    let (input, _) = take_sws(input)?; // LWS
-    let mut input_tmp = input;
-    // I use this value in end of fucntion. But compiler throw warning:
-    // "value assigned to `count_wsps_after_last_value` is never read"
-    // So, lets name it _* do supress warning
-    let mut _count_wsps_after_last_value = 0;
+    is_challenge: bool,
+) -> nom::IResult<&'a [u8], (&'a [u8] /*vstr*/, AuthValue<'a>), SipParseError<'a>> {
+    let (input, scheme) = take_while1(is_token_char)(source_input)?;
+    let mut value = AuthValue {
+        scheme,
+        token68: None,
+        params: Vec::new(),
+    };
+    let (after_lws, _) = take_sws(input)?; // LWS
+    if after_lws.len() == input.len()
+        || after_lws.is_empty()
+        || after_lws[0] == b','
+        || is_crlf(after_lws)
+    {
+        // auth-scheme without token68 and parameters
+        return Ok((input, (scheme, value)));
+    }
+
+    if take_param(after_lws).is_err() {
+        let (input, token68) = take_token68(after_lws)?;
+        value.token68 = Some(token68);
+        let hdr_len = source_input.len() - input.len();
+        return Ok((input, (&source_input[..hdr_len], value)));
+    }
+
+    let mut input_tmp = after_lws;
+    let mut hdr_len;
     loop {
-        let (input, param_name) = take_while1(is_token_char)(input_tmp)?;
-        let (input, _) = take_sws_token::equal(input)?;
-
-        let (input, (param_name, param_value)) = if input[0] == b'"' {
-            let (input, (_, param_value, wsps)) = nom_wrappers::take_quoted_string(input)?;
-            _count_wsps_after_last_value = wsps.len();
-            (input, (param_name, param_value))
-        } else {
-            let (input, param_value) = take_while1(is_token_char)(input)?;
-            _count_wsps_after_last_value = 0;
-            (input, (param_name, param_value))
-        };
+        let (input, (param, count_wsps_after_value)) = take_param(input_tmp)?;
+        value.params.push(param);
+        hdr_len = source_input.len() - input.len() - count_wsps_after_value;
+        input_tmp = input;
 
-        let tag_type = param_name_to_tag(param_name);
-        if !tag_type.is_none() {
-            let tt = tag_type.unwrap();
-            if tt == HeaderTagType::NonceCount {
-                if param_value.len() != 8 {
-                    return sip_parse_error!(2, "Invalid nonce len");
-                }
-            }
-            tags.insert(tt, param_value);
+        let (before_comma, _) = take_sws(input)?;
+        if before_comma.first() != Some(&b',') {
+            break;
+        }
+        let (input, _) = take_sws_token::comma(before_comma)?;
+        if is_challenge && is_next_scheme(input) {
+            input_tmp = before_comma;
+            break;
         }
         input_tmp = input;
+    }
+    Ok((input_tmp, (&source_input[..hdr_len], value)))
+}
 
-        if !input.is_empty() && input[0] == b',' {
-            let (input, _) = take_sws_token::comma(input)?;
-            input_tmp = input;
-        } else {
-            break;
+fn take_tags<'a>(
+    source_input: &'a [u8],
+    is_challenge: bool,
+) -> nom::IResult<&'a [u8], (&'a [u8] /*vstr*/, HeaderTags<'a>), SipParseError<'a>> {
+    let (input, (vstr, value)) = take_auth_value(source_input, is_challenge)?;
+    let mut tags = HeaderTags::new();
+    tags.insert(HeaderTagType::AuthSchema, Cow::Borrowed(value.scheme));
+    if let Some(token68) = value.token68 {
+        tags.insert(HeaderTagType::Token68, Cow::Borrowed(token68));
+    }
+    for param in value.params.iter() {
+        if let Some(tt) = param_name_to_tag(param.name) {
+            if tt == HeaderTagType::NonceCount && param.value.len() != 8 {
+                return sip_parse_error!(InvalidHeaderValue, "Invalid nonce len");
+            }
+            tags.insert(tt, Cow::Borrowed(param.value));
         }
     }
-    let hdr_len = source_input.len() - input_tmp.len() - _count_wsps_after_last_value;
-    Ok((input_tmp, (&source_input[..hdr_len], tags)))
+    Ok((input, (vstr, tags)))
+}
+
+/// Takes credentials of Authorization and Proxy-Authorization
+pub fn take<'a>(
+    source_input: &'a [u8],
+) -> nom::IResult<&'a [u8], (&'a [u8] /*vstr*/, HeaderTags<'a>), SipParseError<'a>> {
+    take_tags(source_input, false)
 }
 
-// fn take_param(
-//     input: &[u8],
-// ) -> nom::IResult<
-//     &[u8],
-//     (
-//         &[u8], /* param name */
-//         &[u8], /* param_value without qoutes */
-//     ),
-//     SipParseError,
-// > {
-//     let (input, param_name) = take_while1(is_token_char)(input)?;
-//     let (input, _) = take_sws_token::equal(input)?;
-//     if input[0] == b'"' {
-//         let (input, (_, param_value, _)) = nom_wrappers::take_quoted_string(input)?;
-//         return Ok((input, (param_name, param_value)));
-//     } else {
-//         let (input, param_value) = take_while1(is_token_char)(input)?;
-//         return Ok((input, (param_name, param_value)));
-//     }
-// }
+/// Takes one challenge of WWW-Authenticate and Proxy-Authenticate
+pub fn take_challenge<'a>(
+    source_input: &'a [u8],
+) -> nom::IResult<&'a [u8], (&'a [u8] /*vstr*/, HeaderTags<'a>), SipParseError<'a>> {
+    take_tags(source_input, true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn take_auth_value_token68() {
+        let (input, (vstr, value)) =
+            take_auth_value(b"Basic QWxhZGRpbjpvcGVuIHNlc2FtZQ==\r\n", false).unwrap();
+        assert_eq!(input, b"\r\n");
+        assert_eq!(vstr, b"Basic QWxhZGRpbjpvcGVuIHNlc2FtZQ==");
+        assert_eq!(value.scheme, b"Basic");
+        assert_eq!(value.token68, Some(&b"QWxhZGRpbjpvcGVuIHNlc2FtZQ=="[..]));
+        assert!(value.params.is_empty());
+
+        let (input, (vstr, value)) = take_auth_value(b"Bearer\r\n", true).unwrap();
+        assert_eq!(input, b"\r\n");
+        assert_eq!(vstr, b"Bearer");
+        assert_eq!(value.token68, None);
+    }
+
+    #[test]
+    fn take_auth_value_challenges() {
+        let input = b"Digest realm=\"a.com\", qop=\"auth,auth-int\" ,\r\n Bearer realm=\"b\", \
+                      scope=\"x y\", Basic realm=\"c\"\r\n";
+        let (input, (vstr, value)) = take_auth_value(input, true).unwrap();
+        assert_eq!(vstr, b"Digest realm=\"a.com\", qop=\"auth,auth-int\"");
+        assert_eq!(value.params.len(), 2);
+        assert_eq!(value.params[1].name, b"qop");
+        assert_eq!(value.params[1].value, b"auth,auth-int");
+        assert!(value.params[1].quoted);
+
+        let (input, _) = take_sws_token::comma(input).unwrap();
+        let (input, (vstr, value)) = take_auth_value(input, true).unwrap();
+        assert_eq!(vstr, b"Bearer realm=\"b\", scope=\"x y\"");
+        assert_eq!(value.params[1].value, b"x y");
+
+        let (input, _) = take_sws_token::comma(input).unwrap();
+        let (input, (vstr, _)) = take_auth_value(input, true).unwrap();
+        assert_eq!(vstr, b"Basic realm=\"c\"");
+        assert_eq!(input, b"\r\n");
+    }
+
+    #[test]
+    fn take_auth_value_keeps_unknown_params() {
+        let (_, (_, value)) =
+            take_auth_value(b"Digest realm=x, foo=bar, Other=\"y\"\r\n", false).unwrap();
+        assert_eq!(value.params.len(), 3);
+        assert_eq!(value.params[1].name, b"foo");
+        assert!(!value.params[1].quoted);
+        assert_eq!(value.params[2].name, b"Other");
+        assert_eq!(value.params[2].value, b"y");
+    }
+}
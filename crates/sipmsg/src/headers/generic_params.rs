@@ -1,100 +1,358 @@
 use crate::common::{
     bnfcore::is_token_char,
-    errorparse::SipParseError,
+    errorparse::{ParseErrorKind, SipParseError},
+    escape::{unescape_str, unquote},
     hostport::HostPort,
     nom_wrappers::{from_utf8_nom, take_quoted_string, take_sws, take_while_trim_sws},
     take_sws_token,
 };
-use alloc::collections::btree_map::{BTreeMap, Keys};
+use alloc::{borrow::Cow, string::String, vec::Vec};
+use core::{fmt, net::Ipv6Addr, slice::Iter};
 use nom::{bytes::complete::take_while, multi::many0};
 use unicase::Ascii;
 
+/// Form of parameter value
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum ParamValueForm {
+    Token,
+    /// quoted-string, value is stored without quotes and with escaped pairs as is
+    Quoted,
+    /// IPv6reference, value is stored without brackets
+    Ipv6Reference,
+}
+
+impl ParamValueForm {
+    /// Form that is needed to write value and value to store.
+    /// Only bracketed IPv6 address is IPv6reference, brackets are not stored
+    fn of(value: &str) -> (&str, ParamValueForm) {
+        if !value.is_empty() && value.bytes().all(is_token_char) {
+            return (value, ParamValueForm::Token);
+        }
+        if let Some(address) = value
+            .strip_prefix('[')
+            .and_then(|value| value.strip_suffix(']'))
+        {
+            if address.parse::<Ipv6Addr>().is_ok() {
+                return (address, ParamValueForm::Ipv6Reference);
+            }
+        }
+        (value, ParamValueForm::Quoted)
+    }
+}
+
+/// generic-param = token [ EQUAL gen-value ]
+#[derive(Clone, PartialEq, Debug)]
 pub struct GenericParam<'a> {
-    /// Parameter name
-    pub name: Ascii<&'a str>,
-    /// Param value, without quotes and "[" if it ipv6
-    pub value: Option<&'a str>,
-    // TODO add raw representation param
-    // It needs at least to dump quoted params as is
+    name: Ascii<Cow<'a, str>>,
+    value: Option<(Cow<'a, str>, ParamValueForm)>,
+    /// Parameter as it is in the input, without leading ";".
+    /// `None` if parameter was created or changed
+    raw: Option<Cow<'a, str>>,
 }
 
 impl<'a> GenericParam<'a> {
-    fn parse(
-        input: &'a [u8],
-    ) -> nom::IResult<&[u8], (Ascii<&'a str>, Option<&'a str>), SipParseError> {
-        let (input, (_, parameter_name, _)) = take_while_trim_sws(input, is_token_char)?;
+    /// Creates parameter, value is written as quoted string if it is not a token
+    /// or IPv6 address in brackets, `"` and `\` of quoted value are escaped.
+    /// Fails if name is not a token or value contains CR, LF or other control characters
+    pub fn new(
+        name: &str,
+        value: Option<&str>,
+    ) -> Result<GenericParam<'a>, SipParseError<'static>> {
+        if name.is_empty() || !name.bytes().all(is_token_char) {
+            return Err(SipParseError::new(
+                ParseErrorKind::InvalidParameter,
+                Some("Parameter name must be a token"),
+            ));
+        }
+        let value = match value {
+            Some(value) => Some(GenericParam::new_value(value)?),
+            None => None,
+        };
+        Ok(GenericParam {
+            name: Ascii::new(Cow::Owned(String::from(name))),
+            value,
+            raw: None,
+        })
+    }
 
-        let (_, param_name) = from_utf8_nom(parameter_name)?;
-        if input.is_empty() || input[0] != b'=' {
-            return Ok((input, (Ascii::new(param_name), None)));
+    fn new_value(value: &str) -> Result<(Cow<'a, str>, ParamValueForm), SipParseError<'static>> {
+        let (value, form) = ParamValueForm::of(value);
+        if form != ParamValueForm::Quoted {
+            return Ok((Cow::Owned(String::from(value)), form));
+        }
+        let mut escaped = String::with_capacity(value.len());
+        for c in value.chars() {
+            if (c.is_ascii_control() && c != '\t') || c == '\u{7f}' {
+                return Err(SipParseError::new(
+                    ParseErrorKind::InvalidParameter,
+                    Some("Parameter value contains control characters"),
+                ));
+            }
+            if c == '"' || c == '\\' {
+                escaped.push('\\');
+            }
+            escaped.push(c);
         }
-        let (input, _) = take_sws_token::equal(input)?;
+        Ok((Cow::Owned(escaped), form))
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Param value, without quotes and "[" if it ipv6
+    pub fn value(&self) -> Option<&str> {
+        self.value.as_ref().map(|(value, _)| value.as_ref())
+    }
+
+    pub fn value_form(&self) -> Option<ParamValueForm> {
+        self.value.as_ref().map(|(_, form)| *form)
+    }
+
+    pub fn is_quoted(&self) -> bool {
+        self.value_form() == Some(ParamValueForm::Quoted)
+    }
+
+    /// Value with quoted-pairs of quoted string resolved: `\"` -> `"`
+    pub fn unquoted_value(&self) -> Option<Cow<'_, str>> {
+        let (value, form) = self.value.as_ref()?;
+        if *form != ParamValueForm::Quoted {
+            return Some(Cow::Borrowed(value));
+        }
+        Some(unquote(value))
+    }
+
+    /// Parameter as it was in the input, including whitespaces, without leading ";"
+    pub fn raw(&self) -> Option<&str> {
+        self.raw.as_deref()
+    }
 
-        if input.is_empty() {
-            return sip_parse_error!(2, "generic-param parse error");
+    pub fn into_owned(self) -> GenericParam<'static> {
+        GenericParam {
+            name: Ascii::new(Cow::Owned(self.name.into_inner().into_owned())),
+            value: self
+                .value
+                .map(|(value, form)| (Cow::Owned(value.into_owned()), form)),
+            raw: self.raw.map(|raw| Cow::Owned(raw.into_owned())),
         }
+    }
+
+    fn parse(input: &'a [u8]) -> nom::IResult<&'a [u8], GenericParam<'a>, SipParseError<'a>> {
+        let source_input = input;
+        let (input, (_, parameter_name, _)) = take_while_trim_sws(input, is_token_char)?;
 
-        let (input, parameter_value) = if input[0] == b'"' {
-            let (input, (_, param_val, _)) = take_quoted_string(input).unwrap();
-            (input, param_val)
-        } else if input[0] == b'[' {
-            HostPort::take_ipv6_host(input)?
+        let (_, param_name) = from_utf8_nom(parameter_name)?;
+        let (input, value) = if input.is_empty() || input[0] != b'=' {
+            (input, None)
         } else {
-            take_while(is_token_char)(input)?
+            let (input, _) = take_sws_token::equal(input)?;
+
+            if input.is_empty() {
+                return sip_parse_error!(InvalidParameter, "generic-param parse error");
+            }
+
+            let (input, parameter_value, form) = if input[0] == b'"' {
+                let (input, (_, param_val, _)) = take_quoted_string(input)?;
+                (input, param_val, ParamValueForm::Quoted)
+            } else if input[0] == b'[' {
+                let (input, param_val) = HostPort::take_ipv6_host(input)?;
+                (input, param_val, ParamValueForm::Ipv6Reference)
+            } else {
+                let (input, param_val) = take_while(is_token_char)(input)?;
+                (input, param_val, ParamValueForm::Token)
+            };
+
+            let (input, _) = take_sws(input)?;
+            let (_, parameter_value) = from_utf8_nom(parameter_value)?;
+            (input, Some((Cow::Borrowed(parameter_value), form)))
         };
 
-        let (input, _) = take_sws(input)?;
-        let (_, parameter_value) = from_utf8_nom(parameter_value)?;
+        let (_, raw) = from_utf8_nom(&source_input[..source_input.len() - input.len()])?;
+        Ok((
+            input,
+            GenericParam {
+                name: Ascii::new(Cow::Borrowed(param_name)),
+                value,
+                raw: Some(Cow::Borrowed(raw)),
+            },
+        ))
+    }
+}
 
-        Ok((input, (Ascii::new(param_name), Some(parameter_value))))
+/// Writes parameter without leading ";".
+/// Parsed parameter is written as is
+impl<'a> fmt::Display for GenericParam<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if let Some(raw) = &self.raw {
+            return f.write_str(raw);
+        }
+        write!(f, "{}", self.name)?;
+        match &self.value {
+            None => Ok(()),
+            Some((value, ParamValueForm::Token)) => write!(f, "={}", value),
+            Some((value, ParamValueForm::Ipv6Reference)) => write!(f, "=[{}]", value),
+            // quoted value is stored with escapes as is
+            Some((value, ParamValueForm::Quoted)) => write!(f, "=\"{}\"", value),
+        }
     }
 }
 
-#[derive(PartialEq, Debug)]
+/// Parameters of header value or URI in the order of input.
+/// Duplicates are kept, lookup by name is case-insensitive and returns the first one.
+///
+/// ```rust
+/// use sipmsg::GenericParams;
+///
+/// let (_, mut params) = GenericParams::parse(b";branch=z9hG4bK1;rport;x=\"a b\"").unwrap();
+/// assert_eq!(params.get("rport"), Some(None));
+/// params.set("rport", Some("5060")).unwrap();
+/// params.push("received", Some("192.0.2.1")).unwrap();
+/// assert!(params.set("x", Some("a\r\nVia: evil")).is_err());
+/// params.remove("x");
+/// assert_eq!(params.to_string(), ";branch=z9hG4bK1;rport=5060;received=192.0.2.1");
+/// ```
+#[derive(Clone, PartialEq, Debug, Default)]
 pub struct GenericParams<'a> {
-    params: BTreeMap<Ascii<&'a str>, Option<&'a str>>,
+    params: Vec<GenericParam<'a>>,
 }
 
 impl<'a> GenericParams<'a> {
-    pub fn get(&self, key: &'a str) -> Option<&Option<&'a str>> {
-        let key = Ascii::new(key);
-        self.params.get(&key)
+    pub fn new() -> GenericParams<'a> {
+        GenericParams { params: Vec::new() }
     }
 
-    pub fn keys(&self) -> Keys<'_, Ascii<&'a str>, Option<&'a str>> {
-        self.params.keys()
+    /// Returns `Some(None)` if parameter is present without value
+    pub fn get(&self, key: &str) -> Option<Option<&str>> {
+        self.get_param(key).map(|param| param.value())
     }
 
-    pub fn contains(&self, key: &'a str) -> bool {
-        let key = Ascii::new(key);
-        self.params.contains_key(&key)
+    pub fn get_param(&self, key: &str) -> Option<&GenericParam<'a>> {
+        self.params.iter().find(|param| param.name == key)
     }
 
-    pub fn parse(input: &'a [u8]) -> nom::IResult<&[u8], GenericParams<'a>, SipParseError> {
-        let (input, vec_res) = many0(many_params_parser)(input)?;
-        Ok((
-            input,
-            GenericParams {
-                params: vec_res.into_iter().collect(),
-            },
-        ))
+    /// Values of all parameters with the name
+    pub fn get_all<'s>(&'s self, key: &'s str) -> impl Iterator<Item = Option<&'s str>> + 's {
+        self.params
+            .iter()
+            .filter(move |param| param.name == key)
+            .map(|param| param.value())
+    }
+
+    /// Same as [`get`](GenericParams::get), but escaped characters of value are decoded
+    pub fn get_decoded(&self, key: &str) -> Option<Option<Cow<'_, str>>> {
+        self.get(key).map(|value| value.map(unescape_str))
+    }
+
+    pub fn keys(&self) -> impl Iterator<Item = &Ascii<Cow<'a, str>>> {
+        self.params.iter().map(|param| &param.name)
+    }
+
+    pub fn iter(&self) -> Iter<'_, GenericParam<'a>> {
+        self.params.iter()
+    }
+
+    pub fn contains(&self, key: &str) -> bool {
+        self.params.iter().any(|param| param.name == key)
+    }
+
+    pub fn len(&self) -> usize {
+        self.params.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.params.is_empty()
+    }
+
+    /// Changes value of the first parameter with the name and removes duplicates,
+    /// or appends parameter if it is absent.
+    /// Fails and keeps parameters unchanged if [`GenericParam::new`] fails
+    pub fn set(&mut self, name: &str, value: Option<&str>) -> Result<(), SipParseError<'static>> {
+        self.set_param(GenericParam::new(name, value)?);
+        Ok(())
+    }
+
+    /// Replaces the first parameter with the same name and removes duplicates,
+    /// or appends parameter if it is absent
+    pub(crate) fn set_param(&mut self, new_param: GenericParam<'a>) {
+        match self
+            .params
+            .iter()
+            .position(|param| param.name == new_param.name)
+        {
+            Some(pos) => {
+                let mut index = 0;
+                let name = new_param.name.clone();
+                self.params[pos] = new_param;
+                self.params.retain(|param| {
+                    index += 1;
+                    index - 1 <= pos || param.name != name
+                });
+            }
+            None => self.params.push(new_param),
+        }
+    }
+
+    /// Appends parameter even if parameter with the same name is present.
+    /// Fails and keeps parameters unchanged if [`GenericParam::new`] fails
+    pub fn push(&mut self, name: &str, value: Option<&str>) -> Result<(), SipParseError<'static>> {
+        self.params.push(GenericParam::new(name, value)?);
+        Ok(())
+    }
+
+    /// Removes all parameters with the name, returns `true` if any was removed
+    pub fn remove(&mut self, name: &str) -> bool {
+        let len = self.params.len();
+        self.params.retain(|param| param.name != name);
+        len != self.params.len()
+    }
+
+    pub fn into_owned(self) -> GenericParams<'static> {
+        GenericParams {
+            params: self.params.into_iter().map(|p| p.into_owned()).collect(),
+        }
+    }
+
+    pub fn parse(input: &'a [u8]) -> nom::IResult<&'a [u8], GenericParams<'a>, SipParseError<'a>> {
+        let (input, params) = many0(many_params_parser)(input)?;
+        Ok((input, GenericParams { params }))
+    }
+}
+
+impl<'a, 's> IntoIterator for &'s GenericParams<'a> {
+    type Item = &'s GenericParam<'a>;
+    type IntoIter = Iter<'s, GenericParam<'a>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.params.iter()
     }
 }
 
-fn many_params_parser(
-    input: &[u8],
-) -> nom::IResult<&[u8], (Ascii<&str>, Option<&str>), SipParseError> {
+fn many_params_parser(input: &[u8]) -> nom::IResult<&[u8], GenericParam, SipParseError> {
     if input.len() < 2 || input[0] != b';' {
-        return sip_parse_error!(1, "GenericParamsParser parse error");
+        return sip_parse_error!(InvalidParameter, "GenericParamsParser parse error");
     }
     GenericParam::parse(&input[1..])
 }
+
+/// Writes parameters as `;name=value` pairs.
+/// Parsed parameters are written as they were in the input,
+/// values of new parameters that are not tokens are written as IPv6 reference or quoted string
+impl<'a> fmt::Display for GenericParams<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for param in &self.params {
+            write!(f, ";{}", param)?;
+        }
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use alloc::string::ToString;
 
     fn assert_eq_gp(gparams: &GenericParams, key: &str, val: Option<&str>) {
-        assert_eq!(gparams.get(key), Some(&val));
+        assert_eq!(gparams.get(key), Some(val));
     }
     #[test]
     fn patameters_contains_test() {
@@ -136,9 +394,9 @@ mod tests {
         expected_value: Option<&str>,
         expected_len: usize,
     ) {
-        let (i, (name, value)) = GenericParam::parse(input_str.as_bytes()).unwrap();
-        assert_eq!(name, expected_name);
-        assert_eq!(value, expected_value);
+        let (i, param) = GenericParam::parse(input_str.as_bytes()).unwrap();
+        assert_eq!(param.name(), expected_name);
+        assert_eq!(param.value(), expected_value);
         assert_eq!(i.len(), expected_len);
     }
 
@@ -186,4 +444,103 @@ mod tests {
         fail_parameter_test("");
         fail_parameter_test("a=");
     }
+
+    #[test]
+    fn parameters_order_and_duplicates() {
+        let (_, params) = GenericParams::parse(b";b=1;A=2;b=3;c\r\n").unwrap();
+        assert_eq!(params.len(), 4);
+        let names: Vec<&str> = params.iter().map(|p| p.name()).collect();
+        assert_eq!(names, ["b", "A", "b", "c"]);
+        assert_eq!(params.get("B"), Some(Some("1")));
+        assert_eq!(params.get("a"), Some(Some("2")));
+        let values: Vec<Option<&str>> = params.get_all("b").collect();
+        assert_eq!(values, [Some("1"), Some("3")]);
+    }
+
+    #[test]
+    fn parameters_raw_and_quoting() {
+        let input = "; tag = 1 ;x=\"a \\\"b\\\" c\";received=[2001:db8::9]";
+        let (_, params) = GenericParams::parse(input.as_bytes()).unwrap();
+        assert_eq!(params.to_string(), input);
+
+        let tag = params.get_param("tag").unwrap();
+        assert_eq!(tag.raw(), Some(" tag = 1 "));
+        assert_eq!(tag.value_form(), Some(ParamValueForm::Token));
+
+        let x = params.get_param("x").unwrap();
+        assert!(x.is_quoted());
+        assert_eq!(x.value(), Some("a \\\"b\\\" c"));
+        assert_eq!(x.unquoted_value().unwrap(), "a \"b\" c");
+        assert_eq!(x.to_string(), "x=\"a \\\"b\\\" c\"");
+
+        let received = params.get_param("received").unwrap();
+        assert_eq!(received.value_form(), Some(ParamValueForm::Ipv6Reference));
+        assert_eq!(received.unquoted_value().unwrap(), "2001:db8::9");
+    }
+
+    #[test]
+    fn parameters_set_remove() {
+        let (_, mut params) = GenericParams::parse(b";branch=z9hG4bK1;rport;x=1;rport").unwrap();
+        params.set("RPORT", Some("5060")).unwrap();
+        assert_eq!(params.to_string(), ";branch=z9hG4bK1;RPORT=5060;x=1");
+        params.set("received", Some("[2001:db8::1]")).unwrap();
+        params.set("y", Some("a b")).unwrap();
+        assert_eq!(
+            params.to_string(),
+            ";branch=z9hG4bK1;RPORT=5060;x=1;received=[2001:db8::1];y=\"a b\""
+        );
+        assert!(params.remove("X"));
+        assert!(!params.remove("x"));
+        params.push("y", None).unwrap();
+        assert_eq!(
+            params.to_string(),
+            ";branch=z9hG4bK1;RPORT=5060;received=[2001:db8::1];y=\"a b\";y"
+        );
+
+        let mut params = GenericParams::new();
+        assert!(params.is_empty());
+        params.set("lr", None).unwrap();
+        assert_eq!(params.to_string(), ";lr");
+    }
+
+    #[test]
+    fn parameter_value_form() {
+        let param = GenericParam::new("x", Some("abc:def")).unwrap();
+        assert_eq!(param.value_form(), Some(ParamValueForm::Quoted));
+        assert_eq!(param.to_string(), "x=\"abc:def\"");
+
+        let param = GenericParam::new("x", Some("2001:db8::1")).unwrap();
+        assert_eq!(param.value_form(), Some(ParamValueForm::Quoted));
+
+        let param = GenericParam::new("x", Some("[abc:def]")).unwrap();
+        assert_eq!(param.value_form(), Some(ParamValueForm::Quoted));
+        assert_eq!(param.value(), Some("[abc:def]"));
+
+        let param = GenericParam::new("received", Some("[2001:db8::1]")).unwrap();
+        assert_eq!(param.value_form(), Some(ParamValueForm::Ipv6Reference));
+        assert_eq!(param.value(), Some("2001:db8::1"));
+        assert_eq!(param.to_string(), "received=[2001:db8::1]");
+    }
+
+    #[test]
+    fn parameter_escaping_and_validation() {
+        let param = GenericParam::new("x", Some("a \"b\" \\c")).unwrap();
+        assert_eq!(param.to_string(), "x=\"a \\\"b\\\" \\\\c\"");
+        assert_eq!(param.unquoted_value().unwrap(), "a \"b\" \\c");
+        let param = GenericParam::new("x", Some("a\tb")).unwrap();
+        assert_eq!(param.to_string(), "x=\"a\tb\"");
+
+        for name in ["", "a b", "a;b", "a=b", "a\r\nVia"].iter() {
+            assert!(GenericParam::new(name, None).is_err());
+        }
+        for value in ["a\r\nVia: evil", "a\nb", "a\rb", "a\0b", "a\u{7f}b"].iter() {
+            assert!(GenericParam::new("x", Some(value)).is_err());
+        }
+
+        let (_, mut params) = GenericParams::parse(b";a=1").unwrap();
+        assert!(params.set("a", Some("1\r\nVia: evil")).is_err());
+        assert!(params.set("b c", Some("1")).is_err());
+        assert!(params.push("b", Some("\n")).is_err());
+        assert_eq!(params.to_string(), ";a=1");
+    }
 }
@@ -1,12 +1,24 @@
 use crate::{
-    common::{bnfcore::*, errorparse::SipParseError, nom_wrappers::from_utf8_nom, take_sws_token},
+    common::{
+        bnfcore::*,
+        errorparse::{ParseErrorKind, SipParseError},
+        nom_wrappers::from_utf8_nom,
+        take_sws_token,
+    },
     headers::{
         parsers::ExtensionParser,
         traits::{HeaderValueParserFn, SipHeaderParser},
-        GenericParams, SipRFCHeader, SipUri,
+        typed::TypedHeader,
+        GenericParam, GenericParams, SipRFCHeader, SipUri, TelUri, Uri,
     },
+    parser_config::ParserConfig,
+};
+use alloc::{
+    borrow::Cow,
+    collections::{BTreeMap, VecDeque},
+    string::{String, ToString},
+    vec::Vec,
 };
-use alloc::collections::{BTreeMap, VecDeque};
 use core::str;
 use nom::{bytes::complete::take_while1, character::complete};
 use unicase::Ascii;
@@ -17,11 +29,13 @@ use unicase::Ascii;
 pub enum HeaderValueType {
     EmptyValue,           // SIP header with empty value. Haven't tags
     TokenValue,           // Haven't tags. Simple value of token chars
+    TokenList,            // Haven't tags. token *(SEMI token), e.g. Privacy
     Digit,                // Haven't tags, just *[0-9] in HeaderValue.vstr
     AbsoluteURI,          // tags: AbsoluteURI(R),
     QuotedValue,          // tags: PureValue(R)
     AuthentificationInfo, // tags: AinfoType(R), AinfoValue(R)
     CSeq,                 // tags: Number(R), Method(R)
+    RAck,                 // tags: ResponseNum(R), Number(R), Method(R)
     DateString,           // Haven't tags
     Utf8Text,             // Haven't tags
     Version,              // tags: Major(R) Minor(O)
@@ -33,6 +47,10 @@ pub enum HeaderValueType {
     //       / dresponse / algorithm / cnonce
     //       / opaque / QopValue / nonce-count / auth-param
 
+    // WWW-Authenticate  =  "WWW-Authenticate" HCOLON challenge
+    AuthenticateChallenge, // tags: AuthSchema(R), Token68(O), realm / domain / nonce
+    //       / opaque / stale / algorithm / QopValue
+
     // callid   =  word [ "@" word ]
     CallID, // tags: ID(R), Host(O)
 
@@ -72,9 +90,11 @@ pub enum HeaderTagType {
     Stale,
     QopValue,
     NonceCount,
+    Token68, // token68 of non-Digest scheme, e.g. Basic or Bearer
     ///////////////
     Number,
     Method,
+    ResponseNum, // response-num of RAck
     ID,
     Host,
     Port,
@@ -96,23 +116,23 @@ pub enum HeaderTagType {
     WarnText,
 }
 
-pub type HeaderTags<'a> = BTreeMap<HeaderTagType, &'a [u8]>;
+pub type HeaderTags<'a> = BTreeMap<HeaderTagType, Cow<'a, [u8]>>;
 
 #[derive(PartialEq, Debug)]
 pub struct HeaderValue<'a> {
-    pub vstr: &'a str,
+    pub vstr: Cow<'a, str>,
     pub vtype: HeaderValueType,
     vtags: Option<HeaderTags<'a>>,
-    sip_uri: Option<SipUri<'a>>,
+    uri: Option<Uri<'a>>,
 }
 
 impl<'a> HeaderValue<'a> {
     pub fn create_empty_value() -> HeaderValue<'a> {
         HeaderValue {
-            vstr: "",
+            vstr: Cow::Borrowed(""),
             vtype: HeaderValueType::EmptyValue,
             vtags: None,
-            sip_uri: None,
+            uri: None,
         }
     }
 
@@ -120,17 +140,35 @@ impl<'a> HeaderValue<'a> {
         val: &'a [u8],
         vtype: HeaderValueType,
         vtags: Option<HeaderTags<'a>>,
-        sip_uri: Option<SipUri<'a>>,
+        uri: Option<Uri<'a>>,
     ) -> nom::IResult<&'a [u8], HeaderValue<'a>, SipParseError<'a>> {
         let (_, vstr) = from_utf8_nom(val)?;
 
         Ok((
             val,
             HeaderValue {
-                vstr: vstr,
+                vstr: Cow::Borrowed(vstr),
                 vtype: vtype,
                 vtags: vtags,
-                sip_uri: sip_uri,
+                uri: uri,
+            },
+        ))
+    }
+
+    /// Same as `new`, but invalid UTF-8 sequences are replaced by U+FFFD
+    pub fn new_lossy(
+        val: &'a [u8],
+        vtype: HeaderValueType,
+        vtags: Option<HeaderTags<'a>>,
+        uri: Option<Uri<'a>>,
+    ) -> nom::IResult<&'a [u8], HeaderValue<'a>, SipParseError<'a>> {
+        Ok((
+            val,
+            HeaderValue {
+                vstr: String::from_utf8_lossy(val),
+                vtype,
+                vtags,
+                uri,
             },
         ))
     }
@@ -139,8 +177,57 @@ impl<'a> HeaderValue<'a> {
         self.vtags.as_ref()
     }
 
+    /// URI of name-addr value
+    pub fn uri(&self) -> Option<&Uri<'a>> {
+        self.uri.as_ref()
+    }
+
     pub fn sip_uri(&self) -> Option<&SipUri<'a>> {
-        self.sip_uri.as_ref()
+        self.uri.as_ref().and_then(|uri| uri.sip())
+    }
+
+    pub fn tel_uri(&self) -> Option<&TelUri<'a>> {
+        self.uri.as_ref().and_then(|uri| uri.tel())
+    }
+
+    pub fn into_owned(self) -> HeaderValue<'static> {
+        HeaderValue {
+            vstr: Cow::Owned(self.vstr.into_owned()),
+            vtype: self.vtype,
+            vtags: self.vtags.map(|tags| {
+                tags.into_iter()
+                    .map(|(k, v)| (k, Cow::Owned(v.into_owned())))
+                    .collect()
+            }),
+            uri: self.uri.map(|u| u.into_owned()),
+        }
+    }
+}
+
+/// Physical line of the message the header value was parsed from.
+/// Values of a comma-separated list are written back as this line
+/// while all of them are present and unchanged
+#[derive(PartialEq, Debug)]
+pub(crate) struct HeaderLine<'a> {
+    /// Number of the line in the header section
+    pub id: usize,
+    /// Position of the value in the line
+    pub index: usize,
+    /// Count of values in the line
+    pub count: usize,
+    /// Name, values and separators as they are in the message, without CRLF.
+    /// Only the first value of the line keeps it
+    pub raw: Option<Cow<'a, [u8]>>,
+}
+
+impl<'a> HeaderLine<'a> {
+    fn into_owned(self) -> HeaderLine<'static> {
+        HeaderLine {
+            id: self.id,
+            index: self.index,
+            count: self.count,
+            raw: self.raw.map(|raw| Cow::Owned(raw.into_owned())),
+        }
     }
 }
 
@@ -148,13 +235,15 @@ impl<'a> HeaderValue<'a> {
 /// [rfc3261 section-7.3](https://tools.ietf.org/html/rfc3261#section-7.3)
 pub struct Header<'a> {
     /// SIP header name
-    pub name: Ascii<&'a str>,
+    pub name: Ascii<Cow<'a, str>>,
     /// SIP header value
     pub value: HeaderValue<'a>,
     /// SIP parameters
     parameters: Option<GenericParams<'a>>,
     /// Raw representation part of string that contain value and params
-    pub raw_value_param: &'a[u8]
+    pub raw_value_param: Cow<'a, [u8]>,
+    /// Line of the parsed message, `None` for new or changed header
+    pub(crate) line: Option<HeaderLine<'a>>,
 }
 
 impl<'a> Header<'a> {
@@ -162,13 +251,76 @@ impl<'a> Header<'a> {
         name: &'a str,
         value: HeaderValue<'a>,
         parameters: Option<GenericParams<'a>>,
-        raw_value_param: &'a[u8],
+        raw_value_param: &'a [u8],
     ) -> Header<'a> {
         Header {
-            name: { Ascii::new(name) },
+            name: { Ascii::new(Cow::Borrowed(name)) },
             value: value,
             parameters: parameters,
-            raw_value_param: raw_value_param
+            raw_value_param: Cow::Borrowed(raw_value_param),
+            line: None,
+        }
+    }
+
+    pub fn into_owned(self) -> Header<'static> {
+        Header {
+            name: Ascii::new(Cow::Owned(self.name.into_inner().into_owned())),
+            value: self.value.into_owned(),
+            parameters: self.parameters.map(|p| p.into_owned()),
+            raw_value_param: Cow::Owned(self.raw_value_param.into_owned()),
+            line: self.line.map(|line| line.into_owned()),
+        }
+    }
+
+    /// Original line of the first header and count of headers of this line,
+    /// if the line starts at the first header and all its values follow unchanged
+    pub(crate) fn unchanged_line<'h>(headers: &'h [Header<'a>]) -> Option<(&'h [u8], usize)> {
+        let first = headers.first()?.line.as_ref()?;
+        let raw = first.raw.as_ref()?;
+        if first.index != 0 || headers.len() < first.count {
+            return None;
+        }
+        for (index, header) in headers[..first.count].iter().enumerate().skip(1) {
+            match &header.line {
+                Some(line) if line.id == first.id && line.index == index => {}
+                _ => return None,
+            }
+        }
+        Some((raw, first.count))
+    }
+
+    /// Creates header from name and value, e.g. to insert it to the message.
+    /// Value is parsed by the parser of header name, so it must contain one header value
+    /// (`Via: SIP/2.0/UDP 10.0.0.1;branch=z9hG4bK1`, but not `Allow: INVITE, ACK`)
+    pub fn from_name_value(
+        name: &str,
+        value: &str,
+    ) -> Result<Header<'static>, SipParseError<'static>> {
+        let mut line = String::with_capacity(name.len() + value.len() + 4);
+        line.push_str(name);
+        line.push_str(": ");
+        line.push_str(value);
+        line.push_str("\r\n");
+
+        match Header::parse(line.as_bytes()) {
+            Ok((rest, (_, mut headers))) => {
+                if headers.len() != 1 || rest != b"\r\n" {
+                    return Err(SipParseError::new(
+                        ParseErrorKind::InvalidHeaderValue,
+                        Some("Value must contain single header"),
+                    ));
+                }
+                let mut header = headers.pop_front().unwrap().into_owned();
+                header.line = None;
+                Ok(header)
+            }
+            // message of error points to the temporary buffer
+            Err(nom::Err::Error(e)) | Err(nom::Err::Failure(e)) => {
+                Err(SipParseError::new(e.kind, None))
+            }
+            Err(nom::Err::Incomplete(_)) => {
+                Err(SipParseError::new(ParseErrorKind::InvalidHeaderValue, None))
+            }
         }
     }
 
@@ -176,9 +328,56 @@ impl<'a> Header<'a> {
         self.parameters.as_ref()
     }
 
-    pub fn find_parser(header_name: &'a str) -> (Option<SipRFCHeader>, HeaderValueParserFn) {
+    /// Decodes header to typed view, e.g. `ViaHeader`
+    pub fn typed<'h, T: TypedHeader<'h>>(&'h self) -> Result<T, SipParseError<'static>> {
+        T::from_header(self)
+    }
+
+    /// Sets parameter of header value, e.g. `received` of Via.
+    /// `raw_value_param` is updated, so the header is serialized with new parameter.
+    /// Fails and keeps header unchanged if name or value is invalid, see [`GenericParam::new`]
+    pub fn set_param(
+        &mut self,
+        name: &str,
+        value: Option<&str>,
+    ) -> Result<(), SipParseError<'static>> {
+        let param = GenericParam::new(name, value)?;
+        self.update_params(|params| params.set_param(param));
+        Ok(())
+    }
+
+    /// Removes all parameters with the name, returns `true` if any was removed
+    pub fn remove_param(&mut self, name: &str) -> bool {
+        let mut removed = false;
+        self.update_params(|params| removed = params.remove(name));
+        removed
+    }
+
+    /// Parameters are always written at the end of `raw_value_param`
+    fn update_params<F: FnOnce(&mut GenericParams<'a>)>(&mut self, update: F) {
+        let old_len = self
+            .parameters
+            .as_ref()
+            .map_or(0, |params| params.to_string().len());
+        let params = self.parameters.get_or_insert_with(GenericParams::new);
+        update(params);
+        self.line = None;
+
+        let value_len = self.raw_value_param.len() - old_len;
+        let mut raw = Vec::from(&self.raw_value_param[..value_len]);
+        raw.extend_from_slice(params.to_string().as_bytes());
+        if params.is_empty() {
+            self.parameters = None;
+        }
+        self.raw_value_param = Cow::Owned(raw);
+    }
+
+    pub fn find_parser(
+        header_name: &'a str,
+        config: &ParserConfig,
+    ) -> (Option<SipRFCHeader>, HeaderValueParserFn) {
         match SipRFCHeader::from_str(&header_name) {
-            Some(rfc_header) => (Some(rfc_header), rfc_header.get_parser()),
+            Some(rfc_header) => (Some(rfc_header), rfc_header.get_parser(config)),
             None => (None, ExtensionParser::take_value),
         }
     }
@@ -188,7 +387,7 @@ impl<'a> Header<'a> {
         let (input, _) = take_sws_token::colon(input)?;
         match str::from_utf8(header_name) {
             Ok(hdr_str) => Ok((input, hdr_str)),
-            Err(_) => sip_parse_error!(1, "Bad header name"),
+            Err(_) => sip_parse_error!(InvalidHeaderName),
         }
     }
 
@@ -208,10 +407,13 @@ impl<'a> Header<'a> {
         // skip whitespaces after take value
         let (inp, _) = complete::space0(inp)?;
         if inp.is_empty() {
-            return sip_parse_error!(1, "Error parse header value");
+            return sip_parse_error!(InvalidHeaderValue, "Unexpected end of header value");
         }
         if inp[0] != b',' && inp[0] != b';' && inp[0] != b' ' && !is_crlf(inp) {
-            return sip_parse_error!(2, "Error parse header value");
+            return sip_parse_error!(
+                InvalidHeaderValue,
+                "Unexpected character after header value"
+            );
         }
 
         if inp[0] == b';' {
@@ -231,16 +433,35 @@ impl<'a> Header<'a> {
         Ok((input, Some(parameters)))
     }
 
+    /// Parses header line up to CRLF, which is not consumed
     pub fn parse(
         input: &'a [u8],
     ) -> nom::IResult<&[u8], (Option<SipRFCHeader>, VecDeque<Header<'a>>), SipParseError> {
+        Header::parse_with_config(input, &ParserConfig::strict())
+    }
+
+    /// Same as `parse`, but lenient `config` replaces invalid UTF-8
+    /// of display names and text values by U+FFFD
+    pub fn parse_with_config(
+        input: &'a [u8],
+        config: &ParserConfig,
+    ) -> nom::IResult<&'a [u8], (Option<SipRFCHeader>, VecDeque<Header<'a>>), SipParseError<'a>>
+    {
+        let line_start = input;
         let mut headers = VecDeque::new();
-        let (input, header_name) = Header::take_name(input)?;
-        let (rfc_type, value_parser) = Header::find_parser(header_name);
+        let (input, header_name) = Header::take_name(input)
+            .map_err(|err| err.map(|e| e.or_kind(ParseErrorKind::InvalidHeaderName).at(input)))?;
+        let (rfc_type, value_parser) = Header::find_parser(header_name, config);
         let mut inp = input;
         loop {
-            let (input, (value, params)) = Header::take_value(inp, value_parser)?;
-            headers.push_back(Header::new(header_name, value, params, &inp[..inp.len() - input.len()]));
+            let (input, (value, params)) = Header::take_value(inp, value_parser)
+                .map_err(|err| err.map(|e| e.in_header(header_name, inp)))?;
+            headers.push_back(Header::new(
+                header_name,
+                value,
+                params,
+                &inp[..inp.len() - input.len()],
+            ));
             if input[0] == b',' {
                 let (input, _) = take_sws_token::comma(input)?;
                 inp = input;
@@ -249,6 +470,20 @@ impl<'a> Header<'a> {
             inp = input;
             break;
         }
+        let raw_line = &line_start[..line_start.len() - inp.len()];
+        let count = headers.len();
+        for (index, header) in headers.iter_mut().enumerate() {
+            header.line = Some(HeaderLine {
+                id: 0,
+                index,
+                count,
+                raw: if index == 0 {
+                    Some(Cow::Borrowed(raw_line))
+                } else {
+                    None
+                },
+            });
+        }
         Ok((inp, (rfc_type, headers)))
     }
 }
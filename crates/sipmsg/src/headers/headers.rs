@@ -1,155 +1,467 @@
 use crate::{
-    common::{bnfcore::is_crlf, errorparse::SipParseError},
-    headers::{SipHeader, SipRFCHeader},
+    common::errorparse::SipParseError,
+    headers::{SipHeader, SipRFCHeader, TypedHeader},
+    parser_config::{
+        is_empty_line, original_position, take_header_line, DeviationKind, ParseContext,
+        ParserConfig,
+    },
 };
-use alloc::collections::{
-    btree_map::{BTreeMap, Keys},
-    VecDeque,
+use alloc::{
+    borrow::Cow,
+    collections::{BTreeMap, VecDeque},
+    vec,
+    vec::Vec,
+};
+use core::{
+    mem,
+    ops::{Deref, Index},
+    slice, str,
 };
-use core::str;
 use nom::bytes::complete::tag;
 use unicase::Ascii;
 
+/// Headers in the original (wire) order.
+/// Lookup by name is made through index of header positions
 pub struct Headers<'a> {
-    rfc_headers: BTreeMap<SipRFCHeader, VecDeque<SipHeader<'a>>>,
-    ext_headers: Option<BTreeMap<Ascii<&'a str>, VecDeque<SipHeader<'a>>>>,
+    headers: Vec<SipHeader<'a>>,
+    rfc_index: BTreeMap<SipRFCHeader, Vec<usize>>,
+    /// Positions of extension headers grouped by name
+    /// in order of the first appearance
+    ext_index: Vec<Vec<usize>>,
 }
 
-impl<'a> Headers<'a> {
-    pub fn get_ext(&self, key: &'a str) -> Option<&VecDeque<SipHeader<'a>>> {
-        match &self.ext_headers {
-            Some(hdrs) => hdrs.get(&Ascii::new(key)),
-            None => None,
+/// Headers with the same name in the order they appear in the message
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct HeadersGroup<'h, 'a> {
+    headers: &'h [SipHeader<'a>],
+    positions: &'h [usize],
+}
+
+impl<'h, 'a> HeadersGroup<'h, 'a> {
+    pub fn len(&self) -> usize {
+        self.positions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.positions.is_empty()
+    }
+
+    pub fn get(&self, idx: usize) -> Option<&'h SipHeader<'a>> {
+        self.positions.get(idx).map(|pos| &self.headers[*pos])
+    }
+
+    pub fn iter(&self) -> HeadersGroupIter<'h, 'a> {
+        HeadersGroupIter {
+            headers: self.headers,
+            positions: self.positions.iter(),
         }
     }
+}
+
+impl<'h, 'a> Index<usize> for HeadersGroup<'h, 'a> {
+    type Output = SipHeader<'a>;
+
+    fn index(&self, idx: usize) -> &SipHeader<'a> {
+        &self.headers[self.positions[idx]]
+    }
+}
+
+impl<'h, 'a> IntoIterator for HeadersGroup<'h, 'a> {
+    type Item = &'h SipHeader<'a>;
+    type IntoIter = HeadersGroupIter<'h, 'a>;
+
+    fn into_iter(self) -> HeadersGroupIter<'h, 'a> {
+        self.iter()
+    }
+}
+
+impl<'h, 'a> IntoIterator for &HeadersGroup<'h, 'a> {
+    type Item = &'h SipHeader<'a>;
+    type IntoIter = HeadersGroupIter<'h, 'a>;
+
+    fn into_iter(self) -> HeadersGroupIter<'h, 'a> {
+        self.iter()
+    }
+}
+
+pub struct HeadersGroupIter<'h, 'a> {
+    headers: &'h [SipHeader<'a>],
+    positions: slice::Iter<'h, usize>,
+}
+
+impl<'h, 'a> Iterator for HeadersGroupIter<'h, 'a> {
+    type Item = &'h SipHeader<'a>;
+
+    fn next(&mut self) -> Option<&'h SipHeader<'a>> {
+        self.positions.next().map(|pos| &self.headers[*pos])
+    }
+}
+
+/// Header of [`Headers`] for editing in place.
+/// Value and parameters can be changed, name can't be changed,
+/// so header stays in the same place of index
+pub struct HeaderMut<'h, 'a> {
+    header: &'h mut SipHeader<'a>,
+}
+
+impl<'h, 'a> HeaderMut<'h, 'a> {
+    /// Sets parameter of header value, see [`SipHeader::set_param`]
+    pub fn set_param(
+        &mut self,
+        name: &str,
+        value: Option<&str>,
+    ) -> Result<(), SipParseError<'static>> {
+        self.header.set_param(name, value)
+    }
+
+    /// Removes all parameters with the name, returns `true` if any was removed
+    pub fn remove_param(&mut self, name: &str) -> bool {
+        self.header.remove_param(name)
+    }
+
+    /// Replaces value and parameters, e.g. decrement Max-Forwards.
+    /// Value is parsed by the parser of header name, header is not changed on error
+    pub fn set_value(&mut self, value: &str) -> Result<(), SipParseError<'static>> {
+        let mut header: SipHeader<'a> = SipHeader::from_name_value(&self.header.name, value)?;
+        mem::swap(&mut header.name, &mut self.header.name);
+        *self.header = header;
+        Ok(())
+    }
+}
+
+impl<'h, 'a> Deref for HeaderMut<'h, 'a> {
+    type Target = SipHeader<'a>;
+
+    fn deref(&self) -> &SipHeader<'a> {
+        self.header
+    }
+}
+
+impl<'a> Headers<'a> {
+    pub fn get_ext(&self, key: &str) -> Option<HeadersGroup<'_, 'a>> {
+        self.ext_positions(key)
+            .map(|positions| self.group(positions))
+    }
     /// Get headers that defined in rfc
-    pub fn get_rfc(&self, hdr: SipRFCHeader) -> Option<&VecDeque<SipHeader<'a>>> {
-        self.rfc_headers.get(&hdr)
+    pub fn get_rfc(&self, hdr: SipRFCHeader) -> Option<HeadersGroup<'_, 'a>> {
+        self.rfc_index
+            .get(&hdr)
+            .map(|positions| self.group(positions))
     }
 
     /// get single value
     /// Returns some value if header by key should be present only one time
-    pub fn get_ext_s(&self, key: &'a str) -> Option<&SipHeader<'a>> {
-        match &self.ext_headers {
-            Some(hdrs) => match hdrs.get(&Ascii::new(key)) {
-                Some(s) => {
-                    if s.len() == 1 {
-                        return Some(&s[0]);
-                    } else {
-                        return None;
-                    };
-                }
-                None => None,
-            },
-            None => None,
+    pub fn get_ext_s(&self, key: &str) -> Option<&SipHeader<'a>> {
+        match self.ext_positions(key) {
+            Some(positions) if positions.len() == 1 => Some(&self.headers[positions[0]]),
+            _ => None,
         }
     }
 
     /// Get header that defined in rfc
     pub fn get_rfc_s(&self, hdr: SipRFCHeader) -> Option<&SipHeader<'a>> {
-        match self.rfc_headers.get(&hdr) {
-            Some(s) => {
-                if s.len() == 1 {
-                    return Some(&s[0]);
-                } else {
-                    return None;
-                };
-            }
-            None => None,
+        match self.rfc_index.get(&hdr) {
+            Some(positions) if positions.len() == 1 => Some(&self.headers[positions[0]]),
+            _ => None,
         }
     }
 
+    /// Typed view of the topmost header defined in rfc, e.g. `ViaHeader` of `Via`.
+    /// `None` if header is absent
+    pub fn get_typed<'h, T: TypedHeader<'h>>(
+        &'h self,
+        hdr: SipRFCHeader,
+    ) -> Option<Result<T, SipParseError<'static>>> {
+        let pos = self.rfc_index.get(&hdr)?[0];
+        Some(T::from_header(&self.headers[pos]))
+    }
+
+    /// Typed views of all headers defined in rfc, e.g. `NameAddrHeader` of `Route`.
+    /// Error is returned if any of them can't be decoded
+    pub fn get_typed_all<'h, T: TypedHeader<'h>>(
+        &'h self,
+        hdr: SipRFCHeader,
+    ) -> Option<Result<Vec<T>, SipParseError<'static>>> {
+        let positions = self.rfc_index.get(&hdr)?;
+        Some(
+            positions
+                .iter()
+                .map(|pos| T::from_header(&self.headers[*pos]))
+                .collect(),
+        )
+    }
+
+    /// Iterator over all headers in the order they appear in the message
+    pub fn iter(&self) -> slice::Iter<'_, SipHeader<'a>> {
+        self.headers.iter()
+    }
+
     /// Returns length of unique headers
     // TODO rename to unique_len and add total_len
     pub fn len(&self) -> usize {
-        match &self.ext_headers {
-            Some(ext_headers) => ext_headers.len() + self.rfc_headers.len(),
-            None => self.rfc_headers.len(),
-        }
+        self.ext_index.len() + self.rfc_index.len()
     }
 
     fn new() -> Headers<'a> {
         Headers {
-            ext_headers: None,
-            rfc_headers: BTreeMap::<SipRFCHeader, VecDeque<SipHeader<'a>>>::new(),
+            headers: Vec::new(),
+            rfc_index: BTreeMap::new(),
+            ext_index: Vec::new(),
         }
     }
 
-    fn add_rfc_header(
-        &mut self,
-        header_type: SipRFCHeader,
-        mut vec_headers: VecDeque<SipHeader<'a>>,
-    ) {
-        if self.rfc_headers.contains_key(&header_type) {
-            self.rfc_headers
-                .get_mut(&header_type)
-                .unwrap()
-                .append(&mut vec_headers)
-        } else {
-            self.rfc_headers.insert(header_type, vec_headers);
+    fn group<'h>(&'h self, positions: &'h [usize]) -> HeadersGroup<'h, 'a> {
+        HeadersGroup {
+            headers: &self.headers,
+            positions,
+        }
+    }
+
+    fn ext_positions(&self, key: &str) -> Option<&Vec<usize>> {
+        self.ext_index
+            .iter()
+            .find(|positions| self.headers[positions[0]].name == key)
+    }
+
+    /// Positions of headers with the same name as `name`
+    fn positions_by_name(&self, name: &str) -> Option<&Vec<usize>> {
+        match SipRFCHeader::from_str(name) {
+            Some(hdr_type) => self.rfc_index.get(&hdr_type),
+            None => self.ext_positions(name),
         }
     }
 
-    fn add_extension_header(&mut self, mut vec_headers: VecDeque<SipHeader<'a>>) {
-        if self.ext_headers == None {
-            self.ext_headers = Some(BTreeMap::<Ascii<&'a str>, VecDeque<SipHeader<'a>>>::new());
+    /// Appends header to the end and updates index
+    fn push_header(&mut self, header_type: Option<SipRFCHeader>, header: SipHeader<'a>) {
+        let pos = self.headers.len();
+        let headers = &self.headers;
+        match header_type {
+            Some(hdr_type) => self.rfc_index.entry(hdr_type).or_default().push(pos),
+            None => match self
+                .ext_index
+                .iter_mut()
+                .find(|positions| headers[positions[0]].name == header.name)
+            {
+                Some(positions) => positions.push(pos),
+                None => self.ext_index.push(vec![pos]),
+            },
         }
+        self.headers.push(header);
+    }
 
-        if self
-            .ext_headers
-            .as_ref()
-            .unwrap()
-            .contains_key(&vec_headers[0].name)
-        {
-            self.ext_headers
-                .as_mut()
-                .unwrap()
-                .get_mut(&vec_headers[0].name)
-                .unwrap()
-                .append(&mut vec_headers)
-        } else {
-            self.ext_headers
-                .as_mut()
-                .unwrap()
-                .insert(vec_headers[0].name, vec_headers);
+    /// Rebuilds index after headers were inserted or removed in the middle
+    fn reindex(&mut self) {
+        let headers = core::mem::take(&mut self.headers);
+        self.rfc_index.clear();
+        self.ext_index.clear();
+        for header in headers {
+            let header_type = SipRFCHeader::from_str(&header.name);
+            self.push_header(header_type, header);
         }
     }
 
-    pub fn get_rfc_headers_keys(&self) -> Keys<'_, SipRFCHeader, VecDeque<SipHeader<'a>>> {
-        self.rfc_headers.keys()
+    fn insert_at(&mut self, pos: usize, mut header: SipHeader<'a>) {
+        // new header is written by itself, not as a part of parsed line
+        header.line = None;
+        self.headers.insert(pos, header);
+        self.reindex();
+    }
+
+    /// Removes headers at positions, positions must be sorted
+    fn remove_positions(&mut self, positions: &[usize]) -> VecDeque<SipHeader<'a>> {
+        let mut removed = VecDeque::with_capacity(positions.len());
+        for pos in positions.iter().rev() {
+            removed.push_front(self.headers.remove(*pos));
+        }
+        self.reindex();
+        removed
+    }
+
+    /// Appends header after headers with the same name.
+    /// If there are no such headers, header is appended to the end
+    pub fn insert(&mut self, mut header: SipHeader<'a>) {
+        match self.positions_by_name(&header.name) {
+            Some(positions) => {
+                let pos = positions[positions.len() - 1] + 1;
+                self.insert_at(pos, header);
+            }
+            None => {
+                header.line = None;
+                let header_type = SipRFCHeader::from_str(&header.name);
+                self.push_header(header_type, header);
+            }
+        }
+    }
+
+    /// Inserts header before headers with the same name,
+    /// e.g. push Via on top.
+    /// If there are no such headers, header is appended to the end
+    pub fn prepend(&mut self, header: SipHeader<'a>) {
+        match self.positions_by_name(&header.name) {
+            Some(positions) => {
+                let pos = positions[0];
+                self.insert_at(pos, header);
+            }
+            None => self.insert(header),
+        }
+    }
+
+    /// Removes all headers with the same name and inserts header
+    /// at place of the first of them, e.g. rewrite Max-Forwards.
+    /// Returns removed headers
+    pub fn replace(&mut self, header: SipHeader<'a>) -> Option<VecDeque<SipHeader<'a>>> {
+        let positions = match self.positions_by_name(&header.name) {
+            Some(positions) => positions.clone(),
+            None => {
+                self.insert(header);
+                return None;
+            }
+        };
+        let removed = self.remove_positions(&positions);
+        self.insert_at(positions[0], header);
+        Some(removed)
+    }
+
+    /// Removes all headers defined in rfc by type
+    pub fn remove_rfc(&mut self, hdr: SipRFCHeader) -> Option<VecDeque<SipHeader<'a>>> {
+        let positions = self.rfc_index.get(&hdr)?.clone();
+        Some(self.remove_positions(&positions))
+    }
+
+    /// Removes all extension headers by name
+    pub fn remove_ext(&mut self, key: &str) -> Option<VecDeque<SipHeader<'a>>> {
+        let positions = self.ext_positions(key)?.clone();
+        Some(self.remove_positions(&positions))
+    }
+
+    /// Removes topmost header defined in rfc, e.g. pop Route
+    pub fn pop_rfc(&mut self, hdr: SipRFCHeader) -> Option<SipHeader<'a>> {
+        let pos = self.rfc_index.get(&hdr)?[0];
+        self.remove_positions(&[pos]).pop_front()
+    }
+
+    /// Removes topmost extension header by name
+    pub fn pop_ext(&mut self, key: &str) -> Option<SipHeader<'a>> {
+        let pos = self.ext_positions(key)?[0];
+        self.remove_positions(&[pos]).pop_front()
     }
 
-    pub fn get_ext_headers_keys(
-        &self,
-    ) -> Option<Keys<'_, Ascii<&'a str>, VecDeque<SipHeader<'a>>>> {
-        if self.ext_headers == None {
+    /// Topmost header defined in rfc for editing in place,
+    /// e.g. add `received` parameter to Via or rewrite Max-Forwards
+    pub fn top_rfc_mut(&mut self, hdr: SipRFCHeader) -> Option<HeaderMut<'_, 'a>> {
+        let pos = self.rfc_index.get(&hdr)?[0];
+        Some(HeaderMut {
+            header: &mut self.headers[pos],
+        })
+    }
+
+    /// Topmost extension header by name for editing in place
+    pub fn top_ext_mut(&mut self, key: &str) -> Option<HeaderMut<'_, 'a>> {
+        let pos = self.ext_positions(key)?[0];
+        Some(HeaderMut {
+            header: &mut self.headers[pos],
+        })
+    }
+
+    pub fn get_rfc_headers_keys(&self) -> impl Iterator<Item = &SipRFCHeader> + '_ {
+        self.rfc_index.keys()
+    }
+
+    pub fn get_ext_headers_keys(&self) -> Option<impl Iterator<Item = &Ascii<Cow<'a, str>>> + '_> {
+        if self.ext_index.is_empty() {
             return None;
         }
-        Some(self.ext_headers.as_ref().unwrap().keys())
+        Some(
+            self.ext_index
+                .iter()
+                .map(move |positions| &self.headers[positions[0]].name),
+        )
     }
 
+    pub fn into_owned(self) -> Headers<'static> {
+        Headers {
+            headers: self.headers.into_iter().map(|h| h.into_owned()).collect(),
+            rfc_index: self.rfc_index,
+            ext_index: self.ext_index,
+        }
+    }
+
+    /// Offset and line of error are calculated from the beginning of `input`
     pub fn parse(input: &'a [u8]) -> nom::IResult<&[u8], Headers<'a>, SipParseError> {
+        let config = ParserConfig::strict();
+        let mut ctx = ParseContext::new(&config, input);
+        Headers::parse_with_context(input, &mut ctx).map_err(|err| err.map(|e| e.locate(input)))
+    }
+
+    /// Parses headers up to the empty line, which is not consumed
+    pub(crate) fn parse_with_context(
+        input: &'a [u8],
+        ctx: &mut ParseContext,
+    ) -> nom::IResult<&'a [u8], Headers<'a>, SipParseError<'a>> {
         let mut headers_result = Headers::new();
         let mut inp2 = input;
+        let mut line_id = 0;
         loop {
-            let (input, (rfc_type, vec_headers)) = SipHeader::parse(inp2)?;
-            match rfc_type {
-                Some(hdr_type) => {
-                    headers_result.add_rfc_header(hdr_type, vec_headers);
-                }
+            let line_start = inp2;
+            let (input, line_copy) = take_header_line(inp2, ctx)
+                .map_err(|err| err.map(|e| in_header_line(e, line_start)))?;
+            let (input, (rfc_type, vec_headers)) = match line_copy {
                 None => {
-                    headers_result.add_extension_header(vec_headers);
+                    let (input, headers) = SipHeader::parse_with_config(input, ctx.config)?;
+                    let (input, _) = tag("\r\n")(input)?; // move to header parse
+                    (input, headers)
                 }
+                Some(line) => (input, Headers::parse_line_copy(&line, line_start, ctx)?),
+            };
+            if ctx.is_lenient()
+                && str::from_utf8(&line_start[..line_start.len() - input.len()]).is_err()
+            {
+                ctx.deviate(DeviationKind::NonUtf8, line_start);
             }
-            let (input, _) = tag("\r\n")(input)?; // move to header parse
+            for mut header in vec_headers {
+                if let Some(line) = &mut header.line {
+                    line.id = line_id;
+                }
+                headers_result.push_header(rfc_type, header);
+            }
+            line_id += 1;
             inp2 = input; // skip crlf of header field
-            if is_crlf(inp2) {
+            if is_empty_line(inp2, ctx) {
                 // end of headers and start of body part
                 break;
             }
         }
         Ok((inp2, headers_result))
     }
+
+    /// Parses header line rewritten by lenient parser,
+    /// error points to the beginning of `original` line
+    fn parse_line_copy(
+        line: &[u8],
+        original: &'a [u8],
+        ctx: &ParseContext,
+    ) -> Result<(Option<SipRFCHeader>, VecDeque<SipHeader<'a>>), nom::Err<SipParseError<'a>>> {
+        let (_, (rfc_type, headers)) =
+            SipHeader::parse_with_config(line, ctx.config).map_err(|err| {
+                err.map(|e| {
+                    let e = e.locate(line);
+                    let at = original_position(line, e.offset, original);
+                    in_header_line(SipParseError::new(e.kind, e.message).at(at), original)
+                })
+            })?;
+        Ok((
+            rfc_type,
+            headers.into_iter().map(|h| h.into_owned()).collect(),
+        ))
+    }
+}
+
+/// Adds header name to error of the line that starts at `line`
+fn in_header_line<'a>(err: SipParseError<'a>, line: &'a [u8]) -> SipParseError<'a> {
+    match SipHeader::take_name(line) {
+        Ok((_, name)) => err.in_header(name, line),
+        Err(_) => err.at(line),
+    }
 }
 
 #[cfg(test)]
@@ -189,7 +501,7 @@ mod tests {
                         .params()
                         .unwrap()
                         .get(&"q"),
-                    Some(&Some("0.1"))
+                    Some(Some("0.1"))
                 );
 
                 assert_eq!(
@@ -1,5 +1,8 @@
 mod headers;
+pub use headers::HeaderMut as SipHeaderMut;
 pub use headers::Headers as SipHeaders;
+pub use headers::HeadersGroup as SipHeadersGroup;
+pub use headers::HeadersGroupIter as SipHeadersGroupIter;
 
 mod header;
 pub use header::Header as SipHeader;
@@ -12,11 +15,34 @@ pub use rfcheader::SipRFCHeader;
 pub mod traits;
 
 pub mod generic_params;
+pub use generic_params::GenericParam;
 pub use generic_params::GenericParams;
+pub use generic_params::ParamValueForm;
 
 pub mod sipuri;
 pub use sipuri::SipUri;
 
+pub mod uri_params;
+pub use uri_params::Transport as SipTransport;
+pub use uri_params::UriParams as SipUriParams;
+pub use uri_params::UserParam as SipUserParam;
+
+pub mod teluri;
+pub use teluri::TelUri;
+
+pub mod absolute_uri;
+pub use absolute_uri::AbsoluteUri;
+
+pub mod uri;
+pub use uri::Uri;
+
+pub mod typed;
+pub use typed::{
+    AuthScheme, CSeqHeader, Challenge, ContentType, DateHeader, EventHeader, Expires, MaxForwards,
+    NameAddrHeader, PrivacyHeader, PrivacyValue, RAckHeader, RSeq, ReplacesHeader, SubState,
+    SubscriptionState, TargetDialogHeader, TypedHeader, ViaHeader,
+};
+
+mod auth_params;
 mod name_addr;
 mod parsers;
-mod auth_params;
\ No newline at end of file
@@ -1,17 +1,12 @@
 use crate::{
-    common::{bnfcore::is_token_char, errorparse::SipParseError, take_sws_token, nom_wrappers},
-    headers::{
-        header::{HeaderTagType, HeaderTags},
-    },
+    common::{bnfcore::is_token_char, errorparse::SipParseError, nom_wrappers, take_sws_token},
+    headers::header::{HeaderTagType, HeaderTags},
 };
+use alloc::{borrow::Cow, string::String};
 
-use nom::{
-    bytes::complete::{take_while1},
-    character::complete,
-    sequence::tuple,
-};
+use nom::{bytes::complete::take_while1, character::complete};
 
-use crate::SipUri;
+use crate::Uri;
 
 #[derive(PartialEq, Debug)]
 pub enum NameAddrValueType {
@@ -30,12 +25,9 @@ fn predict_value_type(input: &[u8]) -> NameAddrValueType {
         return NameAddrValueType::AquoutedSipURI;
     }
 
-    if &input[..3] != b"sip" {
-        return NameAddrValueType::TokenDisplayName;
-    }
-
-    if input[3] == b':' || &input[3..5] == b"s:" {
-        return NameAddrValueType::SipURI; // this is start of URI, display name isn't present
+    // ':' is not allowed in token, so this is start of URI, display name isn't present
+    if Uri::is_uri(input) {
+        return NameAddrValueType::SipURI;
     }
 
     return NameAddrValueType::TokenDisplayName;
@@ -54,16 +46,17 @@ fn take_display_name(
         return Ok((input, display_name));
     }
     sip_parse_error!(
-        666,
+        InvalidHeaderValue,
         "Parsing of contact is failed. Something wrong we should never be here"
     )
 }
 
-pub fn take<'a>(
-    source_input: &'a [u8],
-) -> nom::IResult<&'a [u8], (&[u8], HeaderTags<'a>, Option<SipUri>), SipParseError<'a>> {
+/// Raw value, tags and URI of name-addr or addr-spec
+type NameAddr<'a> = (&'a [u8], HeaderTags<'a>, Option<Uri<'a>>);
+
+pub fn take<'a>(source_input: &'a [u8]) -> nom::IResult<&'a [u8], NameAddr<'a>, SipParseError<'a>> {
     if source_input.len() < 5 {
-        return sip_parse_error!(2, "name-addr header value is too short");
+        return sip_parse_error!(InvalidHeaderValue, "name-addr header value is too short");
     }
     let mut tags = HeaderTags::new();
     let next_value_type = predict_value_type(source_input);
@@ -71,14 +64,14 @@ pub fn take<'a>(
         || next_value_type == NameAddrValueType::TokenDisplayName
     {
         let (input, display_name) = take_display_name(source_input, next_value_type)?;
-        tags.insert(HeaderTagType::DisplayName, display_name);
+        tags.insert(HeaderTagType::DisplayName, Cow::Borrowed(display_name));
         input
     } else {
         source_input
     };
 
     if input.is_empty() {
-        return sip_parse_error!(3, "Contact header value is invalid");
+        return sip_parse_error!(InvalidHeaderValue, "Contact header value is invalid");
     }
 
     let (input, is_quoted_uri) = if input[0] == b'<' {
@@ -89,45 +82,44 @@ pub fn take<'a>(
     };
 
     if source_input.len() < 5 {
-        return sip_parse_error!(2, "Contact header value is too short");
+        return sip_parse_error!(InvalidHeaderValue, "Contact header value is too short");
     }
 
-    let is_sip_uri = &input[..4] == b"sip:" || &input[..5] == b"sips:";
-    if !is_sip_uri && !is_quoted_uri {
-        return sip_parse_error!(4, "Absolute uri in contact header without <> not supported");
+    let uri_input = input;
+    let (input, uri) = Uri::parse_ext(input, is_quoted_uri)?;
+    if uri.absolute().is_some() {
+        tags.insert(
+            HeaderTagType::AbsoluteURI,
+            Cow::Borrowed(&uri_input[..uri_input.len() - input.len()]),
+        );
     }
-
-    if is_sip_uri {
-        let (input, sipuri) = SipUri::parse_ext(input, is_quoted_uri)?;
-        let mut count_wsps_after_raquout = 0;
-        let input = if is_quoted_uri {
-            let (input, wsps_after) = take_sws_token::raquot(input)?;
-            count_wsps_after_raquout = wsps_after.len();
-            input
-        } else {
-            input
-        };
-        return Ok((
-            input,
-            (
-                &source_input[..source_input.len() - input.len() - count_wsps_after_raquout],
-                tags,
-                Some(sipuri),
-            ),
-        ));
-    }
-
-    // this is absolute uri
-    let uri_taker = take_while1(|c| c != b'>');
-    let (input, (uri, spaces_after_raquot)) = tuple((uri_taker, take_sws_token::raquot))(input)?;
-    tags.insert(HeaderTagType::AbsoluteURI, uri);
-
+    let mut count_wsps_after_raquout = 0;
+    let input = if is_quoted_uri {
+        let (input, wsps_after) = take_sws_token::raquot(input)?;
+        count_wsps_after_raquout = wsps_after.len();
+        input
+    } else {
+        input
+    };
     Ok((
         input,
         (
-            &source_input[..source_input.len() - input.len() - spaces_after_raquot.len()],
+            &source_input[..source_input.len() - input.len() - count_wsps_after_raquout],
             tags,
-            None,
+            Some(uri),
         ),
     ))
 }
+
+/// Same as [`take`], but invalid UTF-8 sequences of display name are replaced by U+FFFD
+pub fn take_lossy<'a>(
+    source_input: &'a [u8],
+) -> nom::IResult<&'a [u8], NameAddr<'a>, SipParseError<'a>> {
+    let (input, (vstr_val, mut tags, uri)) = take(source_input)?;
+    if let Some(display_name) = tags.get_mut(&HeaderTagType::DisplayName) {
+        if let Cow::Owned(name) = String::from_utf8_lossy(display_name) {
+            *display_name = Cow::Owned(name.into_bytes());
+        }
+    }
+    Ok((input, (vstr_val, tags, uri)))
+}
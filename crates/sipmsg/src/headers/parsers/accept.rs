@@ -54,12 +54,12 @@ impl AcceptParser {
 
 impl SipHeaderParser for AcceptParser {
     fn take_value(input: &[u8]) -> nom::IResult<&[u8], HeaderValue, SipParseError> {
-        let (inp, (left_part, (_, slash_part, _), right_part)) = tuple((
+        let (inp, _) = tuple((
             take_while1(is_token_char),
             take_sws_token::slash,
             take_while1(is_token_char),
         ))(input)?;
-        let offset = left_part.len() + slash_part.len() + right_part.len();
+        let offset = input.len() - inp.len();
         let (_, hdr_val) =
             HeaderValue::new(&input[..offset], HeaderValueType::TokenValue, None, None)?;
         Ok((inp, hdr_val))
@@ -88,6 +88,10 @@ mod test {
             AcceptParser::take_value("application/h.245 ; q=0.1\r\n".as_bytes()).unwrap();
         assert_eq!(input, " ; q=0.1\r\n".as_bytes());
         assert_eq!(val.vstr, "application/h.245");
+
+        let (input, val) = AcceptParser::take_value("text / html;level=1\r\n".as_bytes()).unwrap();
+        assert_eq!(input, ";level=1\r\n".as_bytes());
+        assert_eq!(val.vstr, "text / html");
     }
 
     #[test]
@@ -5,7 +5,7 @@ use crate::{
         traits::SipHeaderParser,
     },
 };
-use nom::{bytes::complete::take_while1};
+use nom::bytes::complete::take_while1;
 
 /// Accept-Encoding  =  "Accept-Encoding" HCOLON
 //                      [ encoding *(COMMA encoding) ]
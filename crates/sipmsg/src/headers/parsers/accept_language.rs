@@ -5,7 +5,7 @@ use crate::{
         traits::SipHeaderParser,
     },
 };
-use nom::{bytes::complete::take_while1};
+use nom::bytes::complete::take_while1;
 
 /// Accept-Language  =  "Accept-Language" HCOLON
 //                      [ language *(COMMA language) ]
@@ -28,14 +28,20 @@ impl SipHeaderParser for AcceptLanguageParser {
             return Ok((input, hdr_val));
         }
         if left_part.len() < 1 || left_part.len() > 8 {
-            return sip_parse_error!(1, "Invalid length of left part of AcceptLanguage Header");
+            return sip_parse_error!(
+                InvalidHeaderValue,
+                "Invalid length of left part of AcceptLanguage Header"
+            );
         }
 
         let (input, _) = nom::character::complete::char('-')(input)?; // skip -
         let (input, right_part) = take_while1(is_alpha)(input)?;
 
         if right_part.len() < 1 || right_part.len() > 8 {
-            return sip_parse_error!(2, "Invalid length of right part of AcceptLanguage Header");
+            return sip_parse_error!(
+                InvalidHeaderValue,
+                "Invalid length of right part of AcceptLanguage Header"
+            );
         }
         let offset = left_part.len() + right_part.len() + 1 /*`-`*/;
         let (_, hdr_val) = HeaderValue::new(
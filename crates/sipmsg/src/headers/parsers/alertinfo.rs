@@ -3,32 +3,32 @@ use crate::{
     headers::{
         header::{HeaderTagType, HeaderTags, HeaderValue, HeaderValueType},
         traits::SipHeaderParser,
+        Uri,
     },
 };
-
-use nom::bytes::complete::take_while1;
+use alloc::borrow::Cow;
 
 /// Alert-Info   =  "Alert-Info" HCOLON alert-param *(COMMA alert-param)
 // alert-param  =  LAQUOT absoluteURI RAQUOT *( SEMI generic-param )
+// Call-Info and Error-Info values have the same form
 pub struct AlertInfoParser;
 
 impl SipHeaderParser for AlertInfoParser {
     fn take_value(source_input: &[u8]) -> nom::IResult<&[u8], HeaderValue, SipParseError> {
-        // let uri = take_while1(|c| !is_wsp(c) && c != b'>');
         let (input, _) = take_sws_token::laquot(source_input)?;
-        let (input, uri) = take_while1(|c| c != b'>')(input)?;
-        let (input, spaces_after_raquot) = take_sws_token::raquot(input)?;
-
+        let (rest, uri) = Uri::parse(input)?;
+        let uri_bytes = &input[..input.len() - rest.len()];
+        let (input, spaces_after_raquot) = take_sws_token::raquot(rest)?;
 
         let mut tags = HeaderTags::new();
-        tags.insert(HeaderTagType::AbsoluteURI, uri);
+        tags.insert(HeaderTagType::AbsoluteURI, Cow::Borrowed(uri_bytes));
 
         // 1 for '>' char
         let (_, hdr_val) = HeaderValue::new(
             &source_input[..source_input.len() - input.len() - spaces_after_raquot.len()],
             HeaderValueType::AbsoluteURI,
             Some(tags),
-            None,
+            Some(uri),
         )?;
         Ok((input, hdr_val))
     }
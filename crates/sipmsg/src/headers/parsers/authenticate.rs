@@ -0,0 +1,60 @@
+use crate::{
+    common::errorparse::SipParseError,
+    headers::{
+        auth_params,
+        header::{HeaderValue, HeaderValueType},
+        traits::SipHeaderParser,
+    },
+};
+
+// WWW-Authenticate    =  "WWW-Authenticate" HCOLON challenge
+// Proxy-Authenticate  =  "Proxy-Authenticate" HCOLON challenge
+// challenge           =  ("Digest" LWS digest-cln *(COMMA digest-cln))
+//                        / other-challenge
+// Header may contain several challenges separated by comma (rfc7235),
+// each of them is a separate header value
+pub struct Authenticate;
+
+impl SipHeaderParser for Authenticate {
+    fn take_value(source_input: &[u8]) -> nom::IResult<&[u8], HeaderValue<'_>, SipParseError<'_>> {
+        let (input, (vstr, tags)) = auth_params::take_challenge(source_input)?;
+        let (_, hdr_val) = HeaderValue::new(
+            vstr,
+            HeaderValueType::AuthenticateChallenge,
+            Some(tags),
+            None,
+        )?;
+        Ok((input, hdr_val))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::headers::header::HeaderTagType;
+
+    #[test]
+    fn authenticate_parser_test() {
+        let (input, val) = Authenticate::take_value(
+            b"Digest realm=\"atlanta.com\", stale=TRUE, unknown=x, Bearer realm=\"b\"\r\n",
+        )
+        .unwrap();
+        assert_eq!(input, b", Bearer realm=\"b\"\r\n");
+        assert_eq!(
+            val.vstr,
+            "Digest realm=\"atlanta.com\", stale=TRUE, unknown=x"
+        );
+        assert_eq!(
+            val.tags().unwrap()[&HeaderTagType::Stale],
+            "TRUE".as_bytes()
+        );
+
+        let (input, val) = Authenticate::take_value(b"Basic realm=\"b\"\r\n").unwrap();
+        assert_eq!(input, b"\r\n");
+        assert_eq!(
+            val.tags().unwrap()[&HeaderTagType::AuthSchema],
+            "Basic".as_bytes()
+        );
+        assert_eq!(val.tags().unwrap()[&HeaderTagType::Realm], "b".as_bytes());
+    }
+}
@@ -8,6 +8,7 @@ use crate::{
         traits::SipHeaderParser,
     },
 };
+use alloc::borrow::Cow;
 use nom::bytes::complete::take_while;
 
 pub struct AuthenticationInfoParser;
@@ -29,14 +30,17 @@ impl SipHeaderParser for AuthenticationInfoParser {
     fn take_value(source_input: &[u8]) -> nom::IResult<&[u8], HeaderValue, SipParseError> {
         let (input, info_name) = take_while(is_alpha)(source_input)?;
         if !AuthenticationInfoParser::is_info_name_allowed(info_name) {
-            return sip_parse_error!(1, "AuthentificatiionInfo value name is invalid");
+            return sip_parse_error!(
+                InvalidHeaderValue,
+                "Authentication-Info value name is invalid"
+            );
         }
         let (input, (_, _, _)) = take_sws_token::equal(input)?;
         let (input, (_, value, spaces_after_rdquot)) = take_quoted_string(input).unwrap();
 
         let mut tags = HeaderTags::new();
-        tags.insert(HeaderTagType::AinfoType, info_name);
-        tags.insert(HeaderTagType::AinfoValue, value);
+        tags.insert(HeaderTagType::AinfoType, Cow::Borrowed(info_name));
+        tags.insert(HeaderTagType::AinfoValue, Cow::Borrowed(value));
 
         let (_, hdr_val) = HeaderValue::new(
             &source_input[..source_input.len() - input.len() - spaces_after_rdquot.len()],
@@ -1,15 +1,15 @@
 use crate::{
     common::errorparse::SipParseError,
     headers::{
-        header::{HeaderValue, HeaderValueType},
         auth_params,
+        header::{HeaderValue, HeaderValueType},
         traits::SipHeaderParser,
     },
 };
 
 // Authorization     =  "Authorization" HCOLON credentials
 // credentials       =  ("Digest" LWS digest-response) / other-response
-// other-response not supported
+// other-response is taken as token68 or auth-params of rfc7235 (Basic, Bearer...)
 pub struct Authorization;
 
 // tags: username / realm / nonce / digest-uri
@@ -47,11 +47,28 @@ mod test {
         assert_eq!(val.vstr, "NoOneKnowsThisScheme opaque-data=here");
         assert_eq!(
             val.tags().unwrap()[&HeaderTagType::AuthSchema],
-            b"NoOneKnowsThisScheme"
+            "NoOneKnowsThisScheme".as_bytes()
         );
         assert_eq!(input, b"\r\n");
     }
 
+    #[test]
+    fn authorization_parser_test_token68() {
+        let (input, val) = Authorization::take_value(
+            "Bearer eyJhbGciOiJSUzI1NiJ9.eyJzdWIiOiIxIn0.c2ln\r\n".as_bytes(),
+        )
+        .unwrap();
+        assert_eq!(input, b"\r\n");
+        assert_eq!(
+            val.tags().unwrap()[&HeaderTagType::AuthSchema],
+            "Bearer".as_bytes()
+        );
+        assert_eq!(
+            val.tags().unwrap()[&HeaderTagType::Token68],
+            "eyJhbGciOiJSUzI1NiJ9.eyJzdWIiOiIxIn0.c2ln".as_bytes()
+        );
+    }
+
     #[test]
     fn authorization_parser_test() {
         let val = Authorization::take_value(
@@ -64,22 +81,37 @@ mod test {
         \turi=\"sip:bob@biloxi.com\", qop=auth, nc=00000001,unkownqparam=\"value\", cnonce=\"0a4f113b\", \
         response=\"6629fae49393a05397450978507c4ef1\", opaque=\"5ccc069c403ebaf9f0171e9517f40e41\"");
         assert_eq!(input, b"\r\n");
-        assert_eq!(val.tags().unwrap()[&HeaderTagType::Username], b"bob");
-        assert_eq!(val.tags().unwrap()[&HeaderTagType::Realm], b"biloxi.com");
+        assert_eq!(
+            val.tags().unwrap()[&HeaderTagType::Username],
+            "bob".as_bytes()
+        );
+        assert_eq!(
+            val.tags().unwrap()[&HeaderTagType::Realm],
+            "biloxi.com".as_bytes()
+        );
         assert_eq!(
             val.tags().unwrap()[&HeaderTagType::DigestUri],
-            b"sip:bob@biloxi.com"
+            "sip:bob@biloxi.com".as_bytes()
+        );
+        assert_eq!(
+            val.tags().unwrap()[&HeaderTagType::QopValue],
+            "auth".as_bytes()
+        );
+        assert_eq!(
+            val.tags().unwrap()[&HeaderTagType::NonceCount],
+            "00000001".as_bytes()
+        );
+        assert_eq!(
+            val.tags().unwrap()[&HeaderTagType::Cnonce],
+            "0a4f113b".as_bytes()
         );
-        assert_eq!(val.tags().unwrap()[&HeaderTagType::QopValue], b"auth");
-        assert_eq!(val.tags().unwrap()[&HeaderTagType::NonceCount], b"00000001");
-        assert_eq!(val.tags().unwrap()[&HeaderTagType::Cnonce], b"0a4f113b");
         assert_eq!(
             val.tags().unwrap()[&HeaderTagType::Dresponse],
-            b"6629fae49393a05397450978507c4ef1"
+            "6629fae49393a05397450978507c4ef1".as_bytes()
         );
         assert_eq!(
             val.tags().unwrap()[&HeaderTagType::Opaque],
-            b"5ccc069c403ebaf9f0171e9517f40e41"
+            "5ccc069c403ebaf9f0171e9517f40e41".as_bytes()
         );
     }
 }
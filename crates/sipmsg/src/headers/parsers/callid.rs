@@ -5,6 +5,7 @@ use crate::{
         traits::SipHeaderParser,
     },
 };
+use alloc::borrow::Cow;
 use nom::bytes::complete::{take, take_while1};
 
 /// Call-ID  =  ( "Call-ID" / "i" ) HCOLON callid
@@ -16,11 +17,11 @@ impl SipHeaderParser for CallID {
         let mut tags = HeaderTags::new();
 
         let (input, id) = take_while1(is_word_char)(source_input)?;
-        tags.insert(HeaderTagType::ID, id);
+        tags.insert(HeaderTagType::ID, Cow::Borrowed(id));
         if !input.is_empty() && input[0] == b'@' {
             let (input, _) = take(1usize)(input)?;
             let (input, host) = take_while1(is_word_char)(input)?;
-            tags.insert(HeaderTagType::Host, host);
+            tags.insert(HeaderTagType::Host, Cow::Borrowed(host));
 
             let (_, hdr_val) = HeaderValue::new(
                 &source_input[..id.len() + host.len() + 1 /* 1 - is '@' */],
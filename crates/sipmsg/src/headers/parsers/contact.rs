@@ -6,6 +6,7 @@ use crate::{
         traits::SipHeaderParser,
     },
 };
+use alloc::borrow::Cow;
 
 /*
 Contact        =  ("Contact" / "m" ) HCOLON
@@ -46,7 +47,7 @@ pub struct Contact;
 
 fn make_star_value(source_input: &[u8]) -> nom::IResult<&[u8], HeaderValue, SipParseError> {
     let mut tags = HeaderTags::new();
-    tags.insert(HeaderTagType::Star, &source_input[..1]);
+    tags.insert(HeaderTagType::Star, Cow::Borrowed(&source_input[..1]));
     let (input, _) = take_sws_token::star(source_input)?;
     let (_, hdr_val) = HeaderValue::new(
         &source_input[..1],
@@ -60,7 +61,7 @@ fn make_star_value(source_input: &[u8]) -> nom::IResult<&[u8], HeaderValue, SipP
 impl SipHeaderParser for Contact {
     fn take_value(source_input: &[u8]) -> nom::IResult<&[u8], HeaderValue, SipParseError> {
         if source_input.is_empty() {
-            return sip_parse_error!(1, "Contact header value is empty");
+            return sip_parse_error!(InvalidHeaderValue, "Contact header value is empty");
         }
 
         if source_input[0] == b'*' {
@@ -74,6 +75,21 @@ impl SipHeaderParser for Contact {
     }
 }
 
+impl Contact {
+    /// Lenient parser, invalid UTF-8 of display name is replaced by U+FFFD
+    pub fn take_value_lossy<'a>(
+        source_input: &'a [u8],
+    ) -> nom::IResult<&'a [u8], HeaderValue<'a>, SipParseError<'a>> {
+        if !source_input.is_empty() && source_input[0] == b'*' {
+            return make_star_value(source_input);
+        }
+        let (input, (vstr_val, tags, sipuri)) = name_addr::take_lossy(source_input)?;
+        let (_, hdr_val) =
+            HeaderValue::new_lossy(vstr_val, HeaderValueType::NameAddr, Some(tags), sipuri)?;
+        Ok((input, hdr_val))
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -81,7 +97,7 @@ mod test {
     #[test]
     fn contact_parser_test() {
         let (_, val) = Contact::take_value("* \r\n".as_bytes()).unwrap();
-        assert_eq!(val.tags().unwrap()[&HeaderTagType::Star], b"*");
+        assert_eq!(val.tags().unwrap()[&HeaderTagType::Star], "*".as_bytes());
 
         let (input, val) = Contact::take_value(
             "\"Mr. Watson\"  <sip:watson@worcester.bell-telephone.com> ;q=0.7; expires=3600 \r\n"
@@ -90,7 +106,7 @@ mod test {
         .unwrap();
         assert_eq!(
             val.tags().unwrap()[&HeaderTagType::DisplayName],
-            b"Mr. Watson"
+            "Mr. Watson".as_bytes()
         );
         assert_eq!(val.sip_uri().unwrap().scheme, sipuri::RequestUriScheme::SIP);
         assert_eq!(val.sip_uri().unwrap().user_info().unwrap().value, "watson");
@@ -116,7 +132,10 @@ mod test {
         assert_eq!(input, b";expires=60 \r\n");
         /*---------------------------------------------*/
         let (_, val) = Contact::take_value("\"\" <sip:carol@chicago.com> \r\n".as_bytes()).unwrap();
-        assert_eq!(val.tags().unwrap()[&HeaderTagType::DisplayName], b"");
+        assert_eq!(
+            val.tags().unwrap()[&HeaderTagType::DisplayName],
+            "".as_bytes()
+        );
         assert_eq!(val.sip_uri().unwrap().user_info().unwrap().value, "carol");
         /*---------------------------------------------*/
 
@@ -127,7 +146,7 @@ mod test {
 
         assert_eq!(
             val.tags().unwrap()[&HeaderTagType::DisplayName],
-            b"Mr. Watson"
+            "Mr. Watson".as_bytes()
         );
         assert_eq!(val.sip_uri().unwrap().user_info().unwrap().value, "watson");
         assert_eq!(
@@ -150,7 +169,7 @@ mod test {
 
         assert_eq!(
             val.sip_uri().unwrap().params().unwrap().get(&"line"),
-            Some(&Some("12071"))
+            Some(Some("12071"))
         );
         assert_eq!(
             input,
@@ -159,7 +178,10 @@ mod test {
         /*---------------------------------------------*/
         let (_, val) =
             Contact::take_value("Caller <mailto:carol@chicago.com> \r\n".as_bytes()).unwrap();
-        assert_eq!(val.tags().unwrap()[&HeaderTagType::DisplayName], b"Caller");
+        assert_eq!(
+            val.tags().unwrap()[&HeaderTagType::DisplayName],
+            "Caller".as_bytes()
+        );
         assert_eq!(
             val.tags().unwrap()[&HeaderTagType::AbsoluteURI],
             "mailto:carol@chicago.com".as_bytes()
@@ -167,7 +189,10 @@ mod test {
         assert_eq!(val.vstr, "Caller <mailto:carol@chicago.com>");
 
         let (_, val) = Contact::take_value("A <sip:carol@chicago.com> \r\n".as_bytes()).unwrap();
-        assert_eq!(val.tags().unwrap()[&HeaderTagType::DisplayName], b"A");
+        assert_eq!(
+            val.tags().unwrap()[&HeaderTagType::DisplayName],
+            "A".as_bytes()
+        );
         assert_eq!(val.sip_uri().unwrap().hostport.host, "chicago.com");
         assert_eq!(val.sip_uri().unwrap().user_info().unwrap().value, "carol");
         /*---------------------------------------------*/
@@ -179,7 +204,10 @@ mod test {
         let (inp, val) =
             Contact::take_value("\"Caller\" <sip:caller@[2001:db8::20]> \r\n".as_bytes()).unwrap();
         assert_eq!(val.sip_uri().unwrap().scheme, sipuri::RequestUriScheme::SIP);
-        assert_eq!(val.tags().unwrap()[&HeaderTagType::DisplayName], b"Caller");
+        assert_eq!(
+            val.tags().unwrap()[&HeaderTagType::DisplayName],
+            "Caller".as_bytes()
+        );
         assert_eq!(val.sip_uri().unwrap().user_info().unwrap().value, "caller");
         assert_eq!(val.sip_uri().unwrap().hostport.host, "2001:db8::20");
         assert_eq!(inp, "\r\n".as_bytes());
@@ -208,7 +236,7 @@ mod test {
                 .params()
                 .unwrap()
                 .get(&"unknownparam"),
-            Some(&None)
+            Some(None)
         );
 
         assert_eq!(inp, "\r\n".as_bytes());
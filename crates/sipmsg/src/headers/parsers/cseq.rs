@@ -7,6 +7,7 @@ use crate::headers::{
     header::{HeaderTagType, HeaderTags, HeaderValue, HeaderValueType},
     traits::SipHeaderParser,
 };
+use alloc::borrow::Cow;
 
 use nom::bytes::complete::take_while1;
 
@@ -19,8 +20,8 @@ impl SipHeaderParser for CSeq {
         let (input, number) = take_while1(is_digit)(source_input)?;
         let (input, _) = take_sws(input)?;
         let (input, method) = take_while1(is_token_char)(input)?;
-        tags.insert(HeaderTagType::Number, number);
-        tags.insert(HeaderTagType::Method, method);
+        tags.insert(HeaderTagType::Number, Cow::Borrowed(number));
+        tags.insert(HeaderTagType::Method, Cow::Borrowed(method));
 
         let (_, hdr_val) = HeaderValue::new(
             &source_input[..source_input.len() - input.len()],
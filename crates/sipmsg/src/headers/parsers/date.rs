@@ -65,24 +65,24 @@ impl SipHeaderParser for Date {
     fn take_value(source_input: &[u8]) -> nom::IResult<&[u8], HeaderValue, SipParseError> {
         let (input, wday) = take(3usize)(source_input)?;
         if !Date::is_wkday(wday) {
-            return sip_parse_error!(1, "Invalid wday value in Date header");
+            return sip_parse_error!(InvalidHeaderValue, "Invalid wday value in Date header");
         }
         let (input, _) = tag(", ")(input)?;
         let (input, day) = digit1(input)?;
         if day.len() > 2 {
-            return sip_parse_error!(1, "Invalid day value in Date header");
+            return sip_parse_error!(InvalidHeaderValue, "Invalid day value in Date header");
         }
         let (input, _) = char(' ')(input)?;
 
         let (input, month) = take(3usize)(input)?;
         if !Date::is_month(month) {
-            return sip_parse_error!(2, "Invalid month value in Date header");
+            return sip_parse_error!(InvalidHeaderValue, "Invalid month value in Date header");
         }
         let (input, _) = char(' ')(input)?;
         let (input, year) = digit1(input)?;
         if year.len() != 4 {
             // time bomb :D
-            return sip_parse_error!(3, "Invalid year value in Date header");
+            return sip_parse_error!(InvalidHeaderValue, "Invalid year value in Date header");
         }
         let (input, _) = char(' ')(input)?;
         let (input, (_hours, _, _mins, _, _seconds)) =
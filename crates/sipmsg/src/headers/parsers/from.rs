@@ -17,3 +17,15 @@ impl SipHeaderParser for From {
         Ok((input, hdr_val))
     }
 }
+
+impl From {
+    /// Lenient parser, invalid UTF-8 of display name is replaced by U+FFFD
+    pub fn take_value_lossy<'a>(
+        source_input: &'a [u8],
+    ) -> nom::IResult<&'a [u8], HeaderValue<'a>, SipParseError<'a>> {
+        let (input, (vstr_val, tags, sipuri)) = name_addr::take_lossy(source_input)?;
+        let (_, hdr_val) =
+            HeaderValue::new_lossy(vstr_val, HeaderValueType::NameAddr, Some(tags), sipuri)?;
+        Ok((input, hdr_val))
+    }
+}
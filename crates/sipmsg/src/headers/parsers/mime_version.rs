@@ -2,9 +2,10 @@ use crate::{
     common::{bnfcore::is_digit, errorparse::SipParseError},
     headers::{
         header::{HeaderTagType, HeaderTags, HeaderValue, HeaderValueType},
-        traits::SipHeaderParser
-    }
+        traits::SipHeaderParser,
+    },
 };
+use alloc::borrow::Cow;
 use nom::bytes::complete::take_while1;
 
 pub struct MimeVersion;
@@ -15,8 +16,8 @@ impl SipHeaderParser for MimeVersion {
         let (inp, _) = nom::character::complete::char('.')(inp)?;
         let (inp, minor) = take_while1(is_digit)(inp)?;
         let mut tags = HeaderTags::new();
-        tags.insert(HeaderTagType::Major, major);
-        tags.insert(HeaderTagType::Minor, minor);
+        tags.insert(HeaderTagType::Major, Cow::Borrowed(major));
+        tags.insert(HeaderTagType::Minor, Cow::Borrowed(minor));
         let (_, hdr_val) = HeaderValue::new(
             &source_input[..source_input.len() - inp.len()],
             HeaderValueType::Digit,
@@ -36,14 +37,7 @@ mod test {
         let (input, val) = MimeVersion::take_value("1.2 \r\n".as_bytes()).unwrap();
         assert_eq!(input, " \r\n".as_bytes());
         assert_eq!(val.vstr, "1.2");
-        assert_eq!(
-            val.tags().unwrap()[&HeaderTagType::Major],
-            "1".as_bytes()
-        );
-        assert_eq!(
-            val.tags().unwrap()[&HeaderTagType::Minor],
-            "2".as_bytes()
-        );
+        assert_eq!(val.tags().unwrap()[&HeaderTagType::Major], "1".as_bytes());
+        assert_eq!(val.tags().unwrap()[&HeaderTagType::Minor], "2".as_bytes());
     }
 }
-
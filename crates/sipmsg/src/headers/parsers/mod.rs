@@ -10,6 +10,8 @@ mod extension;
 pub use extension::ExtensionParser;
 mod authentication_info;
 pub use authentication_info::AuthenticationInfoParser;
+mod authenticate;
+pub use authenticate::Authenticate;
 mod authorization;
 pub use authorization::Authorization;
 mod callid;
@@ -22,6 +24,10 @@ mod date;
 pub use date::Date;
 mod from;
 pub use from::From;
+mod privacy;
+pub use privacy::Privacy;
+mod rack;
+pub use rack::RAck;
 mod retry_after;
 pub use retry_after::RetryAfter;
 mod user_agent;
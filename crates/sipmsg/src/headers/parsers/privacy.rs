@@ -0,0 +1,51 @@
+use crate::common::{bnfcore::is_token_char, errorparse::SipParseError, take_sws_token};
+use crate::headers::{
+    header::{HeaderValue, HeaderValueType},
+    traits::SipHeaderParser,
+};
+
+use nom::bytes::complete::take_while1;
+
+/// Privacy-hdr  =  "Privacy" HCOLON priv-value *(";" priv-value)
+/// ([rfc3323](https://tools.ietf.org/html/rfc3323#section-4.2)).
+/// Values are separated by semicolon, so they are not taken as header parameters
+pub struct Privacy;
+
+impl SipHeaderParser for Privacy {
+    fn take_value(source_input: &[u8]) -> nom::IResult<&[u8], HeaderValue<'_>, SipParseError<'_>> {
+        let (mut input, _) = take_while1(is_token_char)(source_input)?;
+        while let Ok((rest, _)) = take_sws_token::semi(input) {
+            match take_while1::<_, _, SipParseError>(is_token_char)(rest) {
+                Ok((rest, _)) => input = rest,
+                Err(_) => break,
+            }
+        }
+        let (_, hdr_val) = HeaderValue::new(
+            &source_input[..source_input.len() - input.len()],
+            HeaderValueType::TokenList,
+            None,
+            None,
+        )?;
+        Ok((input, hdr_val))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_privacy_value() {
+        let (input, val) = Privacy::take_value("id\r\n".as_bytes()).unwrap();
+        assert_eq!(input, b"\r\n");
+        assert_eq!(val.vstr, "id");
+
+        let (input, val) = Privacy::take_value("header ; user;critical\r\n".as_bytes()).unwrap();
+        assert_eq!(input, b"\r\n");
+        assert_eq!(val.vstr, "header ; user;critical");
+
+        let (input, val) = Privacy::take_value("id;\r\n".as_bytes()).unwrap();
+        assert_eq!(input, b";\r\n");
+        assert_eq!(val.vstr, "id");
+    }
+}
@@ -0,0 +1,61 @@
+use crate::common::nom_wrappers::take_lws;
+use crate::common::{
+    bnfcore::{is_digit, is_token_char},
+    errorparse::SipParseError,
+};
+use crate::headers::{
+    header::{HeaderTagType, HeaderTags, HeaderValue, HeaderValueType},
+    traits::SipHeaderParser,
+};
+use alloc::borrow::Cow;
+
+use nom::bytes::complete::take_while1;
+
+/// RAck  =  "RAck" HCOLON response-num LWS CSeq-num LWS Method
+/// ([rfc3262](https://tools.ietf.org/html/rfc3262#section-10))
+pub struct RAck;
+
+impl SipHeaderParser for RAck {
+    fn take_value(source_input: &[u8]) -> nom::IResult<&[u8], HeaderValue<'_>, SipParseError<'_>> {
+        let mut tags = HeaderTags::new();
+        let (input, response_num) = take_while1(is_digit)(source_input)?;
+        let (input, _) = take_lws(input)?;
+        let (input, number) = take_while1(is_digit)(input)?;
+        let (input, _) = take_lws(input)?;
+        let (input, method) = take_while1(is_token_char)(input)?;
+        tags.insert(HeaderTagType::ResponseNum, Cow::Borrowed(response_num));
+        tags.insert(HeaderTagType::Number, Cow::Borrowed(number));
+        tags.insert(HeaderTagType::Method, Cow::Borrowed(method));
+
+        let (_, hdr_val) = HeaderValue::new(
+            &source_input[..source_input.len() - input.len()],
+            HeaderValueType::RAck,
+            Some(tags),
+            None,
+        )?;
+        Ok((input, hdr_val))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_rack_value() {
+        let (input, val) = RAck::take_value("776656 1 INVITE\r\n".as_bytes()).unwrap();
+        assert_eq!(input, "\r\n".as_bytes());
+        assert_eq!(val.vstr, "776656 1 INVITE");
+        assert_eq!(
+            val.tags().unwrap()[&HeaderTagType::ResponseNum],
+            "776656".as_bytes()
+        );
+        assert_eq!(val.tags().unwrap()[&HeaderTagType::Number], "1".as_bytes());
+        assert_eq!(
+            val.tags().unwrap()[&HeaderTagType::Method],
+            "INVITE".as_bytes()
+        );
+
+        assert!(RAck::take_value("776656 INVITE\r\n".as_bytes()).is_err());
+    }
+}
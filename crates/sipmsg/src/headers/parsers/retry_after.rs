@@ -7,6 +7,7 @@ use crate::{
         traits::SipHeaderParser,
     },
 };
+use alloc::borrow::Cow;
 use nom::bytes::complete::{take_until, take_while1};
 
 pub struct RetryAfter;
@@ -16,12 +17,12 @@ impl SipHeaderParser for RetryAfter {
         let (input, seconds) = take_while1(is_digit)(source_input)?;
         let (input, _) = take_sws(input)?;
         let mut tags = HeaderTags::new();
-        tags.insert(HeaderTagType::Seconds, seconds);
+        tags.insert(HeaderTagType::Seconds, Cow::Borrowed(seconds));
         if !input.is_empty() && input[0] == b'(' {
             let (input, _) = take_sws_token::lparen(input)?;
             let (input, comment) = take_until(")")(input)?;
             let input = &input[1..]; // skio )
-            tags.insert(HeaderTagType::Comment, comment);
+            tags.insert(HeaderTagType::Comment, Cow::Borrowed(comment));
             let (_, hdr_val) = HeaderValue::new(
                 &source_input[..source_input.len() - input.len()],
                 HeaderValueType::RetryAfter,
@@ -5,6 +5,21 @@ use crate::{
 use nom::bytes::complete::take_until;
 
 pub fn take(source_input: &[u8]) -> nom::IResult<&[u8], HeaderValue, SipParseError> {
+    let (input, value) = take_text(source_input)?;
+    let (_, hdr_val) = HeaderValue::new(value, HeaderValueType::Utf8Text, None, None)?;
+    Ok((input, hdr_val))
+}
+
+/// Lenient parser, invalid UTF-8 is replaced by U+FFFD
+pub fn take_lossy<'a>(
+    source_input: &'a [u8],
+) -> nom::IResult<&'a [u8], HeaderValue<'a>, SipParseError<'a>> {
+    let (input, value) = take_text(source_input)?;
+    let (_, hdr_val) = HeaderValue::new_lossy(value, HeaderValueType::Utf8Text, None, None)?;
+    Ok((input, hdr_val))
+}
+
+fn take_text<'a>(source_input: &'a [u8]) -> nom::IResult<&'a [u8], &'a [u8], SipParseError<'a>> {
     let mut tmp_input = source_input;
     loop {
         let (input, _) = take_until("\r\n")(tmp_input)?;
@@ -16,19 +31,24 @@ pub fn take(source_input: &[u8]) -> nom::IResult<&[u8], HeaderValue, SipParseErr
         tmp_input = input;
         break;
     }
-    let (_, hdr_val) = HeaderValue::new(
+    Ok((
+        tmp_input,
         &source_input[..source_input.len() - tmp_input.len()],
-        HeaderValueType::Utf8Text,
-        None,
-        None,
-    )?;
-    Ok((tmp_input, hdr_val))
+    ))
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
 
+    #[test]
+    fn test_utf8text_lossy_value() {
+        assert!(take(b"Bo\xffxes\r\n").is_err());
+        let (input, val) = take_lossy(b"Bo\xffxes\r\n").unwrap();
+        assert_eq!(val.vstr, "Bo\u{FFFD}xes");
+        assert_eq!(input, b"\r\n");
+    }
+
     #[test]
     fn test_utf8text_value() {
         let (input, val) = take("Boxes by Bob\r\n".as_bytes()).unwrap();
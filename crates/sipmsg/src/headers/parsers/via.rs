@@ -8,6 +8,7 @@ use crate::{
         traits::SipHeaderParser,
     },
 };
+use alloc::borrow::Cow;
 
 use nom::bytes::complete::take_while1;
 
@@ -41,12 +42,18 @@ impl SipHeaderParser for Via {
         let (input, _) = take_lws(input)?;
         let (input, (host, port)) = HostPort::take_hostport(input)?;
         let mut tags = HeaderTags::new();
-        tags.insert(HeaderTagType::ProtocolName, protocol_name);
-        tags.insert(HeaderTagType::ProtocolVersion, protocol_version);
-        tags.insert(HeaderTagType::ProtocolTransport, protocol_transport);
-        tags.insert(HeaderTagType::Host, host);
+        tags.insert(HeaderTagType::ProtocolName, Cow::Borrowed(protocol_name));
+        tags.insert(
+            HeaderTagType::ProtocolVersion,
+            Cow::Borrowed(protocol_version),
+        );
+        tags.insert(
+            HeaderTagType::ProtocolTransport,
+            Cow::Borrowed(protocol_transport),
+        );
+        tags.insert(HeaderTagType::Host, Cow::Borrowed(host));
         if port != None {
-            tags.insert(HeaderTagType::Port, port.unwrap());
+            tags.insert(HeaderTagType::Port, Cow::Borrowed(port.unwrap()));
         }
 
         let (_, hdr_val) = HeaderValue::new(
@@ -70,16 +77,22 @@ mod test {
                 .unwrap();
         assert_eq!(val.vstr, "SIP/2.0/UDP bobspc.biloxi.com:5060");
         assert_eq!(input, b";received=192.0.2.4\r\n");
-        assert_eq!(val.tags().unwrap()[&HeaderTagType::ProtocolName], b"SIP");
-        assert_eq!(val.tags().unwrap()[&HeaderTagType::ProtocolVersion], b"2.0");
+        assert_eq!(
+            val.tags().unwrap()[&HeaderTagType::ProtocolName],
+            "SIP".as_bytes()
+        );
+        assert_eq!(
+            val.tags().unwrap()[&HeaderTagType::ProtocolVersion],
+            "2.0".as_bytes()
+        );
         assert_eq!(
             val.tags().unwrap()[&HeaderTagType::ProtocolTransport],
-            b"UDP"
+            "UDP".as_bytes()
         );
         assert_eq!(
             val.tags().unwrap()[&HeaderTagType::Host],
-            b"bobspc.biloxi.com"
+            "bobspc.biloxi.com".as_bytes()
         );
-        assert_eq!(val.tags().unwrap()[&HeaderTagType::Port], b"5060");
+        assert_eq!(val.tags().unwrap()[&HeaderTagType::Port], "5060".as_bytes());
     }
 }
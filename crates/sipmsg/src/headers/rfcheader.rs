@@ -1,14 +1,16 @@
 use crate::headers::{
     parsers::{
         digit_header, token_header, utf8_trim_header, AcceptEncodingParser, AcceptLanguageParser,
-        AcceptParser, AlertInfoParser, AuthenticationInfoParser, Authorization, CSeq, CallID,
-        Contact, Date, From, MimeVersion, RetryAfter, Timestamp, UserAgent, Via, Warning,
+        AcceptParser, AlertInfoParser, Authenticate, AuthenticationInfoParser, Authorization, CSeq,
+        CallID, Contact, Date, From, MimeVersion, Privacy, RAck, RetryAfter, Timestamp, UserAgent,
+        Via, Warning,
     },
     traits::{HeaderValueParserFn, SipHeaderParser},
 };
+use crate::parser_config::{ParseMode, ParserConfig};
 use unicase::Ascii;
 
-/// Headers that defined in rfc3261
+/// Headers that defined in rfc3261 and its extensions
 #[derive(Copy, Clone, PartialEq, Debug, PartialOrd, Ord, Eq)]
 pub enum SipRFCHeader {
     Accept,
@@ -16,6 +18,8 @@ pub enum SipRFCHeader {
     AcceptLanguage,
     AlertInfo,
     Allow,
+    /// [rfc6665](https://tools.ietf.org/html/rfc6665)
+    AllowEvents,
     AuthenticationInfo,
     Authorization,
     CallID,
@@ -29,25 +33,49 @@ pub enum SipRFCHeader {
     CSeq,
     Date,
     ErrorInfo,
+    /// [rfc6665](https://tools.ietf.org/html/rfc6665)
+    Event,
     Expires,
     From,
     InReplyTo,
+    /// [rfc3911](https://tools.ietf.org/html/rfc3911)
+    Join,
     MaxForwards,
     MimeVersion,
     MinExpires,
     Organization,
+    /// [rfc3325](https://tools.ietf.org/html/rfc3325)
+    PAssertedIdentity,
+    /// [rfc3325](https://tools.ietf.org/html/rfc3325)
+    PPreferredIdentity,
     Priority,
+    /// [rfc3323](https://tools.ietf.org/html/rfc3323)
+    Privacy,
     ProxyAuthenticate,
     ProxyAuthorization,
     ProxyRequire,
+    /// [rfc3262](https://tools.ietf.org/html/rfc3262)
+    RAck,
     RecordRoute,
+    /// [rfc3515](https://tools.ietf.org/html/rfc3515)
+    ReferTo,
+    /// [rfc3892](https://tools.ietf.org/html/rfc3892)
+    ReferredBy,
+    /// [rfc3891](https://tools.ietf.org/html/rfc3891)
+    Replaces,
     ReplyTo,
     Require,
     RetryAfter,
     Route,
+    /// [rfc3262](https://tools.ietf.org/html/rfc3262)
+    RSeq,
     Server,
     Subject,
+    /// [rfc6665](https://tools.ietf.org/html/rfc6665)
+    SubscriptionState,
     Supported,
+    /// [rfc4538](https://tools.ietf.org/html/rfc4538)
+    TargetDialog,
     Timestamp,
     To,
     Unsupported,
@@ -74,6 +102,8 @@ impl SipRFCHeader {
         match_str!("Accept-Language", SipRFCHeader::AcceptLanguage);
         match_str!("Alert-Info", SipRFCHeader::AlertInfo);
         match_str!("Allow", SipRFCHeader::Allow);
+        match_str!("Allow-Events", SipRFCHeader::AllowEvents);
+        match_str!("u", SipRFCHeader::AllowEvents);
         match_str!("Authentication-Info", SipRFCHeader::AuthenticationInfo);
         match_str!("Authorization", SipRFCHeader::Authorization);
         match_str!("Call-ID", SipRFCHeader::CallID);
@@ -92,28 +122,43 @@ impl SipRFCHeader {
         match_str!("CSeq", SipRFCHeader::CSeq);
         match_str!("Date", SipRFCHeader::Date);
         match_str!("Error-Info", SipRFCHeader::ErrorInfo);
+        match_str!("Event", SipRFCHeader::Event);
+        match_str!("o", SipRFCHeader::Event);
         match_str!("Expires", SipRFCHeader::Expires);
         match_str!("From", SipRFCHeader::From);
         match_str!("f", SipRFCHeader::From);
         match_str!("In-Reply-To", SipRFCHeader::InReplyTo);
+        match_str!("Join", SipRFCHeader::Join);
         match_str!("Max-Forwards", SipRFCHeader::MaxForwards);
         match_str!("MIME-Version", SipRFCHeader::MimeVersion);
         match_str!("Min-Expires", SipRFCHeader::MinExpires);
         match_str!("Organization", SipRFCHeader::Organization);
+        match_str!("P-Asserted-Identity", SipRFCHeader::PAssertedIdentity);
+        match_str!("P-Preferred-Identity", SipRFCHeader::PPreferredIdentity);
         match_str!("Priority", SipRFCHeader::Priority);
+        match_str!("Privacy", SipRFCHeader::Privacy);
         match_str!("Proxy-Authenticate", SipRFCHeader::ProxyAuthenticate);
         match_str!("Proxy-Authorization", SipRFCHeader::ProxyAuthorization);
         match_str!("Proxy-Require", SipRFCHeader::ProxyRequire);
+        match_str!("RAck", SipRFCHeader::RAck);
         match_str!("Record-Route", SipRFCHeader::RecordRoute);
+        match_str!("Refer-To", SipRFCHeader::ReferTo);
+        match_str!("r", SipRFCHeader::ReferTo);
+        match_str!("Referred-By", SipRFCHeader::ReferredBy);
+        match_str!("b", SipRFCHeader::ReferredBy);
+        match_str!("Replaces", SipRFCHeader::Replaces);
         match_str!("Reply-To", SipRFCHeader::ReplyTo);
         match_str!("Require", SipRFCHeader::Require);
         match_str!("Retry-After", SipRFCHeader::RetryAfter);
         match_str!("Route", SipRFCHeader::Route);
+        match_str!("RSeq", SipRFCHeader::RSeq);
         match_str!("Server", SipRFCHeader::Server);
         match_str!("Subject", SipRFCHeader::Subject);
         match_str!("s", SipRFCHeader::Subject);
+        match_str!("Subscription-State", SipRFCHeader::SubscriptionState);
         match_str!("Supported", SipRFCHeader::Supported);
         match_str!("k", SipRFCHeader::Supported);
+        match_str!("Target-Dialog", SipRFCHeader::TargetDialog);
         match_str!("Timestamp", SipRFCHeader::Timestamp);
         match_str!("To", SipRFCHeader::To);
         match_str!("t", SipRFCHeader::To);
@@ -126,13 +171,14 @@ impl SipRFCHeader {
         None
     }
 
-    pub fn as_str(&self) -> &str {
+    pub fn as_str(&self) -> &'static str {
         match self {
             &SipRFCHeader::Accept => "Accept",
             &SipRFCHeader::AcceptEncoding => "Accept-Encoding",
             &SipRFCHeader::AcceptLanguage => "Accept-Language",
             &SipRFCHeader::AlertInfo => "Alert-Info",
             &SipRFCHeader::Allow => "Allow",
+            &SipRFCHeader::AllowEvents => "Allow-Events",
             &SipRFCHeader::AuthenticationInfo => "Authentication-Info",
             &SipRFCHeader::Authorization => "Authorization",
             &SipRFCHeader::CallID => "Call-ID",
@@ -146,25 +192,37 @@ impl SipRFCHeader {
             &SipRFCHeader::CSeq => "CSeq",
             &SipRFCHeader::Date => "Date",
             &SipRFCHeader::ErrorInfo => "Error-Info",
+            &SipRFCHeader::Event => "Event",
             &SipRFCHeader::Expires => "Expires",
             &SipRFCHeader::From => "From",
             &SipRFCHeader::InReplyTo => "In-Reply-To",
+            &SipRFCHeader::Join => "Join",
             &SipRFCHeader::MaxForwards => "Max-Forwards",
             &SipRFCHeader::MimeVersion => "MIME-Version",
             &SipRFCHeader::MinExpires => "Min-Expires",
             &SipRFCHeader::Organization => "Organization",
+            &SipRFCHeader::PAssertedIdentity => "P-Asserted-Identity",
+            &SipRFCHeader::PPreferredIdentity => "P-Preferred-Identity",
             &SipRFCHeader::Priority => "Priority",
+            &SipRFCHeader::Privacy => "Privacy",
             &SipRFCHeader::ProxyAuthenticate => "Proxy-Authenticate",
             &SipRFCHeader::ProxyAuthorization => "Proxy-Authorization",
             &SipRFCHeader::ProxyRequire => "Proxy-Require",
+            &SipRFCHeader::RAck => "RAck",
             &SipRFCHeader::RecordRoute => "Record-Route",
+            &SipRFCHeader::ReferTo => "Refer-To",
+            &SipRFCHeader::ReferredBy => "Referred-By",
+            &SipRFCHeader::Replaces => "Replaces",
             &SipRFCHeader::ReplyTo => "Reply-To",
             &SipRFCHeader::Require => "Require",
             &SipRFCHeader::RetryAfter => "Retry-After",
             &SipRFCHeader::Route => "Route",
+            &SipRFCHeader::RSeq => "RSeq",
             &SipRFCHeader::Server => "Server",
             &SipRFCHeader::Subject => "Subject",
+            &SipRFCHeader::SubscriptionState => "Subscription-State",
             &SipRFCHeader::Supported => "Supported",
+            &SipRFCHeader::TargetDialog => "Target-Dialog",
             &SipRFCHeader::Timestamp => "Timestamp",
             &SipRFCHeader::To => "To",
             &SipRFCHeader::Unsupported => "Unsupported",
@@ -175,13 +233,34 @@ impl SipRFCHeader {
         }
     }
 
-    pub fn get_parser(&self) -> HeaderValueParserFn {
+    /// Parser of header value. In lenient mode display names
+    /// and UTF-8 text values are parsed with replacement of invalid UTF-8
+    pub fn get_parser(&self, config: &ParserConfig) -> HeaderValueParserFn {
+        if config.mode == ParseMode::Lenient {
+            match self {
+                &SipRFCHeader::Contact => return Contact::take_value_lossy,
+                &SipRFCHeader::From
+                | &SipRFCHeader::To
+                | &SipRFCHeader::PAssertedIdentity
+                | &SipRFCHeader::PPreferredIdentity
+                | &SipRFCHeader::RecordRoute
+                | &SipRFCHeader::ReferTo
+                | &SipRFCHeader::ReferredBy
+                | &SipRFCHeader::Route
+                | &SipRFCHeader::ReplyTo => return From::take_value_lossy,
+                &SipRFCHeader::Organization | &SipRFCHeader::Subject => {
+                    return utf8_trim_header::take_lossy
+                }
+                _ => {}
+            }
+        }
         match self {
             &SipRFCHeader::Accept => AcceptParser::take_value,
             &SipRFCHeader::AcceptEncoding => AcceptEncodingParser::take_value,
             &SipRFCHeader::AcceptLanguage => AcceptLanguageParser::take_value,
             &SipRFCHeader::AlertInfo => AlertInfoParser::take_value,
             &SipRFCHeader::Allow => token_header::take,
+            &SipRFCHeader::AllowEvents => token_header::take,
             &SipRFCHeader::AuthenticationInfo => AuthenticationInfoParser::take_value,
             &SipRFCHeader::Authorization => Authorization::take_value,
             &SipRFCHeader::CallID => CallID::take_value,
@@ -195,32 +274,44 @@ impl SipRFCHeader {
             &SipRFCHeader::CSeq => CSeq::take_value,
             &SipRFCHeader::Date => Date::take_value,
             &SipRFCHeader::ErrorInfo => AlertInfoParser::take_value,
+            &SipRFCHeader::Event => token_header::take,
             &SipRFCHeader::Expires => digit_header::take,
             &SipRFCHeader::From => From::take_value,
             &SipRFCHeader::To => From::take_value,
             &SipRFCHeader::InReplyTo => CallID::take_value,
+            &SipRFCHeader::Join => CallID::take_value,
             &SipRFCHeader::MaxForwards => digit_header::take,
             &SipRFCHeader::Organization => utf8_trim_header::take,
+            &SipRFCHeader::PAssertedIdentity => From::take_value,
+            &SipRFCHeader::PPreferredIdentity => From::take_value,
             &SipRFCHeader::Priority => token_header::take,
-            &SipRFCHeader::ProxyAuthenticate => Authorization::take_value,
+            &SipRFCHeader::Privacy => Privacy::take_value,
+            &SipRFCHeader::ProxyAuthenticate => Authenticate::take_value,
             &SipRFCHeader::ProxyAuthorization => Authorization::take_value,
             &SipRFCHeader::ProxyRequire => token_header::take,
+            &SipRFCHeader::RAck => RAck::take_value,
             &SipRFCHeader::RecordRoute => From::take_value,
+            &SipRFCHeader::ReferTo => From::take_value,
+            &SipRFCHeader::ReferredBy => From::take_value,
+            &SipRFCHeader::Replaces => CallID::take_value,
             &SipRFCHeader::Route => From::take_value,
             &SipRFCHeader::ReplyTo => From::take_value,
             &SipRFCHeader::Require => token_header::take,
             &SipRFCHeader::RetryAfter => RetryAfter::take_value,
+            &SipRFCHeader::RSeq => digit_header::take,
             &SipRFCHeader::Server => UserAgent::take_value,
             &SipRFCHeader::UserAgent => UserAgent::take_value,
             &SipRFCHeader::Subject => utf8_trim_header::take,
+            &SipRFCHeader::SubscriptionState => token_header::take,
             &SipRFCHeader::Supported => token_header::take,
+            &SipRFCHeader::TargetDialog => CallID::take_value,
             &SipRFCHeader::MimeVersion => MimeVersion::take_value,
             &SipRFCHeader::MinExpires => digit_header::take,
             &SipRFCHeader::Timestamp => Timestamp::take_value,
             &SipRFCHeader::Unsupported => token_header::take,
             &SipRFCHeader::Via => Via::take_value,
             &SipRFCHeader::Warning => Warning::take_value,
-            &SipRFCHeader::WWWAuthenticate => Authorization::take_value,
+            &SipRFCHeader::WWWAuthenticate => Authenticate::take_value,
         }
     }
 }
@@ -245,6 +336,30 @@ mod tests {
             SipRFCHeader::ContentLength
         );
 
+        assert_eq!(SipRFCHeader::from_str("rack").unwrap(), SipRFCHeader::RAck);
+        assert_eq!(SipRFCHeader::from_str("RSeq").unwrap(), SipRFCHeader::RSeq);
+
+        assert_eq!(SipRFCHeader::from_str("o").unwrap(), SipRFCHeader::Event);
+        assert_eq!(
+            SipRFCHeader::from_str("U").unwrap(),
+            SipRFCHeader::AllowEvents
+        );
+        assert_eq!(
+            SipRFCHeader::from_str("subscription-state").unwrap(),
+            SipRFCHeader::SubscriptionState
+        );
+
+        assert_eq!(SipRFCHeader::from_str("r").unwrap(), SipRFCHeader::ReferTo);
+        assert_eq!(
+            SipRFCHeader::from_str("B").unwrap(),
+            SipRFCHeader::ReferredBy
+        );
+
+        assert_eq!(
+            SipRFCHeader::from_str("p-asserted-identity").unwrap(),
+            SipRFCHeader::PAssertedIdentity
+        );
+
         assert_eq!(SipRFCHeader::from_str("1"), None);
     }
 
@@ -1,12 +1,19 @@
 use crate::{
-    common::bnfcore::is_unreserved, common::hostport::HostPort,
-    common::nom_wrappers::from_utf8_nom, common::nom_wrappers::take_while_with_escaped,
-    errorparse::SipParseError, headers::GenericParams, userinfo::UserInfo,
+    common::bnfcore::is_hnv_char,
+    common::escape::{unescape_str, unescape_unreserved},
+    common::hostport::HostPort,
+    common::nom_wrappers::from_utf8_nom,
+    common::nom_wrappers::take_while_with_escaped,
+    common::sip_method::SipMethod,
+    errorparse::SipParseError,
+    headers::uri_params::{is_maddr, parse_ttl, Transport, UriParams, UserParam},
+    headers::GenericParams,
+    userinfo::UserInfo,
 };
-use alloc::collections::btree_map::BTreeMap;
+use alloc::{borrow::Cow, collections::btree_map::BTreeMap};
 use nom::bytes::complete::{take, take_till, take_until};
 
-use core::str;
+use core::{fmt, net::SocketAddr, str};
 
 #[derive(Copy, Clone, PartialEq, Debug)]
 pub enum RequestUriScheme {
@@ -16,23 +23,30 @@ pub enum RequestUriScheme {
 
 impl RequestUriScheme {
     pub fn from_bytes(s: &[u8]) -> Result<RequestUriScheme, nom::Err<SipParseError>> {
-        match s {
-            b"sip" => Ok(Self::SIP),
-            b"sips" => Ok(Self::SIPS),
-            _ => sip_parse_error!(101, "Can't parse sipuri scheme"),
+        // scheme is case-insensitive
+        if s.eq_ignore_ascii_case(b"sip") {
+            Ok(Self::SIP)
+        } else if s.eq_ignore_ascii_case(b"sips") {
+            Ok(Self::SIPS)
+        } else {
+            sip_parse_error!(InvalidUri, "Can't parse sipuri scheme")
         }
     }
-}
 
-/// hnv-unreserved  =  "[" / "]" / "/" / "?" / ":" / "+" / "$"
-#[inline]
-fn is_hnv_unreserved_char(c: u8) -> bool {
-    c == b'[' || c == b']' || c == b'/' || c == b'?' || c == b':' || c == b'+' || c == b'$'
-}
+    /// 5060 for `sip`, 5061 for `sips`
+    pub fn default_port(&self) -> u16 {
+        match self {
+            RequestUriScheme::SIP => 5060,
+            RequestUriScheme::SIPS => 5061,
+        }
+    }
 
-#[inline]
-fn is_hnv_char(c: u8) -> bool {
-    is_unreserved(c) || is_hnv_unreserved_char(c)
+    pub fn as_str(&self) -> &str {
+        match self {
+            RequestUriScheme::SIP => "sip",
+            RequestUriScheme::SIPS => "sips",
+        }
+    }
 }
 
 // header          =  hname "=" hvalue
@@ -40,8 +54,8 @@ fn is_hnv_char(c: u8) -> bool {
 // hvalue          =  *( hnv-unreserved / unreserved / escaped )
 // headers         =  "?" header *( "&" header )
 pub struct SipUriHeader<'a> {
-    pub name: &'a str,
-    pub value: &'a str,
+    pub name: Cow<'a, str>,
+    pub value: Cow<'a, str>,
 }
 
 impl<'a> SipUriHeader<'a> {
@@ -52,8 +66,8 @@ impl<'a> SipUriHeader<'a> {
             return Ok((
                 input,
                 SipUriHeader {
-                    name: hname_str,
-                    value: "",
+                    name: Cow::Borrowed(hname_str),
+                    value: Cow::Borrowed(""),
                 },
             ));
         }
@@ -66,16 +80,16 @@ impl<'a> SipUriHeader<'a> {
         Ok((
             input,
             SipUriHeader {
-                name: hname_str,
-                value: hvalue_str,
+                name: Cow::Borrowed(hname_str),
+                value: Cow::Borrowed(hvalue_str),
             },
         ))
     }
 
-    fn parse(input: &'a [u8]) -> nom::IResult<&[u8], BTreeMap<&'a str, &'a str>, SipParseError> {
+    fn parse(input: &'a [u8]) -> nom::IResult<&[u8], SipUriHeaders<'a>, SipParseError> {
         let (input, c) = take(1usize)(input)?;
         if c[0] != b'?' {
-            return sip_parse_error!(1, "The first character of headers must be '?'");
+            return sip_parse_error!(InvalidUri, "The first character of headers must be '?'");
         }
 
         let mut result = BTreeMap::new();
@@ -93,9 +107,10 @@ impl<'a> SipUriHeader<'a> {
 
         Ok((inp2, result))
     }
-    
 }
 
+pub type SipUriHeaders<'a> = BTreeMap<Cow<'a, str>, Cow<'a, str>>;
+
 // URI  =  SIP-URI / SIPS-URI
 // SIP-URI          =  "sip:" [ userinfo ] hostport
 // uri-parameters [ headers ]
@@ -104,15 +119,14 @@ impl<'a> SipUriHeader<'a> {
 // userinfo         =  ( user / telephone-subscriber ) [ ":" password ] "@"
 // hostport         =  host [ ":" port ]
 /// Its general form, in the case of a SIP URI, is: sip:user:password@host:port;uri-parameters?headers
-#[derive(PartialEq, Debug)]
+#[derive(Clone, PartialEq, Debug)]
 pub struct SipUri<'a> {
     pub scheme: RequestUriScheme,
     user_info: Option<UserInfo<'a>>,
     pub hostport: HostPort<'a>,
-    // Temporary use parsing from generic-parameters.rs
-    // TODO make according RFC
+    /// Known uri-parameters are validated by parser
     parameters: Option<GenericParams<'a>>,
-    headers: Option<BTreeMap<&'a str, &'a str>>,
+    headers: Option<SipUriHeaders<'a>>,
 }
 
 impl<'a> SipUri<'a> {
@@ -124,29 +138,130 @@ impl<'a> SipUri<'a> {
         self.parameters.as_ref()
     }
 
-    pub fn headers(&self) -> Option<&BTreeMap<&'a str, &'a str>> {
+    /// Typed view of known uri-parameters
+    pub fn uri_params(&self) -> UriParams<'_> {
+        match &self.parameters {
+            // parameters are validated when URI is parsed
+            Some(params) => UriParams::from_params(params).unwrap_or_default(),
+            None => UriParams::default(),
+        }
+    }
+
+    fn param(&self, name: &str) -> Option<Option<&str>> {
+        self.parameters.as_ref().and_then(|p| p.get(name))
+    }
+
+    /// Value of `transport` parameter
+    pub fn transport(&self) -> Option<Transport<'_>> {
+        self.param("transport")
+            .flatten()
+            .and_then(Transport::from_str)
+    }
+
+    /// Value of `user` parameter
+    pub fn user_param(&self) -> Option<UserParam<'_>> {
+        self.param("user").flatten().and_then(UserParam::from_str)
+    }
+
+    /// Value of `method` parameter
+    pub fn method_param(&self) -> Option<SipMethod<'_>> {
+        self.param("method").flatten().and_then(SipMethod::from_str)
+    }
+
+    /// Value of `ttl` parameter
+    pub fn ttl(&self) -> Option<u8> {
+        self.param("ttl").flatten().and_then(parse_ttl)
+    }
+
+    /// Value of `maddr` parameter
+    pub fn maddr(&self) -> Option<&str> {
+        self.param("maddr")
+            .flatten()
+            .filter(|maddr| is_maddr(maddr))
+    }
+
+    /// Returns `true` if `lr` parameter is present
+    pub fn lr(&self) -> bool {
+        self.param("lr").is_some()
+    }
+
+    /// Value of `gr` parameter, `Some(None)` if it is present without value
+    pub fn gr(&self) -> Option<Option<&str>> {
+        self.param("gr")
+    }
+
+    /// Returns `true` if `ob` parameter is present
+    pub fn ob(&self) -> bool {
+        self.param("ob").is_some()
+    }
+
+    /// Port that is used if it is absent in URI ([rfc3263](https://tools.ietf.org/html/rfc3263#section-4.2)).
+    /// Depends on transport parameter, `sips` URI uses TLS over any transport
+    pub fn default_port(&self) -> u16 {
+        match (self.scheme, self.transport()) {
+            (RequestUriScheme::SIPS, Some(Transport::Ws)) | (_, Some(Transport::Wss)) => 443,
+            (RequestUriScheme::SIPS, _) => 5061,
+            (RequestUriScheme::SIP, Some(transport)) => transport.default_port(),
+            (RequestUriScheme::SIP, None) => 5060,
+        }
+    }
+
+    pub fn port_or_default(&self) -> u16 {
+        self.hostport.port.unwrap_or_else(|| self.default_port())
+    }
+
+    /// Socket address if host is IPv4 or IPv6 address
+    pub fn socket_addr(&self) -> Option<SocketAddr> {
+        self.hostport.socket_addr(self.default_port())
+    }
+
+    pub fn headers(&self) -> Option<&SipUriHeaders<'a>> {
         self.headers.as_ref()
     }
 
+    /// Value of URI header with escaped characters decoded.
+    /// Header name is compared case-insensitively after decoding
+    pub fn decoded_header(&self, name: &str) -> Option<Cow<'_, str>> {
+        self.headers
+            .as_ref()?
+            .iter()
+            .find(|(hname, _)| unescape_str(hname).eq_ignore_ascii_case(name))
+            .map(|(_, hvalue)| unescape_str(hvalue))
+    }
+
+    pub fn into_owned(self) -> SipUri<'static> {
+        SipUri {
+            scheme: self.scheme,
+            user_info: self.user_info.map(|u| u.into_owned()),
+            hostport: self.hostport.into_owned(),
+            parameters: self.parameters.map(|p| p.into_owned()),
+            headers: self.headers.map(|hdrs| {
+                hdrs.into_iter()
+                    .map(|(k, v)| (Cow::Owned(k.into_owned()), Cow::Owned(v.into_owned())))
+                    .collect()
+            }),
+        }
+    }
+
     fn try_parse_params(
         input: &'a [u8],
     ) -> nom::IResult<&[u8], Option<GenericParams<'a>>, SipParseError> {
         if input[0] != b';' {
             return Ok((input, None));
         }
-        match GenericParams::parse(input) {
-            Ok((input, params)) => {
-                return Ok((input, Some(params)));
-            }
-            Err(e) => {
-                return Err(e);
-            }
+        let (rest, params) = GenericParams::parse(input)?;
+        if let Err(nom::Err::Error(e)) | Err(nom::Err::Failure(e)) = UriParams::from_params(&params)
+        {
+            return Err(nom::Err::Error(
+                SipParseError::new(e.kind, e.message).at(input),
+            ));
         }
+        Ok((rest, Some(params)))
     }
 
     fn try_parse_headers(
         input: &'a [u8],
-    ) -> nom::IResult<&[u8], Option<BTreeMap<&'a str, &'a str>>, SipParseError> {
+    ) -> nom::IResult<&[u8], Option<SipUriHeaders<'a>>, SipParseError> {
         if input[0] != b'?' {
             return Ok((input, None));
         }
@@ -236,11 +351,136 @@ impl<'a> SipUri<'a> {
     pub fn parse(input: &'a [u8]) -> nom::IResult<&[u8], SipUri<'a>, SipParseError> {
         SipUri::parse_ext(input, true)
     }
+
+    /// Compares URIs according to
+    /// [rfc3261 section-19.1.4](https://tools.ietf.org/html/rfc3261#section-19.1.4).
+    /// Escaped unreserved characters are compared as unescaped,
+    /// escaped reserved characters differ from the characters themselves.
+    ///
+    /// ```rust
+    /// use sipmsg::SipUri;
+    ///
+    /// let (_, a) = SipUri::parse(b"sip:%61lice@atlanta.com;transport=TCP").unwrap();
+    /// let (_, b) = SipUri::parse(b"sip:alice@AtLanTa.CoM;Transport=tcp").unwrap();
+    /// assert!(a.equivalent(&b));
+    ///
+    /// let (_, c) = SipUri::parse(b"sip:alice@atlanta.com:5060;transport=tcp").unwrap();
+    /// assert!(!a.equivalent(&c));
+    /// ```
+    pub fn equivalent(&self, other: &SipUri) -> bool {
+        if self.scheme != other.scheme {
+            return false;
+        }
+
+        // userinfo is case-sensitive
+        match (&self.user_info, &other.user_info) {
+            (None, None) => {}
+            (Some(a), Some(b)) => {
+                if unescape_unreserved(&a.value) != unescape_unreserved(&b.value)
+                    || a.password.as_deref().map(unescape_unreserved)
+                        != b.password.as_deref().map(unescape_unreserved)
+                {
+                    return false;
+                }
+            }
+            _ => return false,
+        }
+
+        // port is not replaced by default value
+        if !self.hostport.host.equivalent(&other.hostport.host)
+            || self.hostport.port != other.hostport.port
+        {
+            return false;
+        }
+
+        SipUri::params_equivalent(self.params(), other.params())
+            && SipUri::headers_equivalent(self.headers(), other.headers())
+    }
+
+    /// Parameters present in both URIs must match,
+    /// parameters that must be present in both URIs or absent in both are
+    /// user, ttl, method, maddr and transport
+    fn params_equivalent(a: Option<&GenericParams>, b: Option<&GenericParams>) -> bool {
+        const REQUIRED: [&str; 5] = ["user", "ttl", "method", "maddr", "transport"];
+        let contains = |params: Option<&GenericParams>, name: &str| match params {
+            Some(params) => params.contains(name),
+            None => false,
+        };
+        for name in REQUIRED.iter() {
+            if contains(a, name) != contains(b, name) {
+                return false;
+            }
+        }
+
+        let (a, b) = match (a, b) {
+            (Some(a), Some(b)) => (a, b),
+            _ => return true,
+        };
+        for name in a.keys() {
+            let b_value = match b.get(name) {
+                Some(value) => value,
+                None => continue,
+            };
+            let a_value = a.get(name).unwrap_or(None);
+            let equal = match (a_value, b_value) {
+                (None, None) => true,
+                (Some(a_value), Some(b_value)) => {
+                    unescape_unreserved(a_value).eq_ignore_ascii_case(&unescape_unreserved(b_value))
+                }
+                _ => false,
+            };
+            if !equal {
+                return false;
+            }
+        }
+        true
+    }
+
+    /// Headers must be present in both URIs and match
+    fn headers_equivalent(a: Option<&SipUriHeaders>, b: Option<&SipUriHeaders>) -> bool {
+        let (a, b) = match (a, b) {
+            (None, None) => return true,
+            (Some(a), Some(b)) => (a, b),
+            (Some(hdrs), None) | (None, Some(hdrs)) => return hdrs.is_empty(),
+        };
+        if a.len() != b.len() {
+            return false;
+        }
+        a.iter().all(|(a_name, a_value)| {
+            b.iter().any(|(b_name, b_value)| {
+                unescape_unreserved(a_name).eq_ignore_ascii_case(&unescape_unreserved(b_name))
+                    && unescape_unreserved(a_value) == unescape_unreserved(b_value)
+            })
+        })
+    }
+}
+
+impl<'a> fmt::Display for SipUri<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}:", self.scheme.as_str())?;
+        if let Some(user_info) = &self.user_info {
+            write!(f, "{}@", user_info)?;
+        }
+        write!(f, "{}", self.hostport)?;
+        if let Some(params) = &self.parameters {
+            write!(f, "{}", params)?;
+        }
+        if let Some(headers) = &self.headers {
+            let mut separator = '?';
+            for (name, value) in headers {
+                write!(f, "{}{}={}", separator, name, value)?;
+                separator = '&';
+            }
+        }
+        Ok(())
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::errorparse::ParseErrorKind;
+    use alloc::string::ToString;
 
     #[test]
     fn test_sip_uri_parse() {
@@ -270,12 +510,15 @@ mod tests {
         assert_eq!(rest.len(), 0);
         assert_eq!(sip_uri.scheme, RequestUriScheme::SIP);
         assert_eq!(sip_uri.user_info().unwrap().value, "alice");
-        assert_eq!(sip_uri.user_info().unwrap().password, Some("secretword"));
+        assert_eq!(
+            sip_uri.user_info().unwrap().password.as_deref(),
+            Some("secretword")
+        );
         assert_eq!(sip_uri.hostport.host, "atlanta.com");
         assert_eq!(sip_uri.hostport.port, None);
         assert_eq!(
             sip_uri.params().unwrap().get(&"transport"),
-            Some(&Some("tcp"))
+            Some(Some("tcp"))
         );
 
         let (rest, sip_uri) = SipUri::parse_ext(
@@ -286,10 +529,13 @@ mod tests {
         assert_eq!(rest.len(), 0);
         assert_eq!(sip_uri.scheme, RequestUriScheme::SIP);
         assert_eq!(sip_uri.user_info().unwrap().value, "+1-212-555-1212");
-        assert_eq!(sip_uri.user_info().unwrap().password, Some("1234"));
+        assert_eq!(
+            sip_uri.user_info().unwrap().password.as_deref(),
+            Some("1234")
+        );
         assert_eq!(sip_uri.hostport.host, "gateway.com");
         assert_eq!(sip_uri.hostport.port, None);
-        assert_eq!(sip_uri.params().unwrap().get(&"user"), Some(&Some("phone")));
+        assert_eq!(sip_uri.params().unwrap().get(&"user"), Some(Some("phone")));
 
         let (rest, sip_uri) = SipUri::parse_ext("sips:1212@gateway.com".as_bytes(), true).unwrap();
         assert_eq!(rest.len(), 0);
@@ -318,11 +564,8 @@ mod tests {
         )
         .unwrap();
         assert_eq!(rest.len(), 0);
-        assert_eq!(
-            sip_uri.headers().unwrap().get(&"subject"),
-            Some(&"project%20x")
-        );
-        assert_eq!(sip_uri.headers().unwrap().get(&"priority"), Some(&"urgent"));
+        assert_eq!(sip_uri.headers().unwrap()["subject"], "project%20x");
+        assert_eq!(sip_uri.headers().unwrap()["priority"], "urgent");
         assert_eq!(sip_uri.scheme, RequestUriScheme::SIPS);
         assert_eq!(sip_uri.user_info().unwrap().value, "alice");
         assert_eq!(sip_uri.hostport.host, "atlanta.com");
@@ -333,13 +576,10 @@ mod tests {
         )
         .unwrap();
         assert_eq!(rest.len(), 0);
-        assert_eq!(
-            sip_uri.headers().unwrap().get(&"to"),
-            Some(&"alice%40atlanta.com")
-        );
+        assert_eq!(sip_uri.headers().unwrap()["to"], "alice%40atlanta.com");
         assert_eq!(
             sip_uri.params().unwrap().get(&"method"),
-            Some(&Some("REGISTER"))
+            Some(Some("REGISTER"))
         );
         assert_eq!(sip_uri.scheme, RequestUriScheme::SIP);
         assert_eq!(sip_uri.hostport.host, "atlanta.com");
@@ -351,11 +591,8 @@ mod tests {
         )
         .unwrap();
         //   assert_eq!(rest.len(), 0);
-        assert_eq!(
-            sip_uri.headers().unwrap().get(&"subject"),
-            Some(&"project%20x")
-        );
-        assert_eq!(sip_uri.headers().unwrap().get(&"priority"), Some(&"urgent"));
+        assert_eq!(sip_uri.headers().unwrap()["subject"], "project%20x");
+        assert_eq!(sip_uri.headers().unwrap()["priority"], "urgent");
         assert_eq!(sip_uri.user_info().unwrap().value, "alice");
         assert_eq!(sip_uri.scheme, RequestUriScheme::SIPS);
         assert_eq!(sip_uri.hostport.host, "atlanta.com");
@@ -364,4 +601,214 @@ mod tests {
 
         assert_eq!(rest, b" ;transport=tcp");
     }
+
+    #[test]
+    fn test_sip_uri_to_string() {
+        let to_string = |uri: &str| {
+            let (_, sip_uri) = SipUri::parse(uri.as_bytes()).unwrap();
+            sip_uri.to_string()
+        };
+        assert_eq!(to_string("sip:atlanta.com"), "sip:atlanta.com");
+        assert_eq!(
+            to_string("sips:alice:secret@atlanta.com:5061;transport=tcp;lr"),
+            "sips:alice:secret@atlanta.com:5061;transport=tcp;lr"
+        );
+        assert_eq!(
+            to_string("sip:[2001:db8::10]:5070;maddr=[2001:db8::20]"),
+            "sip:[2001:db8::10]:5070;maddr=[2001:db8::20]"
+        );
+        assert_eq!(
+            to_string("sip:bob@biloxi.com?subject=project%20x&priority=urgent"),
+            "sip:bob@biloxi.com?priority=urgent&subject=project%20x"
+        );
+    }
+
+    fn equivalent(a: &str, b: &str) -> bool {
+        let (_, a) = SipUri::parse(a.as_bytes()).unwrap();
+        let (_, b) = SipUri::parse(b.as_bytes()).unwrap();
+        a.equivalent(&b) && b.equivalent(&a)
+    }
+
+    #[test]
+    fn test_sip_uri_equivalent() {
+        // Examples of rfc3261 section-19.1.4
+        assert!(equivalent(
+            "sip:%61lice@atlanta.com;transport=TCP",
+            "sip:alice@AtLanTa.CoM;Transport=tcp"
+        ));
+        assert!(equivalent(
+            "sip:carol@chicago.com",
+            "sip:carol@chicago.com;newparam=5"
+        ));
+        assert!(equivalent(
+            "sip:carol@chicago.com;security=on",
+            "sip:carol@chicago.com;newparam=5"
+        ));
+        assert!(equivalent(
+            "sip:biloxi.com;transport=tcp;method=REGISTER?to=sip:bob%40biloxi.com",
+            "sip:biloxi.com;method=REGISTER;transport=tcp?to=sip:bob%40biloxi.com"
+        ));
+        assert!(equivalent(
+            "sip:alice@atlanta.com?subject=project%20x&priority=urgent",
+            "sip:alice@atlanta.com?priority=urgent&subject=project%20x"
+        ));
+
+        assert!(!equivalent(
+            "SIP:ALICE@AtLanTa.CoM;Transport=udp",
+            "sip:alice@AtLanTa.CoM;Transport=UDP"
+        ));
+        assert!(!equivalent("sip:bob@biloxi.com", "sip:bob@biloxi.com:5060"));
+        assert!(!equivalent(
+            "sip:bob@biloxi.com",
+            "sip:bob@biloxi.com;transport=udp"
+        ));
+        assert!(!equivalent(
+            "sip:bob@biloxi.com",
+            "sip:bob@biloxi.com:6000;transport=tcp"
+        ));
+        assert!(!equivalent(
+            "sip:carol@chicago.com",
+            "sip:carol@chicago.com?Subject=next%20meeting"
+        ));
+        assert!(!equivalent(
+            "sip:bob@phone21.boxesbybob.com",
+            "sip:bob@192.0.2.4"
+        ));
+
+        assert!(!equivalent("sip:bob@biloxi.com", "sips:bob@biloxi.com"));
+        assert!(!equivalent(
+            "sip:bob:pass@biloxi.com",
+            "sip:bob:Pass@biloxi.com"
+        ));
+        assert!(!equivalent("sip:biloxi.com", "sip:bob@biloxi.com"));
+        assert!(!equivalent("sip:biloxi.com;user=phone", "sip:biloxi.com"));
+        assert!(!equivalent("sip:biloxi.com;ttl=1", "sip:biloxi.com;ttl=2"));
+        assert!(!equivalent("sip:biloxi.com;lr", "sip:biloxi.com;lr=on"));
+        assert!(equivalent("sip:biloxi.com;lr", "sip:biloxi.com;LR"));
+        assert!(!equivalent(
+            "sip:biloxi.com?subject=a",
+            "sip:biloxi.com?subject=A"
+        ));
+        // escaped reserved characters are not equal to unescaped
+        assert!(!equivalent(
+            "sip:alice;day=tuesday@atlanta.com",
+            "sip:alice%3Bday=tuesday@atlanta.com"
+        ));
+        assert!(equivalent(
+            "sip:alice%3bday=tuesday@atlanta.com",
+            "sip:alice%3Bday=tuesday@atlanta.com"
+        ));
+        assert!(!equivalent(
+            "sip:biloxi.com;x=a%3Db",
+            "sip:biloxi.com;x=a=b"
+        ));
+        assert!(!equivalent(
+            "sip:biloxi.com?to=sip:bob@biloxi.com",
+            "sip:biloxi.com?to=sip:bob%40biloxi.com"
+        ));
+    }
+
+    #[test]
+    fn test_sip_uri_scheme_case() {
+        let (_, sip_uri) = SipUri::parse(b"SIP:ALICE@AtLanTa.CoM;Transport=udp").unwrap();
+        assert_eq!(sip_uri.scheme, RequestUriScheme::SIP);
+        assert_eq!(sip_uri.user_info().unwrap().value, "ALICE");
+        let (_, sip_uri) = SipUri::parse(b"SiPs:bob@biloxi.com").unwrap();
+        assert_eq!(sip_uri.scheme, RequestUriScheme::SIPS);
+        assert_eq!(sip_uri.to_string(), "sips:bob@biloxi.com");
+    }
+
+    #[test]
+    fn test_sip_uri_decoded() {
+        let (_, sip_uri) = SipUri::parse(
+            b"sip:alice%40atlanta.com@biloxi.com;x=a%20b?Subject=project%20x&%74o=bob%40biloxi.com",
+        )
+        .unwrap();
+        assert_eq!(
+            sip_uri.user_info().unwrap().decoded_user(),
+            "alice@atlanta.com"
+        );
+        assert_eq!(
+            sip_uri.params().unwrap().get_decoded("x"),
+            Some(Some(Cow::Borrowed("a b")))
+        );
+        assert_eq!(sip_uri.params().unwrap().get_decoded("y"), None);
+        assert_eq!(sip_uri.decoded_header("subject").unwrap(), "project x");
+        assert_eq!(sip_uri.decoded_header("To").unwrap(), "bob@biloxi.com");
+        assert_eq!(sip_uri.decoded_header("priority"), None);
+    }
+
+    #[test]
+    fn test_sip_uri_typed_params() {
+        let (_, sip_uri) = SipUri::parse(
+            b"sip:alice@atlanta.com;transport=TCP;user=phone;method=INVITE;ttl=255;maddr=[::1];lr;gr=x;ob",
+        )
+        .unwrap();
+        assert_eq!(sip_uri.transport(), Some(Transport::Tcp));
+        assert_eq!(sip_uri.user_param(), Some(UserParam::Phone));
+        assert_eq!(sip_uri.method_param(), Some(SipMethod::INVITE));
+        assert_eq!(sip_uri.ttl(), Some(255));
+        assert_eq!(sip_uri.maddr(), Some("::1"));
+        assert!(sip_uri.lr());
+        assert_eq!(sip_uri.gr(), Some(Some("x")));
+        assert!(sip_uri.ob());
+        assert_eq!(sip_uri.uri_params().ttl, Some(255));
+
+        let (_, sip_uri) = SipUri::parse(b"sip:atlanta.com").unwrap();
+        assert_eq!(sip_uri.transport(), None);
+        assert_eq!(sip_uri.ttl(), None);
+        assert!(!sip_uri.lr());
+        assert_eq!(sip_uri.gr(), None);
+        assert_eq!(sip_uri.uri_params(), UriParams::default());
+
+        for uri in [
+            "sip:alice@atlanta.com;ttl=300",
+            "sip:alice@atlanta.com;maddr",
+            "sip:alice@atlanta.com;transport",
+        ]
+        .iter()
+        {
+            match SipUri::parse(uri.as_bytes()) {
+                Err(nom::Err::Error(e)) => assert_eq!(e.kind, ParseErrorKind::InvalidParameter),
+                _ => panic!("{} is accepted", uri),
+            }
+        }
+        // parameters of URI in angle brackets belong to URI
+        let buf = b"SIP/2.0 200 OK\r\nContact: <sip:alice@atlanta.com;ttl=256>\r\n\r\n";
+        let err = match crate::SipMessage::parse(buf) {
+            Err(nom::Err::Error(e)) => e,
+            _ => panic!(),
+        };
+        assert_eq!(err.kind, ParseErrorKind::InvalidParameter);
+        assert_eq!(err.header, Some("Contact"));
+        assert_eq!(err.offset, Some(47));
+    }
+
+    #[test]
+    fn test_sip_uri_default_port() {
+        let port = |uri: &str| SipUri::parse(uri.as_bytes()).unwrap().1.port_or_default();
+        assert_eq!(port("sip:atlanta.com"), 5060);
+        assert_eq!(port("sip:atlanta.com:5080"), 5080);
+        assert_eq!(port("sips:atlanta.com"), 5061);
+        assert_eq!(port("sips:atlanta.com;transport=tcp"), 5061);
+        assert_eq!(port("sip:atlanta.com;transport=tls"), 5061);
+        assert_eq!(port("sip:atlanta.com;transport=ws"), 80);
+        assert_eq!(port("sips:atlanta.com;transport=ws"), 443);
+        assert_eq!(port("sip:atlanta.com;transport=wss"), 443);
+
+        let (_, sip_uri) = SipUri::parse(b"sips:alice@192.0.2.4").unwrap();
+        assert_eq!(
+            sip_uri.socket_addr(),
+            Some("192.0.2.4:5061".parse().unwrap())
+        );
+        let (_, sip_uri) = SipUri::parse(b"sip:alice@[2001:db8::1]:5070").unwrap();
+        assert_eq!(
+            sip_uri.socket_addr(),
+            Some("[2001:db8::1]:5070".parse().unwrap())
+        );
+        assert_eq!(
+            SipUri::parse(b"sip:atlanta.com").unwrap().1.socket_addr(),
+            None
+        );
+    }
 }
@@ -0,0 +1,234 @@
+use crate::{
+    common::{bnfcore::is_hexdig, errorparse::SipParseError, nom_wrappers::from_utf8_nom},
+    headers::GenericParams,
+};
+use alloc::{borrow::Cow, string::String};
+use core::fmt;
+use nom::bytes::complete::take_while1;
+
+/// visual-separator = "-" / "." / "(" / ")"
+#[inline]
+fn is_visual_separator(c: u8) -> bool {
+    c == b'-' || c == b'.' || c == b'(' || c == b')'
+}
+
+/// phonedigit-hex = HEXDIG / "*" / "#" / [ visual-separator ]
+#[inline]
+fn is_phonedigit_hex(c: u8) -> bool {
+    is_hexdig(c) || c == b'*' || c == b'#' || is_visual_separator(c)
+}
+
+fn remove_visual_separators(number: &str) -> String {
+    number
+        .chars()
+        .filter(|c| !c.is_ascii() || !is_visual_separator(*c as u8))
+        .collect()
+}
+
+/// [rfc3966](https://tools.ietf.org/html/rfc3966) telephone URI.
+/// Ex: `tel:+1-201-555-0123`, `tel:7042;phone-context=example.com`
+///
+/// ```rust
+/// use sipmsg::TelUri;
+///
+/// let (_, uri) = TelUri::parse(b"tel:+1-201-555-0123;ext=1234").unwrap();
+/// assert!(uri.is_global());
+/// assert_eq!(uri.number, "+1-201-555-0123");
+/// assert_eq!(uri.ext(), Some("1234"));
+/// assert_eq!(uri.to_e164().unwrap(), "+12015550123");
+///
+/// let (_, uri) = TelUri::parse(b"tel:555-0123;phone-context=+1-201").unwrap();
+/// assert!(!uri.is_global());
+/// assert_eq!(uri.phone_context(), Some("+1-201"));
+/// assert_eq!(uri.to_e164().unwrap(), "+12015550123");
+/// ```
+#[derive(Clone, PartialEq, Debug)]
+pub struct TelUri<'a> {
+    /// global-number-digits with leading "+" or local-number-digits,
+    /// visual separators are kept as is
+    pub number: Cow<'a, str>,
+    /// isdn-subaddress, extension, context and other parameters
+    parameters: Option<GenericParams<'a>>,
+}
+
+impl<'a> TelUri<'a> {
+    pub fn params(&self) -> Option<&GenericParams<'a>> {
+        self.parameters.as_ref()
+    }
+
+    fn param(&self, name: &str) -> Option<&str> {
+        self.parameters.as_ref().and_then(|p| p.get(name)).flatten()
+    }
+
+    /// global-number starts with "+", local-number requires phone-context
+    pub fn is_global(&self) -> bool {
+        self.number.starts_with('+')
+    }
+
+    /// Value of `phone-context` parameter: global number prefix or domain name
+    pub fn phone_context(&self) -> Option<&str> {
+        self.param("phone-context")
+    }
+
+    /// Value of `isub` parameter
+    pub fn isub(&self) -> Option<&str> {
+        self.param("isub")
+    }
+
+    /// Value of `ext` parameter
+    pub fn ext(&self) -> Option<&str> {
+        self.param("ext")
+    }
+
+    /// Number without visual separators. Leading "+" of global number is kept
+    pub fn normalized_number(&self) -> String {
+        remove_visual_separators(&self.number)
+    }
+
+    /// Returns number in E.164 format (`+` and up to 15 digits).
+    /// Local number is prefixed by phone-context if it is a global number prefix.
+    /// `None` if number can't be presented in E.164
+    pub fn to_e164(&self) -> Option<String> {
+        let result = if self.is_global() {
+            self.normalized_number()
+        } else {
+            let context = self.phone_context()?;
+            if !context.starts_with('+') {
+                return None;
+            }
+            let mut result = remove_visual_separators(context);
+            result.push_str(&self.normalized_number());
+            result
+        };
+        let digits = &result[1..];
+        if digits.is_empty() || digits.len() > 15 || !digits.bytes().all(|c| c.is_ascii_digit()) {
+            return None;
+        }
+        Some(result)
+    }
+
+    /// Returns `true` if number is global and contains only digits (up to 15) and separators
+    pub fn is_e164(&self) -> bool {
+        self.is_global() && self.to_e164().is_some()
+    }
+
+    pub fn into_owned(self) -> TelUri<'static> {
+        TelUri {
+            number: Cow::Owned(self.number.into_owned()),
+            parameters: self.parameters.map(|p| p.into_owned()),
+        }
+    }
+
+    /// telephone-uri = "tel:" telephone-subscriber.
+    /// If `parse_with_parameters` is false, parameters are left in input,
+    /// it is needed for name-addr without "<>" where parameters belong to header
+    pub fn parse_ext(
+        input: &'a [u8],
+        parse_with_parameters: bool,
+    ) -> nom::IResult<&'a [u8], TelUri<'a>, SipParseError<'a>> {
+        if input.len() < 4 || !input[..4].eq_ignore_ascii_case(b"tel:") {
+            return sip_parse_error!(InvalidUri, "tel URI must start with 'tel:'");
+        }
+        let input = &input[4..];
+        let (input, number) = if !input.is_empty() && input[0] == b'+' {
+            let (rest, digits) =
+                take_while1(|c: u8| c.is_ascii_digit() || is_visual_separator(c))(&input[1..])?;
+            (rest, &input[..digits.len() + 1])
+        } else {
+            take_while1(is_phonedigit_hex)(input)?
+        };
+        if !number
+            .iter()
+            .any(|c| *c != b'+' && !is_visual_separator(*c))
+        {
+            return sip_parse_error!(InvalidUri, "tel URI number has no digits");
+        }
+        let (_, number) = from_utf8_nom(number)?;
+
+        let (input, parameters) = if parse_with_parameters && !input.is_empty() && input[0] == b';'
+        {
+            let (input, params) = GenericParams::parse(input)?;
+            (input, Some(params))
+        } else {
+            (input, None)
+        };
+
+        let uri = TelUri {
+            number: Cow::Borrowed(number),
+            parameters,
+        };
+        if parse_with_parameters && !uri.is_global() && uri.phone_context().is_none() {
+            return sip_parse_error!(InvalidUri, "Local number requires phone-context");
+        }
+        Ok((input, uri))
+    }
+
+    pub fn parse(input: &'a [u8]) -> nom::IResult<&'a [u8], TelUri<'a>, SipParseError<'a>> {
+        TelUri::parse_ext(input, true)
+    }
+}
+
+impl<'a> fmt::Display for TelUri<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "tel:{}", self.number)?;
+        if let Some(params) = &self.parameters {
+            write!(f, "{}", params)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::ToString;
+
+    #[test]
+    fn tel_uri_parse() {
+        let (rest, uri) = TelUri::parse(b"tel:+1-201-555-0123>").unwrap();
+        assert_eq!(rest, b">");
+        assert!(uri.is_global());
+        assert_eq!(uri.number, "+1-201-555-0123");
+        assert_eq!(uri.normalized_number(), "+12015550123");
+        assert!(uri.is_e164());
+        assert_eq!(uri.params(), None);
+
+        let (rest, uri) =
+            TelUri::parse(b"TEL:7042;phone-context=example.com;isub=1411;foo=bar").unwrap();
+        assert_eq!(rest.len(), 0);
+        assert!(!uri.is_global());
+        assert_eq!(uri.number, "7042");
+        assert_eq!(uri.phone_context(), Some("example.com"));
+        assert_eq!(uri.isub(), Some("1411"));
+        assert_eq!(uri.ext(), None);
+        assert_eq!(uri.params().unwrap().get("foo"), Some(Some("bar")));
+        assert_eq!(uri.to_e164(), None);
+        assert!(!uri.is_e164());
+
+        let (_, uri) = TelUri::parse(b"tel:*23#;phone-context=+1").unwrap();
+        assert_eq!(uri.number, "*23#");
+        assert_eq!(uri.to_e164(), None);
+
+        let (rest, uri) = TelUri::parse_ext(b"tel:+358-555-1234567;tag=abc", false).unwrap();
+        assert_eq!(rest, b";tag=abc");
+        assert_eq!(uri.to_e164().unwrap(), "+3585551234567");
+    }
+
+    #[test]
+    fn tel_uri_parse_fail() {
+        assert!(TelUri::parse(b"sip:+1-201-555-0123").is_err());
+        assert!(TelUri::parse(b"tel:+").is_err());
+        assert!(TelUri::parse(b"tel:--").is_err());
+        assert!(TelUri::parse(b"tel:7042").is_err());
+        assert_eq!(
+            TelUri::parse(b"tel:+1234567890123456").unwrap().1.to_e164(),
+            None
+        );
+    }
+
+    #[test]
+    fn tel_uri_to_string() {
+        let (_, uri) = TelUri::parse(b"tel:+1-201-555-0123;ext=1234;isub=8765").unwrap();
+        assert_eq!(uri.to_string(), "tel:+1-201-555-0123;ext=1234;isub=8765");
+    }
+}
@@ -0,0 +1,255 @@
+use crate::{
+    common::{errorparse::SipParseError, escape::unquote},
+    headers::{
+        auth_params::take_auth_value,
+        header::Header,
+        typed::{invalid_value, TypedHeader},
+    },
+};
+use alloc::{borrow::Cow, vec::Vec};
+use core::{fmt, str};
+use unicase::Ascii;
+
+/// auth-scheme of challenge or credentials, compared case-insensitively
+#[derive(Clone, PartialEq, Debug)]
+pub enum AuthScheme<'a> {
+    /// [rfc3261 section-22.4](https://tools.ietf.org/html/rfc3261#section-22.4)
+    Digest,
+    /// [rfc7617](https://tools.ietf.org/html/rfc7617), legacy equipment only
+    Basic,
+    /// [rfc8898](https://tools.ietf.org/html/rfc8898)
+    Bearer,
+    Other(Cow<'a, str>),
+}
+
+impl<'a> AuthScheme<'a> {
+    pub fn from_str(scheme: &'a str) -> AuthScheme<'a> {
+        let ascheme = Ascii::new(scheme);
+        if ascheme == "Digest" {
+            AuthScheme::Digest
+        } else if ascheme == "Basic" {
+            AuthScheme::Basic
+        } else if ascheme == "Bearer" {
+            AuthScheme::Bearer
+        } else {
+            AuthScheme::Other(Cow::Borrowed(scheme))
+        }
+    }
+
+    pub fn as_str(&self) -> &str {
+        match self {
+            AuthScheme::Digest => "Digest",
+            AuthScheme::Basic => "Basic",
+            AuthScheme::Bearer => "Bearer",
+            AuthScheme::Other(scheme) => scheme,
+        }
+    }
+
+    pub fn into_owned(self) -> AuthScheme<'static> {
+        match self {
+            AuthScheme::Digest => AuthScheme::Digest,
+            AuthScheme::Basic => AuthScheme::Basic,
+            AuthScheme::Bearer => AuthScheme::Bearer,
+            AuthScheme::Other(scheme) => AuthScheme::Other(Cow::Owned(scheme.into_owned())),
+        }
+    }
+}
+
+impl<'a> fmt::Display for AuthScheme<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// Challenge of WWW-Authenticate or Proxy-Authenticate header.
+/// Header with several challenges is parsed to several header values,
+/// use [`get_typed_all`](crate::SipHeaders::get_typed_all) to decode all of them.
+/// Parameters of [rfc3261 section-25.1](https://tools.ietf.org/html/rfc3261#section-25.1)
+/// `digest-cln` are decoded to fields, quoted-pairs of values are resolved.
+///
+/// ```rust
+/// use sipmsg::*;
+///
+/// let (_, hdrs) = SipHeaders::parse(
+///     "WWW-Authenticate: Digest realm=\"atlanta.com\", qop=\"auth,auth-int\",\r\n \
+///      nonce=\"84a4cc6f\", stale=TRUE, Bearer realm=\"atlanta.com\"\r\n\r\n"
+///         .as_bytes(),
+/// )
+/// .unwrap();
+/// let challenges: Vec<Challenge> = hdrs
+///     .get_typed_all(SipRFCHeader::WWWAuthenticate)
+///     .unwrap()
+///     .unwrap();
+/// assert_eq!(challenges.len(), 2);
+/// assert_eq!(challenges[0].scheme, AuthScheme::Digest);
+/// assert_eq!(challenges[0].qop_options, ["auth", "auth-int"]);
+/// assert!(challenges[0].stale);
+/// assert_eq!(challenges[1].scheme, AuthScheme::Bearer);
+/// assert_eq!(challenges[1].realm.as_deref(), Some("atlanta.com"));
+/// ```
+#[derive(Clone, PartialEq, Debug)]
+pub struct Challenge<'h> {
+    pub scheme: AuthScheme<'h>,
+    pub realm: Option<Cow<'h, str>>,
+    /// URIs of `domain` parameter
+    pub domain: Vec<Cow<'h, str>>,
+    pub nonce: Option<Cow<'h, str>>,
+    pub opaque: Option<Cow<'h, str>>,
+    /// `stale=TRUE`, `false` if parameter is absent
+    pub stale: bool,
+    pub algorithm: Option<Cow<'h, str>>,
+    /// Values of `qop` parameter
+    pub qop_options: Vec<Cow<'h, str>>,
+    /// token68 of non-Digest scheme as it is written in the message
+    pub token68: Option<Cow<'h, str>>,
+    /// Parameters that are not decoded to fields, with names as they are written
+    pub params: Vec<(Cow<'h, str>, Cow<'h, str>)>,
+}
+
+impl<'h> Challenge<'h> {
+    /// Value of parameter that is not decoded to field, name is case-insensitive
+    pub fn param(&self, name: &str) -> Option<&str> {
+        self.params
+            .iter()
+            .find(|(pname, _)| pname.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_ref())
+    }
+
+    pub fn into_owned(self) -> Challenge<'static> {
+        let owned = |v: Cow<'h, str>| -> Cow<'static, str> { Cow::Owned(v.into_owned()) };
+        Challenge {
+            scheme: self.scheme.into_owned(),
+            realm: self.realm.map(owned),
+            domain: self.domain.into_iter().map(owned).collect(),
+            nonce: self.nonce.map(owned),
+            opaque: self.opaque.map(owned),
+            stale: self.stale,
+            algorithm: self.algorithm.map(owned),
+            qop_options: self.qop_options.into_iter().map(owned).collect(),
+            token68: self.token68.map(owned),
+            params: self
+                .params
+                .into_iter()
+                .map(|(name, value)| (owned(name), owned(value)))
+                .collect(),
+        }
+    }
+}
+
+impl<'h> TypedHeader<'h> for Challenge<'h> {
+    fn from_header(header: &'h Header<'h>) -> Result<Challenge<'h>, SipParseError<'static>> {
+        let vstr: &'h str = header.value.vstr.as_ref();
+        let (_, (_, value)) = take_auth_value(vstr.as_bytes(), true)
+            .map_err(|_| invalid_value("Invalid challenge"))?;
+        let to_str = |input: &'h [u8]| {
+            str::from_utf8(input).map_err(|_| invalid_value("Challenge is not valid UTF-8"))
+        };
+
+        let mut challenge = Challenge {
+            scheme: AuthScheme::from_str(to_str(value.scheme)?),
+            realm: None,
+            domain: Vec::new(),
+            nonce: None,
+            opaque: None,
+            stale: false,
+            algorithm: None,
+            qop_options: Vec::new(),
+            token68: value.token68.map(to_str).transpose()?.map(Cow::Borrowed),
+            params: Vec::new(),
+        };
+        for param in value.params.iter() {
+            let name = to_str(param.name)?;
+            let raw_value = to_str(param.value)?;
+            let param_value = if param.quoted {
+                unquote(raw_value)
+            } else {
+                Cow::Borrowed(raw_value)
+            };
+            let aname = Ascii::new(name);
+            if aname == "realm" {
+                challenge.realm = Some(param_value);
+            } else if aname == "domain" {
+                challenge.domain = raw_value
+                    .split(' ')
+                    .filter(|uri| !uri.is_empty())
+                    .map(Cow::Borrowed)
+                    .collect();
+            } else if aname == "nonce" {
+                challenge.nonce = Some(param_value);
+            } else if aname == "opaque" {
+                challenge.opaque = Some(param_value);
+            } else if aname == "stale" {
+                challenge.stale = param_value.eq_ignore_ascii_case("true");
+            } else if aname == "algorithm" {
+                challenge.algorithm = Some(param_value);
+            } else if aname == "qop" {
+                challenge.qop_options = raw_value
+                    .split(',')
+                    .map(|qop| qop.trim())
+                    .filter(|qop| !qop.is_empty())
+                    .map(Cow::Borrowed)
+                    .collect();
+            } else {
+                challenge.params.push((Cow::Borrowed(name), param_value));
+            }
+        }
+        Ok(challenge)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn digest_challenge() {
+        let header = Header::from_name_value(
+            "WWW-Authenticate",
+            "Digest realm=\"atlanta.com\", domain=\"sip:ss1.carrier.com sip:ss2.carrier.com\", \
+             qop=\"auth, auth-int\", nonce=\"f84f1cec41e6cbe5aea9c8e88d359\", opaque=\"\", \
+             stale=FALSE, algorithm=MD5, charset=UTF-8",
+        )
+        .unwrap();
+        let c = Challenge::from_header(&header).unwrap();
+        assert_eq!(c.scheme, AuthScheme::Digest);
+        assert_eq!(c.realm.as_deref(), Some("atlanta.com"));
+        assert_eq!(c.domain, ["sip:ss1.carrier.com", "sip:ss2.carrier.com"]);
+        assert_eq!(c.qop_options, ["auth", "auth-int"]);
+        assert_eq!(c.nonce.as_deref(), Some("f84f1cec41e6cbe5aea9c8e88d359"));
+        assert_eq!(c.opaque.as_deref(), Some(""));
+        assert!(!c.stale);
+        assert_eq!(c.algorithm.as_deref(), Some("MD5"));
+        assert_eq!(c.token68, None);
+        assert_eq!(c.param("Charset"), Some("UTF-8"));
+    }
+
+    #[test]
+    fn other_challenges() {
+        let header = Header::from_name_value(
+            "WWW-Authenticate",
+            "Bearer realm=\"atlanta.com\", scope=\"sip:alice\", \
+             authz_server=\"https://as.example.com/token\"",
+        )
+        .unwrap();
+        let c = Challenge::from_header(&header).unwrap();
+        assert_eq!(c.scheme, AuthScheme::Bearer);
+        assert_eq!(c.realm.as_deref(), Some("atlanta.com"));
+        assert_eq!(
+            c.param("authz_server"),
+            Some("https://as.example.com/token")
+        );
+
+        let header =
+            Header::from_name_value("WWW-Authenticate", "basic realm=\"a \\\"b\\\"\"").unwrap();
+        let c = Challenge::from_header(&header).unwrap();
+        assert_eq!(c.scheme, AuthScheme::Basic);
+        assert_eq!(c.realm.as_deref(), Some("a \"b\""));
+
+        let header = Header::from_name_value("WWW-Authenticate", "Custom dGVzdA==").unwrap();
+        let c = Challenge::from_header(&header).unwrap();
+        assert_eq!(c.scheme, AuthScheme::Other(Cow::Borrowed("Custom")));
+        assert_eq!(c.scheme.as_str(), "Custom");
+        assert_eq!(c.token68.as_deref(), Some("dGVzdA=="));
+        assert!(c.params.is_empty());
+    }
+}
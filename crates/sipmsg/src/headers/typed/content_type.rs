@@ -0,0 +1,78 @@
+use crate::{
+    common::errorparse::SipParseError,
+    headers::{
+        header::Header,
+        typed::{invalid_value, TypedHeader},
+        GenericParams,
+    },
+};
+use alloc::borrow::Cow;
+use core::fmt;
+
+/// Content-Type     =  ( "Content-Type" / "c" ) HCOLON media-type
+/// media-type       =  m-type SLASH m-subtype *(SEMI m-parameter)
+#[derive(Clone, PartialEq, Debug)]
+pub struct ContentType<'h> {
+    /// m-type, e.g. `application`
+    pub media_type: Cow<'h, str>,
+    /// m-subtype, e.g. `sdp`
+    pub subtype: Cow<'h, str>,
+    /// m-parameter, e.g. `charset`
+    pub params: GenericParams<'h>,
+}
+
+impl<'h> ContentType<'h> {
+    /// Compares type and subtype case-insensitively, parameters are ignored
+    pub fn is(&self, media_type: &str, subtype: &str) -> bool {
+        self.media_type.eq_ignore_ascii_case(media_type)
+            && self.subtype.eq_ignore_ascii_case(subtype)
+    }
+
+    pub fn into_owned(self) -> ContentType<'static> {
+        ContentType {
+            media_type: Cow::Owned(self.media_type.into_owned()),
+            subtype: Cow::Owned(self.subtype.into_owned()),
+            params: self.params.into_owned(),
+        }
+    }
+}
+
+impl<'h> TypedHeader<'h> for ContentType<'h> {
+    fn from_header(header: &'h Header<'h>) -> Result<ContentType<'h>, SipParseError<'static>> {
+        let value = header.value.vstr.as_ref();
+        let slash = value
+            .find('/')
+            .ok_or_else(|| invalid_value("Content-Type must be type/subtype"))?;
+        Ok(ContentType {
+            media_type: Cow::Borrowed(value[..slash].trim_end()),
+            subtype: Cow::Borrowed(value[slash + 1..].trim_start()),
+            params: header.params().cloned().unwrap_or_default(),
+        })
+    }
+}
+
+impl<'h> fmt::Display for ContentType<'h> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}/{}{}", self.media_type, self.subtype, self.params)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn content_type_header() {
+        let header = Header::from_name_value("c", "text/html ; charset=ISO-8859-4").unwrap();
+        let content_type = ContentType::from_header(&header).unwrap();
+        assert_eq!(content_type.media_type, "text");
+        assert_eq!(content_type.subtype, "html");
+        assert_eq!(content_type.params.get("charset"), Some(Some("ISO-8859-4")));
+        assert!(content_type.is("TEXT", "Html"));
+
+        let header = Header::from_name_value("Content-Type", "application / sdp").unwrap();
+        let content_type = ContentType::from_header(&header).unwrap();
+        assert!(content_type.is("application", "sdp"));
+        assert!(content_type.params.is_empty());
+    }
+}
@@ -0,0 +1,65 @@
+use crate::{
+    common::{errorparse::SipParseError, sip_method::SipMethod},
+    headers::{
+        header::{Header, HeaderTagType},
+        typed::{invalid_value, tag_str, TypedHeader},
+    },
+};
+use core::fmt;
+
+/// CSeq  =  "CSeq" HCOLON 1*DIGIT LWS Method
+#[derive(Clone, PartialEq, Debug)]
+pub struct CSeqHeader<'h> {
+    /// Sequence number, must be less than 2**31
+    pub seq: u32,
+    pub method: SipMethod<'h>,
+}
+
+impl<'h> CSeqHeader<'h> {
+    pub fn into_owned(self) -> CSeqHeader<'static> {
+        CSeqHeader {
+            seq: self.seq,
+            method: self.method.into_owned(),
+        }
+    }
+}
+
+impl<'h> TypedHeader<'h> for CSeqHeader<'h> {
+    fn from_header(header: &'h Header<'h>) -> Result<CSeqHeader<'h>, SipParseError<'static>> {
+        let seq = tag_str(header, HeaderTagType::Number)
+            .and_then(|seq| seq.parse::<u32>().ok())
+            .filter(|seq| *seq < 1 << 31)
+            .ok_or_else(|| invalid_value("Invalid CSeq number"))?;
+        let method = tag_str(header, HeaderTagType::Method)
+            .and_then(SipMethod::from_str)
+            .ok_or_else(|| invalid_value("Invalid CSeq method"))?;
+        Ok(CSeqHeader { seq, method })
+    }
+}
+
+impl<'h> fmt::Display for CSeqHeader<'h> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} {}", self.seq, self.method)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cseq_header() {
+        let header = Header::from_name_value("CSeq", "4711 INVITE").unwrap();
+        let cseq = CSeqHeader::from_header(&header).unwrap();
+        assert_eq!(cseq.seq, 4711);
+        assert_eq!(cseq.method, SipMethod::INVITE);
+
+        let header = Header::from_name_value("CSeq", "4711 invite").unwrap();
+        let cseq = CSeqHeader::from_header(&header).unwrap();
+        assert_eq!(cseq.method.as_str(), "invite");
+        assert_ne!(cseq.method, SipMethod::INVITE);
+
+        let header = Header::from_name_value("CSeq", "2147483648 ACK").unwrap();
+        assert!(CSeqHeader::from_header(&header).is_err());
+    }
+}
@@ -0,0 +1,297 @@
+use crate::{
+    common::errorparse::SipParseError,
+    headers::{
+        header::Header,
+        typed::{invalid_value, TypedHeader},
+    },
+};
+use core::fmt;
+
+const WKDAYS: [&str; 7] = ["Sun", "Mon", "Tue", "Wed", "Thu", "Fri", "Sat"];
+const MONTHS: [&str; 12] = [
+    "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+];
+
+fn is_leap_year(year: i64) -> bool {
+    year % 4 == 0 && (year % 100 != 0 || year % 400 == 0)
+}
+
+fn days_in_month(year: i64, month: u8) -> u8 {
+    match month {
+        2 if is_leap_year(year) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// Days since 1970-01-01 of proleptic Gregorian calendar
+fn days_from_civil(year: i64, month: u8, day: u8) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let yoe = year - era * 400;
+    let mp = (i64::from(month) + 9) % 12;
+    let doy = (153 * mp + 2) / 5 + i64::from(day) - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146097 + doe - 719468
+}
+
+/// (year, month, day) of days since 1970-01-01
+fn civil_from_days(days: i64) -> (i64, u8, u8) {
+    let days = days + 719468;
+    let era = days.div_euclid(146097);
+    let doe = days - era * 146097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u8;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u8;
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month, day)
+}
+
+/// Parses exactly `len` digits
+fn number(input: &str, len: usize) -> Option<u16> {
+    if input.len() != len || !input.bytes().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    input.parse().ok()
+}
+
+/// Date  =  "Date" HCOLON SIP-date.
+/// SIP-date is rfc1123-date, time is always in GMT:
+/// `Sat, 13 Nov 2010 23:29:00 GMT`.
+/// Day of week is not checked against the date when it is parsed
+/// and is computed when the date is formatted.
+///
+/// ```rust
+/// use sipmsg::*;
+///
+/// let date = DateHeader::from_unix_time(1_289_690_940).unwrap();
+/// assert_eq!(date.to_string(), "Sat, 13 Nov 2010 23:29:00 GMT");
+///
+/// let msg_buf = SipResponseBuilder::new(SipResponseStatusCode::OK)
+///     .header(SipRFCHeader::Date, date.to_string())
+///     .build()
+///     .unwrap();
+/// let (_, msg) = SipMessage::parse(&msg_buf).unwrap();
+/// let received: DateHeader = msg
+///     .response()
+///     .unwrap()
+///     .headers
+///     .get_typed(SipRFCHeader::Date)
+///     .unwrap()
+///     .unwrap();
+/// assert_eq!(received, date);
+/// assert_eq!(received.unix_time(), 1_289_690_940);
+/// ```
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub struct DateHeader {
+    year: u16,
+    month: u8,
+    day: u8,
+    hour: u8,
+    minute: u8,
+    second: u8,
+}
+
+impl DateHeader {
+    /// `None` if values are out of range, year is 4 digits
+    pub fn new(
+        year: u16,
+        month: u8,
+        day: u8,
+        hour: u8,
+        minute: u8,
+        second: u8,
+    ) -> Option<DateHeader> {
+        if year > 9999
+            || month == 0
+            || month > 12
+            || day == 0
+            || day > days_in_month(i64::from(year), month)
+            || hour > 23
+            || minute > 59
+            || second > 59
+        {
+            return None;
+        }
+        Some(DateHeader {
+            year,
+            month,
+            day,
+            hour,
+            minute,
+            second,
+        })
+    }
+
+    /// Date of seconds since 1970-01-01 00:00:00 GMT.
+    /// `None` if the year does not fit in 4 digits
+    pub fn from_unix_time(seconds: i64) -> Option<DateHeader> {
+        let days = seconds.div_euclid(86400);
+        let time = seconds.rem_euclid(86400);
+        let (year, month, day) = civil_from_days(days);
+        if !(0..=9999).contains(&year) {
+            return None;
+        }
+        Some(DateHeader {
+            year: year as u16,
+            month,
+            day,
+            hour: (time / 3600) as u8,
+            minute: (time % 3600 / 60) as u8,
+            second: (time % 60) as u8,
+        })
+    }
+
+    pub fn year(&self) -> u16 {
+        self.year
+    }
+
+    /// 1 - 12
+    pub fn month(&self) -> u8 {
+        self.month
+    }
+
+    /// 1 - 31
+    pub fn day(&self) -> u8 {
+        self.day
+    }
+
+    pub fn hour(&self) -> u8 {
+        self.hour
+    }
+
+    pub fn minute(&self) -> u8 {
+        self.minute
+    }
+
+    pub fn second(&self) -> u8 {
+        self.second
+    }
+
+    /// Seconds since 1970-01-01 00:00:00 GMT, negative for earlier dates
+    pub fn unix_time(&self) -> i64 {
+        let days = days_from_civil(i64::from(self.year), self.month, self.day);
+        days * 86400
+            + i64::from(self.hour) * 3600
+            + i64::from(self.minute) * 60
+            + i64::from(self.second)
+    }
+
+    /// wkday of the date: `Mon`, `Tue`...
+    pub fn weekday(&self) -> &'static str {
+        let days = days_from_civil(i64::from(self.year), self.month, self.day);
+        // 1970-01-01 is Thursday
+        WKDAYS[(days + 4).rem_euclid(7) as usize]
+    }
+
+    /// rfc1123-date  =  wkday "," SP date1 SP time SP "GMT".
+    /// Day of month of one digit is accepted
+    pub fn from_str(value: &str) -> Option<DateHeader> {
+        let wkday = value.get(..3)?;
+        if !WKDAYS.contains(&wkday) {
+            return None;
+        }
+        let rest = value[3..].strip_prefix(", ")?;
+        let mut parts = rest.split(' ');
+        let day = parts.next()?;
+        let day = number(day, 2).or_else(|| number(day, 1))?;
+        let month = parts.next()?;
+        let month = MONTHS.iter().position(|m| *m == month)? + 1;
+        let year = number(parts.next()?, 4)?;
+        let mut time = parts.next()?.split(':');
+        let hour = number(time.next()?, 2)?;
+        let minute = number(time.next()?, 2)?;
+        let second = number(time.next()?, 2)?;
+        if time.next().is_some() || parts.next()? != "GMT" || parts.next().is_some() {
+            return None;
+        }
+        DateHeader::new(
+            year,
+            month as u8,
+            day as u8,
+            hour as u8,
+            minute as u8,
+            second as u8,
+        )
+    }
+}
+
+impl fmt::Display for DateHeader {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}, {:02} {} {:04} {:02}:{:02}:{:02} GMT",
+            self.weekday(),
+            self.day,
+            MONTHS[usize::from(self.month - 1)],
+            self.year,
+            self.hour,
+            self.minute,
+            self.second
+        )
+    }
+}
+
+impl<'h> TypedHeader<'h> for DateHeader {
+    fn from_header(header: &'h Header<'h>) -> Result<DateHeader, SipParseError<'static>> {
+        DateHeader::from_str(&header.value.vstr).ok_or_else(|| invalid_value("Invalid SIP-date"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::ToString;
+
+    #[test]
+    fn date_header() {
+        let header = Header::from_name_value("Date", "Sat, 13 Nov 2010 23:29:00 GMT").unwrap();
+        let date = DateHeader::from_header(&header).unwrap();
+        assert_eq!(date, DateHeader::new(2010, 11, 13, 23, 29, 0).unwrap());
+        assert_eq!((date.year(), date.month(), date.day()), (2010, 11, 13));
+        assert_eq!((date.hour(), date.minute(), date.second()), (23, 29, 0));
+        assert_eq!(date.weekday(), "Sat");
+        assert_eq!(date.unix_time(), 1_289_690_940);
+
+        let date = DateHeader::from_str("Mon, 2 Jun 1982 00:00:00 GMT").unwrap();
+        assert_eq!(date.to_string(), "Wed, 02 Jun 1982 00:00:00 GMT");
+    }
+
+    #[test]
+    fn date_header_invalid() {
+        assert_eq!(DateHeader::from_str("Sat, 13 Nov 2010 23:29:00 UTC"), None);
+        assert_eq!(DateHeader::from_str("Sat, 13 Nov 2010 24:00:00 GMT"), None);
+        assert_eq!(DateHeader::from_str("Sat, 29 Feb 2011 00:00:00 GMT"), None);
+        assert_eq!(DateHeader::from_str("Sat, 13 nov 2010 23:29:00 GMT"), None);
+        assert_eq!(DateHeader::from_str("Sat, 13 Nov 10 23:29:00 GMT"), None);
+        assert_eq!(DateHeader::from_str("Sat, 13 Nov 2010 23:29 GMT"), None);
+        assert_eq!(DateHeader::from_str("Sat"), None);
+        assert!(DateHeader::from_str("Tue, 29 Feb 2000 00:00:00 GMT").is_some());
+    }
+
+    #[test]
+    fn date_header_unix_time() {
+        let epoch = DateHeader::from_unix_time(0).unwrap();
+        assert_eq!(epoch.to_string(), "Thu, 01 Jan 1970 00:00:00 GMT");
+        assert_eq!(epoch.unix_time(), 0);
+
+        let date = DateHeader::from_unix_time(-1).unwrap();
+        assert_eq!(date.to_string(), "Wed, 31 Dec 1969 23:59:59 GMT");
+        assert_eq!(date.unix_time(), -1);
+
+        for seconds in [951_782_400, 4_107_542_399, 1_700_000_000, -62_167_219_200].iter() {
+            let date = DateHeader::from_unix_time(*seconds).unwrap();
+            assert_eq!(date.unix_time(), *seconds);
+            assert_eq!(DateHeader::from_str(&date.to_string()), Some(date));
+        }
+        assert_eq!(
+            DateHeader::from_unix_time(951_782_400).unwrap().to_string(),
+            "Tue, 29 Feb 2000 00:00:00 GMT"
+        );
+        assert_eq!(DateHeader::from_unix_time(253_402_300_800), None);
+        assert_eq!(DateHeader::from_unix_time(-62_167_219_201), None);
+    }
+}
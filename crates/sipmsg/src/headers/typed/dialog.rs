@@ -0,0 +1,200 @@
+use crate::{
+    common::errorparse::SipParseError,
+    headers::{
+        header::Header,
+        typed::{invalid_value, TypedHeader},
+        GenericParams, SipUri,
+    },
+};
+use alloc::borrow::Cow;
+
+fn param<'h>(params: Option<&'h GenericParams<'h>>, name: &str) -> Option<Cow<'h, str>> {
+    params?.get(name).flatten().map(Cow::Borrowed)
+}
+
+fn call_id<'h>(header: &'h Header<'h>) -> Result<Cow<'h, str>, SipParseError<'static>> {
+    if header.value.vstr.is_empty() {
+        return Err(invalid_value("callid is expected"));
+    }
+    Ok(Cow::Borrowed(header.value.vstr.as_ref()))
+}
+
+/// Replaces        = "Replaces" HCOLON callid *(SEMI replaces-param)
+/// replaces-param  = to-tag / from-tag / early-flag / generic-param
+/// ([rfc3891](https://tools.ietf.org/html/rfc3891#section-6.1)).
+/// Also used for Join ([rfc3911](https://tools.ietf.org/html/rfc3911#section-7.1)),
+/// it has the same grammar without `early-only`.
+///
+/// ```rust
+/// use sipmsg::*;
+///
+/// let (_, hdrs) = SipHeaders::parse(
+///     "Refer-To: <sip:bob@biloxi.com?Replaces=425928%40bobster.example.org%3Bto-tag%3D7743%3Bfrom-tag%3D6472>\r\n\r\n"
+///         .as_bytes(),
+/// )
+/// .unwrap();
+/// let refer_to: NameAddrHeader = hdrs.get_typed(SipRFCHeader::ReferTo).unwrap().unwrap();
+/// let replaces = ReplacesHeader::from_uri(refer_to.uri.sip().unwrap())
+///     .unwrap()
+///     .unwrap();
+/// assert_eq!(replaces.call_id, "425928@bobster.example.org");
+/// assert_eq!(replaces.to_tag, "7743");
+/// assert_eq!(replaces.from_tag, "6472");
+/// assert!(!replaces.early_only);
+/// ```
+#[derive(Clone, PartialEq, Debug)]
+pub struct ReplacesHeader<'h> {
+    pub call_id: Cow<'h, str>,
+    pub to_tag: Cow<'h, str>,
+    pub from_tag: Cow<'h, str>,
+    /// `early-only` parameter is present
+    pub early_only: bool,
+    /// Parameters, including the ones decoded to fields
+    pub params: GenericParams<'h>,
+}
+
+impl<'h> ReplacesHeader<'h> {
+    /// Decodes Replaces header embedded in URI, e.g. in Refer-To:
+    /// `<sip:bob@biloxi.com?Replaces=12345%40192.168.118.3%3Bto-tag%3D12345%3Bfrom-tag%3D5FFE-3994>`.
+    /// `None` if URI has no Replaces header
+    pub fn from_uri(
+        uri: &SipUri,
+    ) -> Option<Result<ReplacesHeader<'static>, SipParseError<'static>>> {
+        let value = uri.decoded_header("Replaces")?;
+        let header = match Header::from_name_value("Replaces", &value) {
+            Ok(header) => header,
+            Err(err) => return Some(Err(err)),
+        };
+        Some(ReplacesHeader::from_header(&header).map(|replaces| replaces.into_owned()))
+    }
+
+    pub fn into_owned(self) -> ReplacesHeader<'static> {
+        ReplacesHeader {
+            call_id: Cow::Owned(self.call_id.into_owned()),
+            to_tag: Cow::Owned(self.to_tag.into_owned()),
+            from_tag: Cow::Owned(self.from_tag.into_owned()),
+            early_only: self.early_only,
+            params: self.params.into_owned(),
+        }
+    }
+}
+
+impl<'h> TypedHeader<'h> for ReplacesHeader<'h> {
+    fn from_header(header: &'h Header<'h>) -> Result<ReplacesHeader<'h>, SipParseError<'static>> {
+        let params = header.params();
+        Ok(ReplacesHeader {
+            call_id: call_id(header)?,
+            to_tag: param(params, "to-tag").ok_or_else(|| invalid_value("to-tag is expected"))?,
+            from_tag: param(params, "from-tag")
+                .ok_or_else(|| invalid_value("from-tag is expected"))?,
+            early_only: params.is_some_and(|p| p.contains("early-only")),
+            params: params.cloned().unwrap_or_default(),
+        })
+    }
+}
+
+/// Target-Dialog  = "Target-Dialog" HCOLON callid *(SEMI td-param)
+/// td-param       = remote-param / local-param / generic-param
+/// ([rfc4538](https://tools.ietf.org/html/rfc4538#section-7)).
+/// Tags are named from the point of view of the sender of the header
+///
+/// ```rust
+/// use sipmsg::*;
+///
+/// let header = SipHeader::from_name_value(
+///     "Target-Dialog",
+///     "fa77as7dad8-sd98ajzz@host.example.com;local-tag=kkaz-;remote-tag=6544",
+/// )
+/// .unwrap();
+/// let target = header.typed::<TargetDialogHeader>().unwrap();
+/// assert_eq!(target.call_id, "fa77as7dad8-sd98ajzz@host.example.com");
+/// assert_eq!(target.local_tag.as_deref(), Some("kkaz-"));
+/// assert_eq!(target.remote_tag.as_deref(), Some("6544"));
+/// ```
+#[derive(Clone, PartialEq, Debug)]
+pub struct TargetDialogHeader<'h> {
+    pub call_id: Cow<'h, str>,
+    pub local_tag: Option<Cow<'h, str>>,
+    pub remote_tag: Option<Cow<'h, str>>,
+    /// Parameters, including the ones decoded to fields
+    pub params: GenericParams<'h>,
+}
+
+impl<'h> TargetDialogHeader<'h> {
+    pub fn into_owned(self) -> TargetDialogHeader<'static> {
+        TargetDialogHeader {
+            call_id: Cow::Owned(self.call_id.into_owned()),
+            local_tag: self.local_tag.map(|t| Cow::Owned(t.into_owned())),
+            remote_tag: self.remote_tag.map(|t| Cow::Owned(t.into_owned())),
+            params: self.params.into_owned(),
+        }
+    }
+}
+
+impl<'h> TypedHeader<'h> for TargetDialogHeader<'h> {
+    fn from_header(
+        header: &'h Header<'h>,
+    ) -> Result<TargetDialogHeader<'h>, SipParseError<'static>> {
+        let params = header.params();
+        Ok(TargetDialogHeader {
+            call_id: call_id(header)?,
+            local_tag: param(params, "local-tag"),
+            remote_tag: param(params, "remote-tag"),
+            params: params.cloned().unwrap_or_default(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn replaces_header() {
+        let header = Header::from_name_value(
+            "Replaces",
+            "98732@sip.example.com;from-tag=r33th4x0r;to-tag=ff87ff;early-only",
+        )
+        .unwrap();
+        let replaces = ReplacesHeader::from_header(&header).unwrap();
+        assert_eq!(replaces.call_id, "98732@sip.example.com");
+        assert_eq!(replaces.from_tag, "r33th4x0r");
+        assert_eq!(replaces.to_tag, "ff87ff");
+        assert!(replaces.early_only);
+
+        let header =
+            Header::from_name_value("Join", "12adf2f34456gs5;to-tag=12345;from-tag=54321").unwrap();
+        let join = ReplacesHeader::from_header(&header).unwrap();
+        assert_eq!(join.call_id, "12adf2f34456gs5");
+        assert!(!join.early_only);
+
+        let header = Header::from_name_value("Replaces", "98732@sip.example.com;to-tag=1").unwrap();
+        assert!(ReplacesHeader::from_header(&header).is_err());
+    }
+
+    #[test]
+    fn replaces_from_uri() {
+        let (_, uri) = SipUri::parse(
+            b"sip:bob@biloxi.com?Replaces=12345%40192.168.118.3%3Bto-tag%3D12345%3Bfrom-tag%3D5FFE-3994&X=1",
+        )
+        .unwrap();
+        let replaces = ReplacesHeader::from_uri(&uri).unwrap().unwrap();
+        assert_eq!(replaces.call_id, "12345@192.168.118.3");
+        assert_eq!(replaces.to_tag, "12345");
+        assert_eq!(replaces.from_tag, "5FFE-3994");
+
+        let (_, uri) = SipUri::parse(b"sip:bob@biloxi.com?Replaces=12345").unwrap();
+        assert!(ReplacesHeader::from_uri(&uri).unwrap().is_err());
+        let (_, uri) = SipUri::parse(b"sip:bob@biloxi.com").unwrap();
+        assert!(ReplacesHeader::from_uri(&uri).is_none());
+    }
+
+    #[test]
+    fn target_dialog_header() {
+        let header = Header::from_name_value("Target-Dialog", "abc@host;remote-tag=1").unwrap();
+        let target = TargetDialogHeader::from_header(&header).unwrap();
+        assert_eq!(target.call_id, "abc@host");
+        assert_eq!(target.local_tag, None);
+        assert_eq!(target.remote_tag.as_deref(), Some("1"));
+    }
+}
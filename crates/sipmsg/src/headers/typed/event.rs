@@ -0,0 +1,272 @@
+use crate::{
+    common::errorparse::SipParseError,
+    headers::{
+        header::Header,
+        typed::{invalid_value, TypedHeader},
+        GenericParams,
+    },
+};
+use alloc::{borrow::Cow, vec::Vec};
+use core::fmt;
+use unicase::Ascii;
+
+/// Event        =  ( "Event" / "o" ) HCOLON event-type *( SEMI event-param )
+/// event-type   =  event-package *( "." event-template )
+/// ([rfc6665](https://tools.ietf.org/html/rfc6665#section-8.4)).
+/// Also used for values of Allow-Events, they don't have parameters.
+///
+/// ```rust
+/// use sipmsg::*;
+///
+/// let (_, hdrs) = SipHeaders::parse(
+///     "o: presence.winfo;id=1\r\nu: presence, dialog\r\n\r\n".as_bytes(),
+/// )
+/// .unwrap();
+/// let event: EventHeader = hdrs.get_typed(SipRFCHeader::Event).unwrap().unwrap();
+/// assert_eq!(event.package, "presence");
+/// assert_eq!(event.templates, ["winfo"]);
+/// assert_eq!(event.id.as_deref(), Some("1"));
+///
+/// let allowed: Vec<EventHeader> = hdrs.get_typed_all(SipRFCHeader::AllowEvents).unwrap().unwrap();
+/// assert!(allowed.iter().any(|event| event.package == "dialog"));
+/// ```
+#[derive(Clone, PartialEq, Debug)]
+pub struct EventHeader<'h> {
+    /// event-package, e.g. `presence`, `dialog`, `refer`
+    pub package: Cow<'h, str>,
+    /// event-template, e.g. `winfo` of `presence.winfo`
+    pub templates: Vec<Cow<'h, str>>,
+    /// Value of `id` parameter
+    pub id: Option<Cow<'h, str>>,
+    /// event-param, including `id`
+    pub params: GenericParams<'h>,
+}
+
+impl<'h> EventHeader<'h> {
+    /// Compares event-type (`package` and `templates`), parameters are not compared
+    pub fn is(&self, event_type: &str) -> bool {
+        let mut parts = event_type.split('.');
+        if parts.next() != Some(self.package.as_ref()) {
+            return false;
+        }
+        let mut templates = self.templates.iter();
+        loop {
+            match (parts.next(), templates.next()) {
+                (None, None) => return true,
+                (Some(part), Some(template)) if part == template => continue,
+                _ => return false,
+            }
+        }
+    }
+
+    pub fn into_owned(self) -> EventHeader<'static> {
+        EventHeader {
+            package: Cow::Owned(self.package.into_owned()),
+            templates: self
+                .templates
+                .into_iter()
+                .map(|t| Cow::Owned(t.into_owned()))
+                .collect(),
+            id: self.id.map(|id| Cow::Owned(id.into_owned())),
+            params: self.params.into_owned(),
+        }
+    }
+}
+
+impl<'h> TypedHeader<'h> for EventHeader<'h> {
+    fn from_header(header: &'h Header<'h>) -> Result<EventHeader<'h>, SipParseError<'static>> {
+        let value: &'h str = header.value.vstr.as_ref();
+        let mut parts = value.split('.');
+        let package = parts.next().unwrap_or_default();
+        let templates: Vec<Cow<'h, str>> = parts.map(Cow::Borrowed).collect();
+        if package.is_empty() || templates.iter().any(|t| t.is_empty()) {
+            return Err(invalid_value("Invalid event-type"));
+        }
+        Ok(EventHeader {
+            package: Cow::Borrowed(package),
+            templates,
+            id: header
+                .params()
+                .and_then(|params| params.get("id"))
+                .flatten()
+                .map(Cow::Borrowed),
+            params: header.params().cloned().unwrap_or_default(),
+        })
+    }
+}
+
+impl<'h> fmt::Display for EventHeader<'h> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.package)?;
+        for template in self.templates.iter() {
+            write!(f, ".{}", template)?;
+        }
+        write!(f, "{}", self.params)
+    }
+}
+
+/// substate-value of Subscription-State
+#[derive(Clone, PartialEq, Debug)]
+pub enum SubState<'a> {
+    Active,
+    Pending,
+    Terminated,
+    /// extension-substate
+    Other(Cow<'a, str>),
+}
+
+impl<'a> SubState<'a> {
+    pub fn from_str(state: &'a str) -> SubState<'a> {
+        let astate = Ascii::new(state);
+        if astate == "active" {
+            SubState::Active
+        } else if astate == "pending" {
+            SubState::Pending
+        } else if astate == "terminated" {
+            SubState::Terminated
+        } else {
+            SubState::Other(Cow::Borrowed(state))
+        }
+    }
+
+    pub fn as_str(&self) -> &str {
+        match self {
+            SubState::Active => "active",
+            SubState::Pending => "pending",
+            SubState::Terminated => "terminated",
+            SubState::Other(state) => state,
+        }
+    }
+
+    pub fn into_owned(self) -> SubState<'static> {
+        match self {
+            SubState::Active => SubState::Active,
+            SubState::Pending => SubState::Pending,
+            SubState::Terminated => SubState::Terminated,
+            SubState::Other(state) => SubState::Other(Cow::Owned(state.into_owned())),
+        }
+    }
+}
+
+impl<'a> fmt::Display for SubState<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// Subscription-State   = "Subscription-State" HCOLON substate-value
+///                        *( SEMI subexp-params )
+/// ([rfc6665](https://tools.ietf.org/html/rfc6665#section-8.4))
+///
+/// ```rust
+/// use sipmsg::*;
+///
+/// let header =
+///     SipHeader::from_name_value("Subscription-State", "terminated;reason=probation;retry-after=30")
+///         .unwrap();
+/// let state = header.typed::<SubscriptionState>().unwrap();
+/// assert_eq!(state.state, SubState::Terminated);
+/// assert_eq!(state.reason.as_deref(), Some("probation"));
+/// assert_eq!(state.retry_after, Some(30));
+/// assert_eq!(state.expires, None);
+/// ```
+#[derive(Clone, PartialEq, Debug)]
+pub struct SubscriptionState<'h> {
+    pub state: SubState<'h>,
+    /// Value of `expires` parameter, seconds
+    pub expires: Option<u32>,
+    /// Value of `reason` parameter of terminated subscription:
+    /// `deactivated`, `probation`, `rejected`, `timeout`, `giveup`, `noresource`, `invariant`
+    pub reason: Option<Cow<'h, str>>,
+    /// Value of `retry-after` parameter, seconds
+    pub retry_after: Option<u32>,
+    /// subexp-params, including the ones decoded to fields
+    pub params: GenericParams<'h>,
+}
+
+impl<'h> SubscriptionState<'h> {
+    pub fn into_owned(self) -> SubscriptionState<'static> {
+        SubscriptionState {
+            state: self.state.into_owned(),
+            expires: self.expires,
+            reason: self.reason.map(|r| Cow::Owned(r.into_owned())),
+            retry_after: self.retry_after,
+            params: self.params.into_owned(),
+        }
+    }
+}
+
+impl<'h> TypedHeader<'h> for SubscriptionState<'h> {
+    fn from_header(
+        header: &'h Header<'h>,
+    ) -> Result<SubscriptionState<'h>, SipParseError<'static>> {
+        let params = header.params();
+        let seconds = |name, message| match params.and_then(|p| p.get(name)) {
+            Some(value) => value
+                .and_then(|v| v.parse().ok())
+                .map(Some)
+                .ok_or_else(|| invalid_value(message)),
+            None => Ok(None),
+        };
+        Ok(SubscriptionState {
+            state: SubState::from_str(&header.value.vstr),
+            expires: seconds("expires", "Invalid Subscription-State expires")?,
+            reason: params
+                .and_then(|p| p.get("reason"))
+                .flatten()
+                .map(Cow::Borrowed),
+            retry_after: seconds("retry-after", "Invalid Subscription-State retry-after")?,
+            params: params.cloned().unwrap_or_default(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::ToString;
+
+    #[test]
+    fn event_header() {
+        let header = Header::from_name_value("Event", "dialog;id=a1;call-id=x").unwrap();
+        let event = EventHeader::from_header(&header).unwrap();
+        assert_eq!(event.package, "dialog");
+        assert!(event.templates.is_empty());
+        assert_eq!(event.id.as_deref(), Some("a1"));
+        assert_eq!(event.params.get("call-id"), Some(Some("x")));
+        assert!(event.is("dialog"));
+        assert!(!event.is("dialo"));
+        assert!(!event.is("dialog.winfo"));
+        assert_eq!(event.to_string(), "dialog;id=a1;call-id=x");
+
+        let header = Header::from_name_value("o", "presence.winfo").unwrap();
+        let event = EventHeader::from_header(&header).unwrap();
+        assert_eq!(event.templates, ["winfo"]);
+        assert!(event.is("presence.winfo"));
+        assert!(!event.is("presence"));
+
+        let header = Header::from_name_value("Event", "presence.").unwrap();
+        assert!(EventHeader::from_header(&header).is_err());
+    }
+
+    #[test]
+    fn subscription_state_header() {
+        let header = Header::from_name_value("Subscription-State", "active;expires=3600").unwrap();
+        let state = SubscriptionState::from_header(&header).unwrap();
+        assert_eq!(state.state, SubState::Active);
+        assert_eq!(state.expires, Some(3600));
+        assert_eq!(state.reason, None);
+
+        let header = Header::from_name_value("Subscription-State", "Pending").unwrap();
+        let state = SubscriptionState::from_header(&header).unwrap();
+        assert_eq!(state.state, SubState::Pending);
+        assert_eq!(state.state.to_string(), "pending");
+
+        let header = Header::from_name_value("Subscription-State", "waiting").unwrap();
+        let state = SubscriptionState::from_header(&header).unwrap();
+        assert_eq!(state.state, SubState::Other(Cow::Borrowed("waiting")));
+
+        let header = Header::from_name_value("Subscription-State", "active;expires=soon").unwrap();
+        assert!(SubscriptionState::from_header(&header).is_err());
+    }
+}
@@ -0,0 +1,78 @@
+//! Typed views of parsed headers.
+//! Values are decoded from [`SipHeader`](crate::SipHeader), so the raw form of the message is kept.
+//!
+//! ```rust
+//! use sipmsg::*;
+//!
+//! let (_, hdrs) = SipHeaders::parse(
+//!     "Via: SIP/2.0/UDP pc33.atlanta.com;branch=z9hG4bK776asdhds;rport\r\n\
+//!      CSeq: 314159 INVITE\r\n\
+//!      Max-Forwards: 70\r\n\r\n"
+//!         .as_bytes(),
+//! )
+//! .unwrap();
+//! let via: ViaHeader = hdrs.get_typed(SipRFCHeader::Via).unwrap().unwrap();
+//! assert_eq!(via.transport, SipTransport::Udp);
+//! assert_eq!(via.branch.as_deref(), Some("z9hG4bK776asdhds"));
+//! assert_eq!(via.rport, Some(None));
+//!
+//! let cseq: CSeqHeader = hdrs.get_typed(SipRFCHeader::CSeq).unwrap().unwrap();
+//! assert_eq!(cseq.seq, 314159);
+//! assert_eq!(cseq.method, SipMethod::INVITE);
+//!
+//! let max_forwards: MaxForwards = hdrs.get_typed(SipRFCHeader::MaxForwards).unwrap().unwrap();
+//! assert_eq!(max_forwards, MaxForwards(70));
+//! ```
+use crate::{
+    common::errorparse::{ParseErrorKind, SipParseError},
+    headers::header::{Header, HeaderTagType},
+};
+use core::str;
+
+mod challenge;
+pub use challenge::{AuthScheme, Challenge};
+
+mod content_type;
+pub use content_type::ContentType;
+
+mod date;
+pub use date::DateHeader;
+
+mod cseq;
+pub use cseq::CSeqHeader;
+
+mod dialog;
+pub use dialog::{ReplacesHeader, TargetDialogHeader};
+
+mod event;
+pub use event::{EventHeader, SubState, SubscriptionState};
+
+mod name_addr;
+pub use name_addr::NameAddrHeader;
+
+mod numeric;
+pub use numeric::{Expires, MaxForwards, RSeq};
+
+mod privacy;
+pub use privacy::{PrivacyHeader, PrivacyValue};
+
+mod rack;
+pub use rack::RAckHeader;
+
+mod via;
+pub use via::ViaHeader;
+
+/// Header that can be decoded from parsed [`SipHeader`](crate::SipHeader)
+pub trait TypedHeader<'h>: Sized {
+    fn from_header(header: &'h Header<'h>) -> Result<Self, SipParseError<'static>>;
+}
+
+pub(crate) fn invalid_value(message: &'static str) -> SipParseError<'static> {
+    SipParseError::new(ParseErrorKind::InvalidHeaderValue, Some(message))
+}
+
+/// Tag of header value as string
+pub(crate) fn tag_str<'h>(header: &'h Header<'h>, tag: HeaderTagType) -> Option<&'h str> {
+    let value = header.value.tags()?.get(&tag)?;
+    str::from_utf8(value).ok()
+}
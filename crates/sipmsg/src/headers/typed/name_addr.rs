@@ -0,0 +1,156 @@
+use crate::{
+    common::{errorparse::SipParseError, escape::unquote},
+    headers::{
+        header::{Header, HeaderTagType},
+        typed::{invalid_value, tag_str, TypedHeader},
+        GenericParams, Uri,
+    },
+};
+use alloc::borrow::Cow;
+use core::fmt;
+
+/// Value of From, To, Contact, Route, Record-Route, Reply-To, Refer-To, Referred-By,
+/// P-Asserted-Identity and P-Preferred-Identity headers:
+/// `( name-addr / addr-spec ) *( SEMI params )`.
+/// Contact `*` is not a name-addr and can't be decoded
+#[derive(Clone, PartialEq, Debug)]
+pub struct NameAddrHeader<'h> {
+    /// Display name with quoted-pairs resolved
+    pub display_name: Option<Cow<'h, str>>,
+    pub uri: Uri<'h>,
+    /// Value of `tag` parameter of From and To headers
+    pub tag: Option<Cow<'h, str>>,
+    /// Header parameters, including `tag`.
+    /// `tag` is written from the field above, not from `params`
+    pub params: GenericParams<'h>,
+}
+
+impl<'h> NameAddrHeader<'h> {
+    pub fn into_owned(self) -> NameAddrHeader<'static> {
+        NameAddrHeader {
+            display_name: self.display_name.map(|d| Cow::Owned(d.into_owned())),
+            uri: self.uri.into_owned(),
+            tag: self.tag.map(|t| Cow::Owned(t.into_owned())),
+            params: self.params.into_owned(),
+        }
+    }
+}
+
+impl<'h> TypedHeader<'h> for NameAddrHeader<'h> {
+    fn from_header(header: &'h Header<'h>) -> Result<NameAddrHeader<'h>, SipParseError<'static>> {
+        let uri = header
+            .value
+            .uri()
+            .ok_or_else(|| invalid_value("name-addr or addr-spec is expected"))?;
+        let display_name = tag_str(header, HeaderTagType::DisplayName).map(|name| {
+            if header.value.vstr.starts_with('"') {
+                unquote(name)
+            } else {
+                Cow::Borrowed(name)
+            }
+        });
+        let tag = header
+            .params()
+            .and_then(|params| params.get("tag"))
+            .flatten()
+            .map(Cow::Borrowed);
+        Ok(NameAddrHeader {
+            display_name,
+            uri: uri.clone(),
+            tag,
+            params: header.params().cloned().unwrap_or_default(),
+        })
+    }
+}
+
+/// Always written in name-addr form, display name is written as quoted-string.
+/// `tag` is written in place of the first `tag` parameter or after all parameters
+impl<'h> fmt::Display for NameAddrHeader<'h> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if let Some(display_name) = &self.display_name {
+            f.write_str("\"")?;
+            for c in display_name.chars() {
+                if c == '"' || c == '\\' {
+                    f.write_str("\\")?;
+                }
+                write!(f, "{}", c)?;
+            }
+            f.write_str("\" ")?;
+        }
+        write!(f, "<{}>", self.uri)?;
+        let mut tag = self.tag.as_ref();
+        for param in self.params.iter() {
+            if param.name().eq_ignore_ascii_case("tag") {
+                if let Some(tag) = tag.take() {
+                    write!(f, ";tag={}", tag)?;
+                }
+            } else {
+                write!(f, ";{}", param)?;
+            }
+        }
+        if let Some(tag) = tag {
+            write!(f, ";tag={}", tag)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::ToString;
+
+    #[test]
+    fn name_addr_header() {
+        let header =
+            Header::from_name_value("From", "\"Bob \\\"B\\\"\" <sips:bob@biloxi.com>;tag=a48s;x")
+                .unwrap();
+        let from = NameAddrHeader::from_header(&header).unwrap();
+        assert_eq!(from.display_name.as_deref(), Some("Bob \"B\""));
+        assert_eq!(from.uri.sip().unwrap().hostport.host, "biloxi.com");
+        assert_eq!(from.tag.as_deref(), Some("a48s"));
+        assert_eq!(from.params.get("x"), Some(None));
+
+        let header = Header::from_name_value("To", "sip:alice@atlanta.com").unwrap();
+        let to = NameAddrHeader::from_header(&header).unwrap();
+        assert_eq!(to.display_name, None);
+        assert_eq!(to.tag, None);
+        assert!(to.params.is_empty());
+
+        let header = Header::from_name_value("Contact", "Alice <tel:+1-201-555-0123>").unwrap();
+        let contact = NameAddrHeader::from_header(&header).unwrap().into_owned();
+        assert_eq!(contact.display_name.as_deref(), Some("Alice"));
+        assert!(contact.uri.tel().unwrap().is_global());
+
+        let header = Header::from_name_value("Contact", "*").unwrap();
+        assert!(NameAddrHeader::from_header(&header).is_err());
+    }
+
+    #[test]
+    fn name_addr_header_round_trip() {
+        let value = "\"Bob \\\"B\\\"\" <sips:bob@biloxi.com>;x;tag=a48s;y=1";
+        let header = Header::from_name_value("From", value).unwrap();
+        let mut from = NameAddrHeader::from_header(&header).unwrap();
+        assert_eq!(from.to_string(), value);
+
+        from.tag = Some(Cow::Borrowed("b59t"));
+        assert_eq!(
+            from.to_string(),
+            "\"Bob \\\"B\\\"\" <sips:bob@biloxi.com>;x;tag=b59t;y=1"
+        );
+        from.tag = None;
+        assert_eq!(
+            from.to_string(),
+            "\"Bob \\\"B\\\"\" <sips:bob@biloxi.com>;x;y=1"
+        );
+
+        let header = Header::from_name_value("To", "<sip:alice@atlanta.com>").unwrap();
+        let mut to = NameAddrHeader::from_header(&header).unwrap();
+        assert_eq!(to.to_string(), "<sip:alice@atlanta.com>");
+        to.tag = Some(Cow::Borrowed("abc"));
+        assert_eq!(to.to_string(), "<sip:alice@atlanta.com>;tag=abc");
+        let header = Header::from_name_value("To", &to.to_string()).unwrap();
+        let to = NameAddrHeader::from_header(&header).unwrap();
+        assert_eq!(to.tag.as_deref(), Some("abc"));
+    }
+}
@@ -0,0 +1,109 @@
+use crate::{
+    common::{bnfcore::is_digit, errorparse::SipParseError},
+    headers::{
+        header::Header,
+        typed::{invalid_value, TypedHeader},
+    },
+};
+use core::fmt;
+
+fn digits<'h>(header: &'h Header<'h>) -> Result<&'h str, SipParseError<'static>> {
+    let value = header.value.vstr.as_ref();
+    if value.is_empty() || !value.bytes().all(is_digit) {
+        return Err(invalid_value("Value must contain digits only"));
+    }
+    Ok(value)
+}
+
+/// Max-Forwards  =  "Max-Forwards" HCOLON 1*DIGIT
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct MaxForwards(pub u8);
+
+impl<'h> TypedHeader<'h> for MaxForwards {
+    fn from_header(header: &'h Header<'h>) -> Result<MaxForwards, SipParseError<'static>> {
+        digits(header)?
+            .parse()
+            .map(MaxForwards)
+            .map_err(|_| invalid_value("Max-Forwards must be in range 0-255"))
+    }
+}
+
+impl fmt::Display for MaxForwards {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// Expires  =  "Expires" HCOLON delta-seconds.
+/// Also used for Min-Expires.
+/// Values larger than 2**32-1 are taken as 2**32-1
+/// ([rfc3261 section-25.1](https://tools.ietf.org/html/rfc3261#section-25.1))
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Expires(pub u32);
+
+impl<'h> TypedHeader<'h> for Expires {
+    fn from_header(header: &'h Header<'h>) -> Result<Expires, SipParseError<'static>> {
+        let value = digits(header)?;
+        Ok(Expires(value.parse().unwrap_or(u32::MAX)))
+    }
+}
+
+impl fmt::Display for Expires {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// RSeq  =  "RSeq" HCOLON response-num,
+/// response-num is in range 1 - 2**31-1
+/// ([rfc3262](https://tools.ietf.org/html/rfc3262#section-7.1))
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct RSeq(pub u32);
+
+impl<'h> TypedHeader<'h> for RSeq {
+    fn from_header(header: &'h Header<'h>) -> Result<RSeq, SipParseError<'static>> {
+        digits(header)?
+            .parse()
+            .ok()
+            .filter(|num| *num >= 1 && *num < 1 << 31)
+            .map(RSeq)
+            .ok_or_else(|| invalid_value("RSeq must be in range 1 - 2**31-1"))
+    }
+}
+
+impl fmt::Display for RSeq {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn max_forwards_header() {
+        let header = Header::from_name_value("Max-Forwards", "70").unwrap();
+        assert_eq!(MaxForwards::from_header(&header).unwrap(), MaxForwards(70));
+        let header = Header::from_name_value("Max-Forwards", "256").unwrap();
+        assert!(MaxForwards::from_header(&header).is_err());
+    }
+
+    #[test]
+    fn expires_header() {
+        let header = Header::from_name_value("Expires", "3600").unwrap();
+        assert_eq!(Expires::from_header(&header).unwrap(), Expires(3600));
+        let header = Header::from_name_value("Min-Expires", "99999999999").unwrap();
+        assert_eq!(Expires::from_header(&header).unwrap(), Expires(u32::MAX));
+    }
+
+    #[test]
+    fn rseq_header() {
+        let header = Header::from_name_value("RSeq", "988789").unwrap();
+        assert_eq!(RSeq::from_header(&header).unwrap(), RSeq(988789));
+        let header = Header::from_name_value("RSeq", "0").unwrap();
+        assert!(RSeq::from_header(&header).is_err());
+        let header = Header::from_name_value("RSeq", "2147483648").unwrap();
+        assert!(RSeq::from_header(&header).is_err());
+    }
+}
@@ -0,0 +1,183 @@
+use crate::{
+    common::errorparse::SipParseError,
+    headers::{
+        header::Header,
+        typed::{invalid_value, TypedHeader},
+    },
+};
+use alloc::{borrow::Cow, vec::Vec};
+use core::fmt;
+use unicase::Ascii;
+
+/// priv-value of Privacy header
+#[derive(Clone, PartialEq, Debug)]
+pub enum PrivacyValue<'a> {
+    /// Headers that can identify the user must be obscured
+    Header,
+    /// Session description must be obscured
+    Session,
+    /// User-level privacy, requested by intermediaries
+    User,
+    /// No privacy functions are requested
+    None,
+    /// Privacy functions must be applied or the request must be rejected
+    Critical,
+    /// P-Asserted-Identity must not be passed outside of the trust domain
+    /// ([rfc3325](https://tools.ietf.org/html/rfc3325#section-9.3))
+    Id,
+    /// token of extension
+    Other(Cow<'a, str>),
+}
+
+impl<'a> PrivacyValue<'a> {
+    pub fn from_str(value: &'a str) -> PrivacyValue<'a> {
+        let avalue = Ascii::new(value);
+        if avalue == "header" {
+            PrivacyValue::Header
+        } else if avalue == "session" {
+            PrivacyValue::Session
+        } else if avalue == "user" {
+            PrivacyValue::User
+        } else if avalue == "none" {
+            PrivacyValue::None
+        } else if avalue == "critical" {
+            PrivacyValue::Critical
+        } else if avalue == "id" {
+            PrivacyValue::Id
+        } else {
+            PrivacyValue::Other(Cow::Borrowed(value))
+        }
+    }
+
+    pub fn as_str(&self) -> &str {
+        match self {
+            PrivacyValue::Header => "header",
+            PrivacyValue::Session => "session",
+            PrivacyValue::User => "user",
+            PrivacyValue::None => "none",
+            PrivacyValue::Critical => "critical",
+            PrivacyValue::Id => "id",
+            PrivacyValue::Other(value) => value,
+        }
+    }
+
+    pub fn into_owned(self) -> PrivacyValue<'static> {
+        match self {
+            PrivacyValue::Header => PrivacyValue::Header,
+            PrivacyValue::Session => PrivacyValue::Session,
+            PrivacyValue::User => PrivacyValue::User,
+            PrivacyValue::None => PrivacyValue::None,
+            PrivacyValue::Critical => PrivacyValue::Critical,
+            PrivacyValue::Id => PrivacyValue::Id,
+            PrivacyValue::Other(value) => PrivacyValue::Other(Cow::Owned(value.into_owned())),
+        }
+    }
+}
+
+impl<'a> fmt::Display for PrivacyValue<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// Privacy-hdr  =  "Privacy" HCOLON priv-value *(";" priv-value)
+/// ([rfc3323](https://tools.ietf.org/html/rfc3323#section-4.2)).
+/// Several Privacy headers of the message are decoded one by one,
+/// use [`get_typed_all`](crate::SipHeaders::get_typed_all) to decode all of them.
+///
+/// ```rust
+/// use sipmsg::*;
+///
+/// let (_, hdrs) = SipHeaders::parse(
+///     "P-Asserted-Identity: \"Cullen Jennings\" <sip:fluffy@cisco.com>, tel:+14085264000\r\n\
+///      Privacy: id;critical\r\n\r\n"
+///         .as_bytes(),
+/// )
+/// .unwrap();
+/// let privacy: PrivacyHeader = hdrs.get_typed(SipRFCHeader::Privacy).unwrap().unwrap();
+/// assert_eq!(privacy.values, [PrivacyValue::Id, PrivacyValue::Critical]);
+/// assert!(privacy.contains(&PrivacyValue::Id));
+///
+/// let identities: Vec<NameAddrHeader> = hdrs
+///     .get_typed_all(SipRFCHeader::PAssertedIdentity)
+///     .unwrap()
+///     .unwrap();
+/// assert_eq!(identities.len(), 2);
+/// assert_eq!(identities[0].display_name.as_deref(), Some("Cullen Jennings"));
+/// assert!(identities[0].uri.sip().is_some());
+/// assert!(identities[1].uri.tel().is_some());
+/// ```
+#[derive(Clone, PartialEq, Debug)]
+pub struct PrivacyHeader<'h> {
+    pub values: Vec<PrivacyValue<'h>>,
+}
+
+impl<'h> PrivacyHeader<'h> {
+    pub fn contains(&self, value: &PrivacyValue) -> bool {
+        self.values.iter().any(|v| v == value)
+    }
+
+    /// `true` if privacy is explicitly not requested with `none` value
+    pub fn is_none(&self) -> bool {
+        self.contains(&PrivacyValue::None)
+    }
+
+    pub fn into_owned(self) -> PrivacyHeader<'static> {
+        PrivacyHeader {
+            values: self.values.into_iter().map(|v| v.into_owned()).collect(),
+        }
+    }
+}
+
+impl<'h> TypedHeader<'h> for PrivacyHeader<'h> {
+    fn from_header(header: &'h Header<'h>) -> Result<PrivacyHeader<'h>, SipParseError<'static>> {
+        let value: &'h str = header.value.vstr.as_ref();
+        let values: Vec<PrivacyValue<'h>> = value
+            .split(';')
+            .map(|v| PrivacyValue::from_str(v.trim()))
+            .collect();
+        if values.iter().any(|v| v.as_str().is_empty()) {
+            return Err(invalid_value("Invalid priv-value"));
+        }
+        Ok(PrivacyHeader { values })
+    }
+}
+
+impl<'h> fmt::Display for PrivacyHeader<'h> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (i, value) in self.values.iter().enumerate() {
+            if i > 0 {
+                write!(f, ";")?;
+            }
+            write!(f, "{}", value)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::ToString;
+
+    #[test]
+    fn privacy_header() {
+        let header = Header::from_name_value("Privacy", "Header ; user;x-custom").unwrap();
+        let privacy = PrivacyHeader::from_header(&header).unwrap();
+        assert_eq!(
+            privacy.values,
+            [
+                PrivacyValue::Header,
+                PrivacyValue::User,
+                PrivacyValue::Other(Cow::Borrowed("x-custom"))
+            ]
+        );
+        assert!(!privacy.is_none());
+        assert_eq!(privacy.to_string(), "header;user;x-custom");
+
+        let header = Header::from_name_value("Privacy", "none").unwrap();
+        let privacy = PrivacyHeader::from_header(&header).unwrap();
+        assert!(privacy.is_none());
+        assert!(header.params().is_none());
+    }
+}
@@ -0,0 +1,107 @@
+use crate::{
+    common::{errorparse::SipParseError, sip_method::SipMethod},
+    headers::{
+        header::{Header, HeaderTagType},
+        typed::{invalid_value, tag_str, TypedHeader},
+    },
+};
+use core::fmt;
+
+/// RAck  =  "RAck" HCOLON response-num LWS CSeq-num LWS Method
+/// ([rfc3262](https://tools.ietf.org/html/rfc3262#section-7.2)).
+/// Acknowledges reliable provisional response in PRACK request
+///
+/// ```rust
+/// use sipmsg::*;
+///
+/// let rack = RAckHeader {
+///     response_num: 776656,
+///     cseq_num: 1,
+///     method: SipMethod::INVITE,
+/// };
+/// let (_, uri) = SipUri::parse(b"sip:bob@biloxi.com").unwrap();
+/// let msg_buf = SipRequestBuilder::new(SipMethod::PRACK, uri)
+///     .header(SipRFCHeader::CSeq, "2 PRACK")
+///     .header(SipRFCHeader::RAck, rack.to_string())
+///     .build()
+///     .unwrap();
+/// let (_, msg) = SipMessage::parse(&msg_buf).unwrap();
+/// let received: RAckHeader = msg
+///     .request()
+///     .unwrap()
+///     .headers
+///     .get_typed(SipRFCHeader::RAck)
+///     .unwrap()
+///     .unwrap();
+/// assert_eq!(received, rack);
+/// ```
+#[derive(Clone, PartialEq, Debug)]
+pub struct RAckHeader<'h> {
+    /// Value of RSeq header of the response
+    pub response_num: u32,
+    /// CSeq number of the response
+    pub cseq_num: u32,
+    /// CSeq method of the response
+    pub method: SipMethod<'h>,
+}
+
+impl<'h> RAckHeader<'h> {
+    pub fn into_owned(self) -> RAckHeader<'static> {
+        RAckHeader {
+            response_num: self.response_num,
+            cseq_num: self.cseq_num,
+            method: self.method.into_owned(),
+        }
+    }
+}
+
+impl<'h> TypedHeader<'h> for RAckHeader<'h> {
+    fn from_header(header: &'h Header<'h>) -> Result<RAckHeader<'h>, SipParseError<'static>> {
+        let number = |tag, message| {
+            tag_str(header, tag)
+                .and_then(|num| num.parse::<u32>().ok())
+                .filter(|num| *num < 1 << 31)
+                .ok_or_else(|| invalid_value(message))
+        };
+        let response_num = number(HeaderTagType::ResponseNum, "Invalid RAck response-num")?;
+        if response_num == 0 {
+            return Err(invalid_value("Invalid RAck response-num"));
+        }
+        let cseq_num = number(HeaderTagType::Number, "Invalid RAck CSeq-num")?;
+        let method = tag_str(header, HeaderTagType::Method)
+            .and_then(SipMethod::from_str)
+            .ok_or_else(|| invalid_value("Invalid RAck method"))?;
+        Ok(RAckHeader {
+            response_num,
+            cseq_num,
+            method,
+        })
+    }
+}
+
+impl<'h> fmt::Display for RAckHeader<'h> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} {} {}", self.response_num, self.cseq_num, self.method)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::ToString;
+
+    #[test]
+    fn rack_header() {
+        let header = Header::from_name_value("RAck", "776656 1 INVITE").unwrap();
+        let rack = RAckHeader::from_header(&header).unwrap();
+        assert_eq!(rack.response_num, 776656);
+        assert_eq!(rack.cseq_num, 1);
+        assert_eq!(rack.method, SipMethod::INVITE);
+        assert_eq!(rack.to_string(), "776656 1 INVITE");
+
+        let header = Header::from_name_value("RAck", "0 1 INVITE").unwrap();
+        assert!(RAckHeader::from_header(&header).is_err());
+        let header = Header::from_name_value("RAck", "1 2147483648 INVITE").unwrap();
+        assert!(RAckHeader::from_header(&header).is_err());
+    }
+}
@@ -0,0 +1,240 @@
+use crate::{
+    common::{
+        errorparse::SipParseError,
+        hostport::{Host, HostPort},
+    },
+    headers::{
+        header::{Header, HeaderTagType},
+        typed::{invalid_value, tag_str, TypedHeader},
+        uri_params::{parse_ttl, Transport},
+        GenericParams,
+    },
+};
+use alloc::borrow::Cow;
+use core::{fmt, net::IpAddr};
+
+/// via-parm = sent-protocol LWS sent-by *( SEMI via-params )
+#[derive(Clone, PartialEq, Debug)]
+pub struct ViaHeader<'h> {
+    /// protocol-name, usually `SIP`
+    pub protocol_name: Cow<'h, str>,
+    /// protocol-version, usually `2.0`
+    pub protocol_version: Cow<'h, str>,
+    pub transport: Transport<'h>,
+    pub sent_by: HostPort<'h>,
+    pub branch: Option<Cow<'h, str>>,
+    /// IPv6 address is accepted with or without brackets
+    pub received: Option<IpAddr>,
+    /// [rfc3581](https://tools.ietf.org/html/rfc3581),
+    /// `Some(None)` if parameter is present without value
+    pub rport: Option<Option<u16>>,
+    pub maddr: Option<Host<'h>>,
+    pub ttl: Option<u8>,
+    /// Parameters that are not decoded to the fields above, e.g. `alias` or `sigcomp-id`
+    pub params: GenericParams<'h>,
+}
+
+impl<'h> ViaHeader<'h> {
+    pub fn into_owned(self) -> ViaHeader<'static> {
+        ViaHeader {
+            protocol_name: Cow::Owned(self.protocol_name.into_owned()),
+            protocol_version: Cow::Owned(self.protocol_version.into_owned()),
+            transport: self.transport.into_owned(),
+            sent_by: self.sent_by.into_owned(),
+            branch: self.branch.map(|b| Cow::Owned(b.into_owned())),
+            received: self.received,
+            rport: self.rport,
+            maddr: self.maddr.map(|m| m.into_owned()),
+            ttl: self.ttl,
+            params: self.params.into_owned(),
+        }
+    }
+}
+
+impl<'h> TypedHeader<'h> for ViaHeader<'h> {
+    fn from_header(header: &'h Header<'h>) -> Result<ViaHeader<'h>, SipParseError<'static>> {
+        let tag = |tag| tag_str(header, tag).ok_or_else(|| invalid_value("Via value is expected"));
+        let transport = Transport::from_str(tag(HeaderTagType::ProtocolTransport)?)
+            .ok_or_else(|| invalid_value("Invalid Via transport"))?;
+        let host = Host::from_str(tag(HeaderTagType::Host)?)
+            .ok_or_else(|| invalid_value("Invalid Via host"))?;
+        let port = match tag_str(header, HeaderTagType::Port) {
+            Some(port) => Some(
+                port.parse()
+                    .map_err(|_| invalid_value("Invalid Via port"))?,
+            ),
+            None => None,
+        };
+
+        let mut via = ViaHeader {
+            protocol_name: Cow::Borrowed(tag(HeaderTagType::ProtocolName)?),
+            protocol_version: Cow::Borrowed(tag(HeaderTagType::ProtocolVersion)?),
+            transport,
+            sent_by: HostPort { host, port },
+            branch: None,
+            received: None,
+            rport: None,
+            maddr: None,
+            ttl: None,
+            params: GenericParams::new(),
+        };
+        let params = match header.params() {
+            Some(params) => params,
+            None => return Ok(via),
+        };
+        via.branch = params.get("branch").flatten().map(Cow::Borrowed);
+        if let Some(received) = params.get("received") {
+            via.received = Some(
+                received
+                    .map(|r| r.trim_start_matches('[').trim_end_matches(']'))
+                    .and_then(|r| r.parse().ok())
+                    .ok_or_else(|| invalid_value("Invalid Via received parameter"))?,
+            );
+        }
+        if let Some(rport) = params.get("rport") {
+            via.rport = Some(match rport {
+                Some(rport) => Some(
+                    rport
+                        .parse()
+                        .map_err(|_| invalid_value("Invalid Via rport parameter"))?,
+                ),
+                None => None,
+            });
+        }
+        if let Some(maddr) = params.get("maddr") {
+            via.maddr = Some(
+                maddr
+                    .and_then(Host::from_str)
+                    .ok_or_else(|| invalid_value("Invalid Via maddr parameter"))?,
+            );
+        }
+        if let Some(ttl) = params.get("ttl") {
+            via.ttl = Some(
+                ttl.and_then(parse_ttl)
+                    .ok_or_else(|| invalid_value("Invalid Via ttl parameter"))?,
+            );
+        }
+        via.params = params.clone();
+        for name in ["branch", "received", "rport", "maddr", "ttl"].iter() {
+            via.params.remove(name);
+        }
+        Ok(via)
+    }
+}
+
+/// Writes IPv6 address in brackets as it is used in parameters
+fn write_param_host(f: &mut fmt::Formatter, host: &Host) -> fmt::Result {
+    match host {
+        Host::Ipv6(_) => write!(f, "[{}]", host),
+        _ => write!(f, "{}", host),
+    }
+}
+
+/// Decoded parameters are written first, then the other parameters
+impl<'h> fmt::Display for ViaHeader<'h> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}/{}/{} {}",
+            self.protocol_name,
+            self.protocol_version,
+            self.transport.as_str().to_ascii_uppercase(),
+            self.sent_by
+        )?;
+        if let Some(branch) = &self.branch {
+            write!(f, ";branch={}", branch)?;
+        }
+        match self.received {
+            Some(IpAddr::V6(addr)) => write!(f, ";received=[{}]", addr)?,
+            Some(IpAddr::V4(addr)) => write!(f, ";received={}", addr)?,
+            None => {}
+        }
+        match self.rport {
+            Some(Some(rport)) => write!(f, ";rport={}", rport)?,
+            Some(None) => write!(f, ";rport")?,
+            None => {}
+        }
+        if let Some(maddr) = &self.maddr {
+            write!(f, ";maddr=")?;
+            write_param_host(f, maddr)?;
+        }
+        if let Some(ttl) = self.ttl {
+            write!(f, ";ttl={}", ttl)?;
+        }
+        write!(f, "{}", self.params)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::ToString;
+    use core::net::Ipv6Addr;
+
+    #[test]
+    fn via_header() {
+        let header = Header::from_name_value(
+            "Via",
+            "SIP/2.0/TLS [2001:db8::9]:5061;branch=z9hG4bK1;received=[2001:db8::1];rport=5062;maddr=224.2.0.1;ttl=16;x",
+        )
+        .unwrap();
+        let v = ViaHeader::from_header(&header).unwrap();
+        assert_eq!(v.protocol_name, "SIP");
+        assert_eq!(v.protocol_version, "2.0");
+        assert_eq!(v.transport, Transport::Tls);
+        assert_eq!(v.sent_by.host, "2001:db8::9");
+        assert_eq!(v.sent_by.port, Some(5061));
+        assert_eq!(v.branch.as_deref(), Some("z9hG4bK1"));
+        assert_eq!(
+            v.received,
+            Some(IpAddr::V6(Ipv6Addr::new(0x2001, 0xdb8, 0, 0, 0, 0, 0, 1)))
+        );
+        assert_eq!(v.rport, Some(Some(5062)));
+        assert_eq!(v.maddr.unwrap(), "224.2.0.1");
+        assert_eq!(v.ttl, Some(16));
+        assert_eq!(v.params.to_string(), ";x");
+
+        let header = Header::from_name_value("Via", "SIP/2.0/UDP pc33.atlanta.com").unwrap();
+        let v = ViaHeader::from_header(&header).unwrap();
+        assert_eq!(v.transport, Transport::Udp);
+        assert_eq!(v.sent_by.host, "pc33.atlanta.com");
+        assert_eq!(v.sent_by.port, None);
+        assert_eq!(v.branch, None);
+        assert_eq!(v.rport, None);
+    }
+
+    #[test]
+    fn via_header_round_trip() {
+        let value = "SIP/2.0/UDP 192.0.2.1:5060;branch=z9hG4bK1;rport;alias;keep=30;\
+                     sigcomp-id=\"urn:uuid:0C67446E-F1A1-11D9-94D3-000A95A0E128\"";
+        let header = Header::from_name_value("Via", value).unwrap();
+        let v = ViaHeader::from_header(&header).unwrap();
+        assert_eq!(v.params.len(), 3);
+        assert_eq!(v.params.get("keep"), Some(Some("30")));
+        assert_eq!(v.to_string(), value);
+
+        let mut v = v.into_owned();
+        v.received = "192.0.2.2".parse().ok();
+        assert_eq!(
+            v.to_string(),
+            "SIP/2.0/UDP 192.0.2.1:5060;branch=z9hG4bK1;received=192.0.2.2;rport;alias;keep=30;\
+             sigcomp-id=\"urn:uuid:0C67446E-F1A1-11D9-94D3-000A95A0E128\""
+        );
+    }
+
+    #[test]
+    fn via_header_invalid() {
+        for value in [
+            "SIP/2.0/UDP host;received=host.com",
+            "SIP/2.0/UDP host;rport=70000",
+            "SIP/2.0/UDP host;ttl=256",
+            "SIP/2.0/UDP -host",
+            "SIP/2.0/UDP host:99999",
+        ]
+        .iter()
+        {
+            let header = Header::from_name_value("Via", value).unwrap();
+            assert!(ViaHeader::from_header(&header).is_err());
+        }
+    }
+}
@@ -0,0 +1,203 @@
+use crate::{
+    common::errorparse::SipParseError,
+    headers::{absolute_uri::scheme_len, AbsoluteUri, SipUri, TelUri},
+};
+use core::fmt;
+
+/// URI of Request-Line, name-addr header value (Contact, From, To, Route...)
+/// or Call-Info, Alert-Info and Error-Info.
+/// URIs of schemes other than `sip`, `sips` and `tel` are kept in generic form of rfc3986.
+///
+/// ```rust
+/// use sipmsg::Uri;
+///
+/// let (_, uri) = Uri::parse(b"urn:service:sos").unwrap();
+/// assert_eq!(uri.scheme(), "urn");
+/// assert_eq!(uri.absolute().unwrap().path, "service:sos");
+///
+/// let (_, uri) = Uri::parse(b"sip:alice@atlanta.com").unwrap();
+/// assert_eq!(uri.sip().unwrap().hostport.host, "atlanta.com");
+/// ```
+#[derive(Clone, PartialEq, Debug)]
+pub enum Uri<'a> {
+    /// `sip:` or `sips:` URI
+    Sip(SipUri<'a>),
+    /// `tel:` URI
+    Tel(TelUri<'a>),
+    /// `urn:` URI ([rfc8141](https://tools.ietf.org/html/rfc8141))
+    Urn(AbsoluteUri<'a>),
+    /// `http:` or `https:` URI
+    Http(AbsoluteUri<'a>),
+    /// `mailto:` URI
+    Mailto(AbsoluteUri<'a>),
+    /// `im:` URI ([rfc3860](https://tools.ietf.org/html/rfc3860))
+    Im(AbsoluteUri<'a>),
+    /// `pres:` URI ([rfc3859](https://tools.ietf.org/html/rfc3859))
+    Pres(AbsoluteUri<'a>),
+    /// URI of any other scheme
+    Other(AbsoluteUri<'a>),
+}
+
+impl<'a> Uri<'a> {
+    pub fn sip(&self) -> Option<&SipUri<'a>> {
+        match self {
+            Uri::Sip(uri) => Some(uri),
+            _ => None,
+        }
+    }
+
+    pub fn tel(&self) -> Option<&TelUri<'a>> {
+        match self {
+            Uri::Tel(uri) => Some(uri),
+            _ => None,
+        }
+    }
+
+    /// Returns generic form of URI that is not `sip`, `sips` or `tel`
+    pub fn absolute(&self) -> Option<&AbsoluteUri<'a>> {
+        match self {
+            Uri::Sip(_) | Uri::Tel(_) => None,
+            Uri::Urn(uri)
+            | Uri::Http(uri)
+            | Uri::Mailto(uri)
+            | Uri::Im(uri)
+            | Uri::Pres(uri)
+            | Uri::Other(uri) => Some(uri),
+        }
+    }
+
+    /// Scheme as it is written in URI, `sip` and `sips` are always lowercase
+    pub fn scheme(&self) -> &str {
+        match self {
+            Uri::Sip(uri) => uri.scheme.as_str(),
+            Uri::Tel(_) => "tel",
+            _ => self.absolute().map_or("", |uri| &uri.scheme),
+        }
+    }
+
+    pub fn into_owned(self) -> Uri<'static> {
+        match self {
+            Uri::Sip(uri) => Uri::Sip(uri.into_owned()),
+            Uri::Tel(uri) => Uri::Tel(uri.into_owned()),
+            Uri::Urn(uri) => Uri::Urn(uri.into_owned()),
+            Uri::Http(uri) => Uri::Http(uri.into_owned()),
+            Uri::Mailto(uri) => Uri::Mailto(uri.into_owned()),
+            Uri::Im(uri) => Uri::Im(uri.into_owned()),
+            Uri::Pres(uri) => Uri::Pres(uri.into_owned()),
+            Uri::Other(uri) => Uri::Other(uri.into_owned()),
+        }
+    }
+
+    /// Returns `true` if input starts with `scheme ":"`
+    pub(crate) fn is_uri(input: &[u8]) -> bool {
+        scheme_len(input).is_some()
+    }
+
+    /// Parses URI according to the scheme.
+    /// `parse_with_parameters` has the same meaning as in `SipUri::parse_ext`
+    pub fn parse_ext(
+        input: &'a [u8],
+        parse_with_parameters: bool,
+    ) -> nom::IResult<&'a [u8], Uri<'a>, SipParseError<'a>> {
+        let scheme = match scheme_len(input) {
+            Some(len) => &input[..len],
+            None => return sip_parse_error!(InvalidUri, "Invalid URI scheme"),
+        };
+        if scheme.eq_ignore_ascii_case(b"sip") || scheme.eq_ignore_ascii_case(b"sips") {
+            let (input, uri) = SipUri::parse_ext(input, parse_with_parameters)?;
+            return Ok((input, Uri::Sip(uri)));
+        }
+        if scheme.eq_ignore_ascii_case(b"tel") {
+            let (input, uri) = TelUri::parse_ext(input, parse_with_parameters)?;
+            return Ok((input, Uri::Tel(uri)));
+        }
+
+        let (input, uri) = AbsoluteUri::parse_ext(input, parse_with_parameters)?;
+        let uri = if scheme.eq_ignore_ascii_case(b"urn") {
+            Uri::Urn(uri)
+        } else if scheme.eq_ignore_ascii_case(b"http") || scheme.eq_ignore_ascii_case(b"https") {
+            Uri::Http(uri)
+        } else if scheme.eq_ignore_ascii_case(b"mailto") {
+            Uri::Mailto(uri)
+        } else if scheme.eq_ignore_ascii_case(b"im") {
+            Uri::Im(uri)
+        } else if scheme.eq_ignore_ascii_case(b"pres") {
+            Uri::Pres(uri)
+        } else {
+            Uri::Other(uri)
+        };
+        Ok((input, uri))
+    }
+
+    pub fn parse(input: &'a [u8]) -> nom::IResult<&'a [u8], Uri<'a>, SipParseError<'a>> {
+        Uri::parse_ext(input, true)
+    }
+}
+
+impl<'a> From<SipUri<'a>> for Uri<'a> {
+    fn from(uri: SipUri<'a>) -> Uri<'a> {
+        Uri::Sip(uri)
+    }
+}
+
+impl<'a> From<TelUri<'a>> for Uri<'a> {
+    fn from(uri: TelUri<'a>) -> Uri<'a> {
+        Uri::Tel(uri)
+    }
+}
+
+impl<'a> fmt::Display for Uri<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Uri::Sip(uri) => write!(f, "{}", uri),
+            Uri::Tel(uri) => write!(f, "{}", uri),
+            _ => match self.absolute() {
+                Some(uri) => write!(f, "{}", uri),
+                None => Ok(()),
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::ToString;
+
+    #[test]
+    fn uri_parse_schemes() {
+        let cases: [(&[u8], &str); 8] = [
+            (b"sips:bob@biloxi.com", "sips"),
+            (b"tel:+1-201-555-0123", "tel"),
+            (b"urn:service:sos", "urn"),
+            (b"https://www.example.com/alice/photo.jpg", "https"),
+            (b"mailto:alice@atlanta.com", "mailto"),
+            (b"im:alice@atlanta.com", "im"),
+            (b"pres:alice@atlanta.com", "pres"),
+            (b"cid:abc@atlanta.com", "cid"),
+        ];
+        for (input, scheme) in cases.iter() {
+            let (rest, uri) = Uri::parse(input).unwrap();
+            assert_eq!(rest.len(), 0);
+            assert_eq!(uri.scheme(), *scheme);
+            assert_eq!(uri.to_string().as_bytes(), *input);
+        }
+
+        assert!(matches!(Uri::parse(b"URN:x:y").unwrap().1, Uri::Urn(_)));
+        assert!(matches!(Uri::parse(b"im:a@b").unwrap().1, Uri::Im(_)));
+        assert!(matches!(Uri::parse(b"pres:a@b").unwrap().1, Uri::Pres(_)));
+        assert!(matches!(
+            Uri::parse(b"mailto:a@b").unwrap().1,
+            Uri::Mailto(_)
+        ));
+        assert!(matches!(Uri::parse(b"http://a").unwrap().1, Uri::Http(_)));
+        assert!(Uri::parse(b"alice@atlanta.com").is_err());
+        let (_, uri) = Uri::parse(b"SIP:ALICE@AtLanTa.CoM;Transport=udp").unwrap();
+        assert_eq!(uri.scheme(), "sip");
+        assert_eq!(uri.sip().unwrap().hostport.host, "AtLanTa.CoM");
+        assert!(matches!(
+            Uri::parse(b"Sips:bob@biloxi.com").unwrap().1,
+            Uri::Sip(_)
+        ));
+    }
+}
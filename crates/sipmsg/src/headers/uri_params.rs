@@ -0,0 +1,335 @@
+use crate::common::{
+    bnfcore::{is_alphanum, is_digit, is_hexdig, is_token_char},
+    errorparse::SipParseError,
+    sip_method::SipMethod,
+};
+use crate::headers::GenericParams;
+use alloc::borrow::Cow;
+use core::fmt;
+use unicase::Ascii;
+
+/// transport-param = "transport=" ( "udp" / "tcp" / "sctp" / "tls" / other-transport )
+/// extended by "ws" and "wss" of [rfc7118](https://tools.ietf.org/html/rfc7118)
+#[derive(Clone, PartialEq, Debug)]
+pub enum Transport<'a> {
+    Udp,
+    Tcp,
+    Tls,
+    Sctp,
+    /// `tls-sctp` of [rfc4168](https://tools.ietf.org/html/rfc4168)
+    TlsSctp,
+    Ws,
+    Wss,
+    /// other-transport, contains token characters only
+    Other(Cow<'a, str>),
+}
+
+impl<'a> Transport<'a> {
+    /// Transport is matched case-insensitively.
+    /// Returns None if `s` is not a token
+    pub fn from_str(s: &'a str) -> Option<Transport<'a>> {
+        if s.is_empty() || !s.bytes().all(is_token_char) {
+            return None;
+        }
+        let transport = Ascii::new(s);
+        let result = if transport == "udp" {
+            Transport::Udp
+        } else if transport == "tcp" {
+            Transport::Tcp
+        } else if transport == "tls" {
+            Transport::Tls
+        } else if transport == "sctp" {
+            Transport::Sctp
+        } else if transport == "tls-sctp" {
+            Transport::TlsSctp
+        } else if transport == "ws" {
+            Transport::Ws
+        } else if transport == "wss" {
+            Transport::Wss
+        } else {
+            Transport::Other(Cow::Borrowed(s))
+        };
+        Some(result)
+    }
+
+    /// Lowercase name as it is used in transport parameter.
+    /// Via header uses the same names in uppercase
+    pub fn as_str(&self) -> &str {
+        match self {
+            Transport::Udp => "udp",
+            Transport::Tcp => "tcp",
+            Transport::Tls => "tls",
+            Transport::Sctp => "sctp",
+            Transport::TlsSctp => "tls-sctp",
+            Transport::Ws => "ws",
+            Transport::Wss => "wss",
+            Transport::Other(transport) => transport,
+        }
+    }
+
+    /// Returns `true` for connection-oriented transports.
+    /// `Other` transport is considered unreliable
+    pub fn is_reliable(&self) -> bool {
+        !matches!(self, Transport::Udp | Transport::Other(_))
+    }
+
+    /// Returns `true` if transport is protected by TLS
+    pub fn is_secure(&self) -> bool {
+        matches!(self, Transport::Tls | Transport::TlsSctp | Transport::Wss)
+    }
+
+    /// Default port of transport: 5060, 5061 for TLS,
+    /// 80 and 443 for WebSocket ([rfc7118](https://tools.ietf.org/html/rfc7118))
+    pub fn default_port(&self) -> u16 {
+        match self {
+            Transport::Tls | Transport::TlsSctp => 5061,
+            Transport::Ws => 80,
+            Transport::Wss => 443,
+            _ => 5060,
+        }
+    }
+
+    pub fn into_owned(self) -> Transport<'static> {
+        match self {
+            Transport::Udp => Transport::Udp,
+            Transport::Tcp => Transport::Tcp,
+            Transport::Tls => Transport::Tls,
+            Transport::Sctp => Transport::Sctp,
+            Transport::TlsSctp => Transport::TlsSctp,
+            Transport::Ws => Transport::Ws,
+            Transport::Wss => Transport::Wss,
+            Transport::Other(transport) => Transport::Other(Cow::Owned(transport.into_owned())),
+        }
+    }
+}
+
+impl<'a> fmt::Display for Transport<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// user-param = "user=" ( "phone" / "ip" / other-user )
+#[derive(Clone, PartialEq, Debug)]
+pub enum UserParam<'a> {
+    Phone,
+    Ip,
+    /// other-user, contains token characters only
+    Other(Cow<'a, str>),
+}
+
+impl<'a> UserParam<'a> {
+    /// Value is matched case-insensitively.
+    /// Returns None if `s` is not a token
+    pub fn from_str(s: &'a str) -> Option<UserParam<'a>> {
+        if s.is_empty() || !s.bytes().all(is_token_char) {
+            return None;
+        }
+        if s.eq_ignore_ascii_case("phone") {
+            Some(UserParam::Phone)
+        } else if s.eq_ignore_ascii_case("ip") {
+            Some(UserParam::Ip)
+        } else {
+            Some(UserParam::Other(Cow::Borrowed(s)))
+        }
+    }
+
+    pub fn as_str(&self) -> &str {
+        match self {
+            UserParam::Phone => "phone",
+            UserParam::Ip => "ip",
+            UserParam::Other(user) => user,
+        }
+    }
+
+    pub fn into_owned(self) -> UserParam<'static> {
+        match self {
+            UserParam::Phone => UserParam::Phone,
+            UserParam::Ip => UserParam::Ip,
+            UserParam::Other(user) => UserParam::Other(Cow::Owned(user.into_owned())),
+        }
+    }
+}
+
+impl<'a> fmt::Display for UserParam<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// ttl = 1*3DIGIT ; 0 to 255
+pub(crate) fn parse_ttl(value: &str) -> Option<u8> {
+    if value.is_empty() || value.len() > 3 || !value.bytes().all(is_digit) {
+        return None;
+    }
+    value.parse().ok()
+}
+
+/// maddr-param = "maddr=" host.
+/// IPv6 reference is stored by `GenericParams` without brackets
+pub(crate) fn is_maddr(value: &str) -> bool {
+    if value.is_empty() {
+        return false;
+    }
+    if value.contains(':') {
+        return value
+            .bytes()
+            .all(|c| is_hexdig(c) || c == b':' || c == b'.');
+    }
+    value
+        .bytes()
+        .all(|c| is_alphanum(c) || c == b'-' || c == b'.')
+}
+
+/// Typed view of SIP URI parameters of
+/// [rfc3261 section-19.1.1](https://tools.ietf.org/html/rfc3261#section-19.1.1)
+/// and common extensions. Other parameters are available through [`GenericParams`].
+///
+/// ```rust
+/// use sipmsg::*;
+///
+/// let (_, uri) =
+///     SipUri::parse(b"sip:+12125551212@gw.example.com;user=phone;transport=WSS;lr;ttl=15").unwrap();
+/// let params = uri.uri_params();
+/// assert_eq!(params.transport, Some(SipTransport::Wss));
+/// assert_eq!(params.user, Some(SipUserParam::Phone));
+/// assert_eq!(params.ttl, Some(15));
+/// assert!(params.lr);
+/// assert!(!params.ob);
+///
+/// assert!(SipUri::parse(b"sip:alice@atlanta.com;ttl=256").is_err());
+/// ```
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct UriParams<'a> {
+    pub transport: Option<Transport<'a>>,
+    pub user: Option<UserParam<'a>>,
+    pub method: Option<SipMethod<'a>>,
+    pub ttl: Option<u8>,
+    /// Host without brackets of IPv6 reference
+    pub maddr: Option<Cow<'a, str>>,
+    /// Loose routing ([rfc3261](https://tools.ietf.org/html/rfc3261#section-16.12.1.1))
+    pub lr: bool,
+    /// GRUU ([rfc5627](https://tools.ietf.org/html/rfc5627)),
+    /// `Some(None)` if parameter is present without value
+    pub gr: Option<Option<Cow<'a, str>>>,
+    /// Outbound ([rfc5626](https://tools.ietf.org/html/rfc5626))
+    pub ob: bool,
+}
+
+impl<'a> UriParams<'a> {
+    /// Validates known parameters. `lr` and `ob` are accepted with any value,
+    /// e.g. `lr=on` of old implementations
+    pub fn from_params(
+        params: &'a GenericParams,
+    ) -> Result<UriParams<'a>, nom::Err<SipParseError<'static>>> {
+        let mut result = UriParams::default();
+        if let Some(value) = params.get("transport") {
+            result.transport = Some(match value.and_then(Transport::from_str) {
+                Some(transport) => transport,
+                None => return sip_parse_error!(InvalidParameter, "Invalid transport parameter"),
+            });
+        }
+        if let Some(value) = params.get("user") {
+            result.user = Some(match value.and_then(UserParam::from_str) {
+                Some(user) => user,
+                None => return sip_parse_error!(InvalidParameter, "Invalid user parameter"),
+            });
+        }
+        if let Some(value) = params.get("method") {
+            result.method = Some(match value.and_then(SipMethod::from_str) {
+                Some(method) => method,
+                None => return sip_parse_error!(InvalidParameter, "Invalid method parameter"),
+            });
+        }
+        if let Some(value) = params.get("ttl") {
+            result.ttl = Some(match value.and_then(parse_ttl) {
+                Some(ttl) => ttl,
+                None => return sip_parse_error!(InvalidParameter, "Invalid ttl parameter"),
+            });
+        }
+        if let Some(value) = params.get("maddr") {
+            result.maddr = Some(match value {
+                Some(maddr) if is_maddr(maddr) => Cow::Borrowed(maddr),
+                _ => return sip_parse_error!(InvalidParameter, "Invalid maddr parameter"),
+            });
+        }
+        result.lr = params.contains("lr");
+        result.gr = params.get("gr").map(|gr| gr.map(Cow::Borrowed));
+        result.ob = params.contains("ob");
+        Ok(result)
+    }
+
+    pub fn into_owned(self) -> UriParams<'static> {
+        UriParams {
+            transport: self.transport.map(|t| t.into_owned()),
+            user: self.user.map(|u| u.into_owned()),
+            method: self.method.map(|m| m.into_owned()),
+            ttl: self.ttl,
+            maddr: self.maddr.map(|m| Cow::Owned(m.into_owned())),
+            lr: self.lr,
+            gr: self.gr.map(|gr| gr.map(|gr| Cow::Owned(gr.into_owned()))),
+            ob: self.ob,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn is_valid(input: &str) -> bool {
+        let (_, params) = GenericParams::parse(input.as_bytes()).unwrap();
+        UriParams::from_params(&params).is_ok()
+    }
+
+    #[test]
+    fn transport_from_str() {
+        assert_eq!(Transport::from_str("UDP"), Some(Transport::Udp));
+        assert_eq!(Transport::from_str("tls-sctp"), Some(Transport::TlsSctp));
+        assert_eq!(Transport::from_str("Ws"), Some(Transport::Ws));
+        assert_eq!(
+            Transport::from_str("quic"),
+            Some(Transport::Other(Cow::Borrowed("quic")))
+        );
+        assert_eq!(Transport::from_str(""), None);
+        assert_eq!(Transport::from_str("a b"), None);
+        assert!(Transport::Wss.is_secure() && Transport::Wss.is_reliable());
+        assert!(!Transport::Udp.is_reliable());
+        assert_eq!(Transport::TlsSctp.as_str(), "tls-sctp");
+        assert_eq!(Transport::Udp.default_port(), 5060);
+        assert_eq!(Transport::Tls.default_port(), 5061);
+        assert_eq!(Transport::Wss.default_port(), 443);
+    }
+
+    #[test]
+    fn uri_params_from_params() {
+        let (_, params) = GenericParams::parse(
+            b";transport=tcp;user=ip;method=REGISTER;ttl=0;maddr=239.255.255.1;lr=on;gr=abc;ob",
+        )
+        .unwrap();
+        let params = UriParams::from_params(&params).unwrap();
+        assert_eq!(params.transport, Some(Transport::Tcp));
+        assert_eq!(params.user, Some(UserParam::Ip));
+        assert_eq!(params.method, Some(SipMethod::REGISTER));
+        assert_eq!(params.ttl, Some(0));
+        assert_eq!(params.maddr.as_deref(), Some("239.255.255.1"));
+        assert!(params.lr);
+        assert_eq!(params.gr, Some(Some(Cow::Borrowed("abc"))));
+        assert!(params.ob);
+
+        let (_, params) = GenericParams::parse(b";maddr=[2001:db8::1];gr;foo=bar").unwrap();
+        let params = UriParams::from_params(&params).unwrap();
+        assert_eq!(params.maddr.as_deref(), Some("2001:db8::1"));
+        assert_eq!(params.gr, Some(None));
+        assert_eq!(params.transport, None);
+        assert!(!params.lr);
+
+        assert!(is_valid(";ttl=255;maddr=example.com"));
+        assert!(!is_valid(";transport"));
+        assert!(!is_valid(";user"));
+        assert!(!is_valid(";ttl=256"));
+        assert!(!is_valid(";ttl=1000"));
+        assert!(!is_valid(";maddr"));
+        assert!(!is_valid(";method"));
+    }
+}
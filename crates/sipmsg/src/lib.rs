@@ -26,49 +26,50 @@
 //! CSeq: 986759 INVITE\r\n\r\nbody_stuff"
 //! .as_bytes();
 //!
-//! // First parameter not realized yet.
-//! // It should consist be residue if Content-Length is less then actual body length.
-//! let (_, sip_msg) = SipMessage::parse(invite_msg_buf).unwrap();
+//! // First parameter is the residue after the message.
+//! // The body is limited by Content-Length or takes the rest of the input when it is absent.
+//! let (residue, sip_msg) = SipMessage::parse(invite_msg_buf).unwrap();
+//! assert_eq!(residue.len(), 0);
 //! let request = sip_msg.request().unwrap();
 //! assert_eq!(request.rl.method, SipMethod::INVITE);
 //! assert_eq!(request.rl.sip_version, SipVersion(2, 0));
 //!
 //! // RURI
-//! assert_eq!(request.rl.uri.scheme, SipRequestUriScheme::SIP);
-//! assert_eq!(request.rl.uri.user_info().unwrap().value, "bob");
-//! assert_eq!(request.rl.uri.hostport.host, "biloxi.com");
-//! assert_eq!(request.rl.uri.params().unwrap().get(&"user"), Some(&Some("phone")));
-//! assert_eq!(request.rl.uri.headers().unwrap().get(&"to"), Some(&"alice%40atlanta.com"));
-//! assert_eq!(request.rl.uri.headers().unwrap().get(&"priority"), Some(&"urgent"));
+//! assert_eq!(request.rl.uri.sip().unwrap().scheme, SipRequestUriScheme::SIP);
+//! assert_eq!(request.rl.uri.sip().unwrap().user_info().unwrap().value, "bob");
+//! assert_eq!(request.rl.uri.sip().unwrap().hostport.host, "biloxi.com");
+//! assert_eq!(request.rl.uri.sip().unwrap().params().unwrap().get(&"user"), Some(Some("phone")));
+//! assert_eq!(request.rl.uri.sip().unwrap().headers().unwrap()["to"], "alice%40atlanta.com");
+//! assert_eq!(request.rl.uri.sip().unwrap().headers().unwrap()["priority"], "urgent");
 //!
 //! let call_id_header = request.headers.get_rfc_s(SipRFCHeader::CallID).unwrap();
 //! assert_eq!(call_id_header.value.vstr, "f81d4fae-7dec-11d0-a765-00a0c91e6bf6@foo.bar.com");
 //! assert_eq!(call_id_header.value.tags().unwrap()[&SipHeaderTagType::ID],
 //!           "f81d4fae-7dec-11d0-a765-00a0c91e6bf6".as_bytes());
-//! assert_eq!(call_id_header.value.tags().unwrap()[&SipHeaderTagType::Host], b"foo.bar.com");
+//! assert_eq!(call_id_header.value.tags().unwrap()[&SipHeaderTagType::Host], "foo.bar.com".as_bytes());
 //!
 //! // Via Header
 //! let via_headers = request.headers.get_rfc(SipRFCHeader::Via).unwrap();
 //! assert_eq!(via_headers[0].value.vstr, "SIP/2.0/UDP pc33.atlanta.com");
 //! assert_eq!(
 //!     via_headers[0].params().unwrap().get(&"branch"),
-//!     Some(&Some("z9hG4bKkjshdyff"))
+//!     Some(Some("z9hG4bKkjshdyff"))
 //! );
 //! assert_eq!(
 //!     via_headers[0].value.tags().unwrap()[&SipHeaderTagType::ProtocolName],
-//!     b"SIP"
+//!     "SIP".as_bytes()
 //! );
 //! assert_eq!(
 //!     via_headers[0].value.tags().unwrap()[&SipHeaderTagType::ProtocolVersion],
-//!     b"2.0"
+//!     "2.0".as_bytes()
 //! );
 //! assert_eq!(
 //!     via_headers[0].value.tags().unwrap()[&SipHeaderTagType::ProtocolTransport],
-//!     b"UDP"
+//!     "UDP".as_bytes()
 //! );
 //! assert_eq!(
 //!     via_headers[0].value.tags().unwrap()[&SipHeaderTagType::Host],
-//!     b"pc33.atlanta.com"
+//!     "pc33.atlanta.com".as_bytes()
 //! );
 //! assert_eq!(via_headers[1].value.vstr, "SIP/2.0/UDP 192.168.1.111");
 //! assert_eq!(
@@ -80,7 +81,7 @@
 //! let contact_header = request.headers.get_rfc_s(SipRFCHeader::Contact).unwrap();
 //! assert_eq!(
 //!            contact_header.value.tags().unwrap()[&SipHeaderTagType::DisplayName],
-//!            b"Caller"
+//!            "Caller".as_bytes()
 //! );
 //! assert_eq!(
 //!            contact_header.value.sip_uri().unwrap().user_info().unwrap().value,
@@ -93,7 +94,7 @@
 //!
 //! assert_eq!(
 //!    contact_header.value.sip_uri().unwrap().params().unwrap().get(&"transport"),
-//!    Some(&Some("tcp"))
+//!    Some(Some("tcp"))
 //! );
 //! assert_eq!(
 //!    contact_header.value.sip_uri().unwrap().params().unwrap().get(&"non-exists-param"),
@@ -106,22 +107,29 @@
 //! assert_eq!(extention_header.value.vstr, "extention header value;param=123;without_value");
 //!
 //! // Body
-//! assert_eq!(request.body.unwrap(), b"body_stuff");
+//! assert_eq!(request.body.as_deref().unwrap(), b"body_stuff");
 //! ```
 //!
 extern crate alloc;
 extern crate nom;
+#[cfg(feature = "std")]
+extern crate std;
 
 #[macro_use]
 pub mod common;
 pub use common::errorparse;
+pub use common::errorparse::ParseErrorKind as SipParseErrorKind;
+pub use common::errorparse::SipParseError;
+pub use common::hostport::Host as SipHost;
+pub use common::hostport::HostPort as SipHostPort;
+pub use common::sip_method::ExtensionMethod as SipExtensionMethod;
 pub use common::sip_method::SipMethod;
 
 mod message;
 pub use message::get_message_type as get_sip_message_type;
 pub use message::MessageType as SipMessageType;
-pub use message::SipVersion;
 pub use message::SipMessage;
+pub use message::SipVersion;
 
 mod userinfo;
 
@@ -133,11 +141,32 @@ mod response;
 pub use response::Response as SipResponse;
 pub use response::StatusCode as SipResponseStatusCode;
 pub use response::StatusLine as SipResponseStatusLine;
+pub use response::UnknownCode as SipResponseUnknownCode;
 
 mod headers;
 pub use headers::sipuri::RequestUriScheme as SipRequestUriScheme;
 pub use headers::*;
 
 mod serializer;
+pub use serializer::FmtOutput as SipFmtOutput;
+pub use serializer::SerializeError as SipSerializeError;
+pub use serializer::SerializerOutput as SipSerializerOutput;
+pub use serializer::SipMsgSerializer;
+pub use serializer::SliceOutput as SipSliceOutput;
+
+mod builder;
+pub use builder::BuildError as SipBuildError;
+pub use builder::RequestBuilder as SipRequestBuilder;
+pub use builder::ResponseBuilder as SipResponseBuilder;
+
+mod parser_config;
+pub use parser_config::Deviation as SipParseDeviation;
+pub use parser_config::DeviationKind as SipParseDeviationKind;
+pub use parser_config::ParseMode as SipParseMode;
+pub use parser_config::ParserConfig as SipParserConfig;
+
+mod stream_parser;
+pub use stream_parser::StreamParser as SipStreamParser;
+pub use stream_parser::StreamParserStatus as SipStreamParserStatus;
 
 pub use unicase::Ascii as SipAscii;
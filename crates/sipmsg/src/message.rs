@@ -1,5 +1,11 @@
-use crate::common::errorparse::SipParseError;
-use crate::{SipRequest, SipResponse};
+use crate::common::{
+    bnfcore::is_token_char,
+    errorparse::{ParseErrorKind, SipParseError},
+};
+use crate::parser_config::{message_type_line, Deviation, ParseContext, ParserConfig};
+use crate::{SipHeaders, SipRFCHeader, SipRequest, SipResponse};
+use alloc::vec::Vec;
+use core::fmt;
 use nom;
 
 /// SIP-Version
@@ -7,6 +13,35 @@ use nom;
 #[derive(Copy, Clone, PartialEq, Debug)]
 pub struct SipVersion(pub u8, pub u8);
 
+impl SipVersion {
+    /// Builds version from `1*DIGIT "." 1*DIGIT` parts of start line.
+    /// Number that does not fit `u8` is reported as `kind` error
+    pub(crate) fn from_digits<'a>(
+        major: &'a [u8],
+        minor: &'a [u8],
+        kind: ParseErrorKind,
+    ) -> Result<SipVersion, nom::Err<SipParseError<'a>>> {
+        let number = |digits: &'a [u8]| {
+            core::str::from_utf8(digits)
+                .ok()
+                .and_then(|s| s.parse::<u8>().ok())
+                .ok_or_else(|| {
+                    nom::Err::Error(
+                        SipParseError::new(kind, Some("SIP version number is too large"))
+                            .at(digits),
+                    )
+                })
+        };
+        Ok(SipVersion(number(major)?, number(minor)?))
+    }
+}
+
+impl fmt::Display for SipVersion {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "SIP/{}.{}", self.0, self.1)
+    }
+}
+
 pub enum SipMessage<'a> {
     Request(SipRequest<'a>),
     Response(SipResponse<'a>),
@@ -29,19 +64,124 @@ impl<'a> SipMessage<'a> {
         }
     }
 
+    /// Copies all borrowed data, so message can be stored
+    /// after the receive buffer is recycled or sent to another thread
+    pub fn into_owned(self) -> SipMessage<'static> {
+        match self {
+            SipMessage::Request(r) => SipMessage::Request(r.into_owned()),
+            SipMessage::Response(r) => SipMessage::Response(r.into_owned()),
+        }
+    }
+
     pub fn parse(raw_message: &'a [u8]) -> nom::IResult<&[u8], SipMessage<'a>, SipParseError> {
-        match get_message_type(raw_message) {
+        let (inp, (msg, _)) = SipMessage::parse_with_config(raw_message, &ParserConfig::strict())?;
+        Ok((inp, msg))
+    }
+
+    /// Parses message according to `config`.
+    /// `SipMessage::parse` is the same as parsing in strict mode.
+    ///
+    /// In lenient mode parsers of start line and headers tolerate common violations
+    /// and all of them are returned with the message.
+    /// Lines that have deviations are copied to the message fixed,
+    /// the rest of message borrows `raw_message`.
+    ///
+    /// ```rust
+    /// use sipmsg::{SipMessage, SipParseDeviationKind, SipParserConfig};
+    ///
+    /// let buf = b"OPTIONS  sip:bob@biloxi.com SIP/2.0\nCSeq: 1 OPTIONS \n\n";
+    /// assert!(SipMessage::parse(buf).is_err());
+    ///
+    /// let (_, (msg, deviations)) =
+    ///     SipMessage::parse_with_config(buf, &SipParserConfig::lenient()).unwrap();
+    /// assert_eq!(msg.request().unwrap().rl.uri.sip().unwrap().hostport.host, "biloxi.com");
+    /// assert_eq!(deviations[0].kind, SipParseDeviationKind::StartLineWhitespace);
+    /// assert_eq!(deviations.len(), 5);
+    /// ```
+    pub fn parse_with_config(
+        raw_message: &'a [u8],
+        config: &ParserConfig,
+    ) -> nom::IResult<&'a [u8], (SipMessage<'a>, Vec<Deviation>), SipParseError<'a>> {
+        let mut ctx = ParseContext::new(config, raw_message);
+        let message_type = match get_message_type(raw_message) {
+            // missing or extra whitespace is reported by parser of start line
+            MessageType::Unknown => get_message_type(&message_type_line(raw_message)),
+            message_type => message_type,
+        };
+        let (inp, msg) = match message_type {
             MessageType::Request => {
-                let (inp, request) = SipRequest::parse(raw_message)?;
-                return Ok((inp, SipMessage::Request(request)));
+                let (inp, request) = SipRequest::parse_with_context(raw_message, &mut ctx)?;
+                (inp, SipMessage::Request(request))
             }
             MessageType::Response => {
-                let (inp, response) = SipResponse::parse(raw_message)?;
-                return Ok((inp, SipMessage::Response(response)));
+                let (inp, response) = SipResponse::parse_with_context(raw_message, &mut ctx)?;
+                (inp, SipMessage::Response(response))
             }
-            MessageType::Unknown => sip_parse_error!(1, "Message is invalid. Can't predict type of message"),
-        }
+            MessageType::Unknown => {
+                return Err(nom::Err::Error(
+                    SipParseError::new(
+                        ParseErrorKind::UnknownMessageType,
+                        Some("Can't predict type of message"),
+                    )
+                    .at(raw_message)
+                    .locate(raw_message),
+                ))
+            }
+        };
+        Ok((inp, (msg, ctx.deviations)))
+    }
+}
+
+/// Takes the message body according to the Content-Length (or `l`) header.
+/// Input must start right after the empty line that ends the header section.
+/// If Content-Length is absent the whole rest of input is the body.
+/// Returns the unconsumed bytes as residue and
+/// [`BodyTruncated`](ParseErrorKind::BodyTruncated) error
+/// when the declared length exceeds the available bytes.
+pub(crate) fn take_body<'a>(
+    input: &'a [u8],
+    headers: &SipHeaders<'a>,
+) -> nom::IResult<&'a [u8], &'a [u8], SipParseError<'a>> {
+    let content_length_hdrs = match headers.get_rfc(SipRFCHeader::ContentLength) {
+        Some(hdrs) => hdrs,
+        None => return Ok((&input[input.len()..], input)),
+    };
+
+    if content_length_hdrs.len() != 1 {
+        return sip_parse_error!(
+            MultipleContentLength,
+            "Content-Length header must be present only once"
+        );
+    }
+
+    let content_length = match parse_content_length(&content_length_hdrs[0].value.vstr) {
+        Some(len) => len,
+        None => return sip_parse_error!(InvalidContentLength, "Invalid Content-Length value"),
+    };
+
+    if content_length > input.len() {
+        return Err(nom::Err::Error(
+            SipParseError::new(
+                ParseErrorKind::BodyTruncated {
+                    declared: content_length,
+                    available: input.len(),
+                },
+                None,
+            )
+            .at(&input[input.len()..]),
+        ));
     }
+
+    Ok((&input[content_length..], &input[..content_length]))
+}
+
+/// Content-Length  =  ( "Content-Length" / "l" ) HCOLON 1*DIGIT.
+/// Sign and other characters are not allowed, `None` also if value overflows `usize`
+pub(crate) fn parse_content_length(value: &str) -> Option<usize> {
+    if value.is_empty() || !value.bytes().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    value.parse().ok()
 }
 
 #[derive(Debug, PartialEq)]
@@ -51,25 +191,10 @@ pub enum MessageType {
     Unknown,
 }
 
-const SIP: &'static [u8] = &['S' as u8, 'I' as u8, 'P' as u8]; // SIP
-                                                               // First 3 letters of Request Method
-const ACK: &'static [u8] = &['A' as u8, 'C' as u8, 'K' as u8]; // ACK
-const BYE: &'static [u8] = &['B' as u8, 'Y' as u8, 'E' as u8]; // BYE
-const REG: &'static [u8] = &['R' as u8, 'E' as u8, 'G' as u8]; // REGISTER
-const CAN: &'static [u8] = &['C' as u8, 'A' as u8, 'N' as u8]; // CANCEL
-const INF: &'static [u8] = &['I' as u8, 'N' as u8, 'F' as u8]; // INFO
-const INV: &'static [u8] = &['I' as u8, 'N' as u8, 'V' as u8]; // INVITE
-const MES: &'static [u8] = &['M' as u8, 'E' as u8, 'S' as u8]; // MESSAGE
-const NOT: &'static [u8] = &['N' as u8, 'O' as u8, 'T' as u8]; // NOTIFY
-const OPT: &'static [u8] = &['O' as u8, 'P' as u8, 'T' as u8]; // OPTIONS
-const PRA: &'static [u8] = &['P' as u8, 'R' as u8, 'A' as u8]; // PRACK
-const PUB: &'static [u8] = &['P' as u8, 'U' as u8, 'B' as u8]; // PUBLISH
-const REF: &'static [u8] = &['R' as u8, 'E' as u8, 'F' as u8]; // REFER
-const SUB: &'static [u8] = &['S' as u8, 'U' as u8, 'B' as u8]; // SUBSCRIBE
-const UPD: &'static [u8] = &['U' as u8, 'P' as u8, 'D' as u8]; // UPDATE
-
 /// Fast determinates message type and minimal validate for further transmission to suitable parser.
-/// Does not validate full first line, just first 3 bytes.
+/// Checks the grammar of the first line up to SIP-Version:
+/// `SIP-Version` for response and `Method SP Request-URI SP SIP-Version` for request.
+/// Request-URI and the rest of line are not validated.
 /// ```rust
 /// assert_eq!(
 ///     sipmsg::get_sip_message_type(
@@ -79,26 +204,43 @@ const UPD: &'static [u8] = &['U' as u8, 'P' as u8, 'D' as u8]; // UPDATE
 /// );
 /// ```
 pub fn get_message_type(mt: &[u8]) -> MessageType {
-    if mt.len() < 3 {
-        MessageType::Unknown
+    // Status-Line  =  SIP-Version SP Status-Code SP Reason-Phrase CRLF
+    if mt.starts_with(b"SIP/") {
+        return MessageType::Response;
+    }
+
+    // Request-Line  =  Method SP Request-URI SP SIP-Version CRLF
+    let method_len = mt.iter().take_while(|c| is_token_char(**c)).count();
+    if method_len == 0 || mt.get(method_len) != Some(&b' ') {
+        return MessageType::Unknown;
+    }
+    let rest = &mt[method_len + 1..];
+    let uri_len = rest
+        .iter()
+        .take_while(|c| **c != b' ' && **c != b'\r' && **c != b'\n')
+        .count();
+    if uri_len == 0 || rest.get(uri_len) != Some(&b' ') {
+        return MessageType::Unknown;
+    }
+    if rest[uri_len + 1..].starts_with(b"SIP/") {
+        MessageType::Request
     } else {
-        match &mt[0..3] {
-            SIP => MessageType::Response,
-            ACK => MessageType::Request,
-            BYE => MessageType::Request,
-            REG => MessageType::Request,
-            CAN => MessageType::Request,
-            INF => MessageType::Request,
-            INV => MessageType::Request,
-            MES => MessageType::Request,
-            NOT => MessageType::Request,
-            OPT => MessageType::Request,
-            PRA => MessageType::Request,
-            PUB => MessageType::Request,
-            REF => MessageType::Request,
-            SUB => MessageType::Request,
-            UPD => MessageType::Request,
-            _ => MessageType::Unknown,
-        }
+        MessageType::Unknown
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_content_length_test() {
+        assert_eq!(parse_content_length("0"), Some(0));
+        assert_eq!(parse_content_length("0349"), Some(349));
+        assert_eq!(parse_content_length(""), None);
+        assert_eq!(parse_content_length("+5"), None);
+        assert_eq!(parse_content_length("-5"), None);
+        assert_eq!(parse_content_length("5 "), None);
+        assert_eq!(parse_content_length("99999999999999999999999"), None);
     }
 }
@@ -0,0 +1,424 @@
+use crate::common::{
+    bnfcore::{is_crlf, is_digit, is_wsp},
+    errorparse::{ParseErrorKind, SipParseError},
+};
+use alloc::{borrow::Cow, vec::Vec};
+use nom::bytes::complete::tag;
+
+/// How strictly the message must follow rfc3261 grammar
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum ParseMode {
+    /// Message is rejected on any violation of grammar
+    Strict,
+    /// Common violations of real-world implementations are tolerated by parsers
+    /// and reported as [`Deviation`]
+    Lenient,
+}
+
+/// Configuration of [`SipMessage::parse_with_config`](crate::SipMessage::parse_with_config)
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct ParserConfig {
+    pub mode: ParseMode,
+}
+
+impl ParserConfig {
+    pub fn strict() -> ParserConfig {
+        ParserConfig {
+            mode: ParseMode::Strict,
+        }
+    }
+
+    pub fn lenient() -> ParserConfig {
+        ParserConfig {
+            mode: ParseMode::Lenient,
+        }
+    }
+}
+
+impl Default for ParserConfig {
+    fn default() -> ParserConfig {
+        ParserConfig::strict()
+    }
+}
+
+/// Violation of grammar that was tolerated in lenient mode
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum DeviationKind {
+    /// Line is terminated by LF instead of CRLF
+    BareLf,
+    /// Start line contains several spaces or tabs between elements,
+    /// or space before/after SIP-Version is missing
+    StartLineWhitespace,
+    /// Spaces or tabs before the end of header line.
+    /// Strict mode passes them to the parser of header value
+    TrailingWhitespace,
+    /// Display name or UTF-8 text header value is not valid UTF-8,
+    /// invalid sequences are replaced by U+FFFD
+    NonUtf8,
+}
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Deviation {
+    pub kind: DeviationKind,
+    /// Line of message, starts from 1
+    pub line: usize,
+}
+
+/// Configuration of parsing and deviations tolerated so far
+pub(crate) struct ParseContext<'c> {
+    pub(crate) config: &'c ParserConfig,
+    /// Beginning of message, line of deviation is calculated from it
+    message: &'c [u8],
+    pub(crate) deviations: Vec<Deviation>,
+}
+
+impl<'c> ParseContext<'c> {
+    pub(crate) fn new(config: &'c ParserConfig, message: &'c [u8]) -> ParseContext<'c> {
+        ParseContext {
+            config,
+            message,
+            deviations: Vec::new(),
+        }
+    }
+
+    pub(crate) fn is_lenient(&self) -> bool {
+        self.config.mode == ParseMode::Lenient
+    }
+
+    /// Records deviation found at the beginning of `at`
+    pub(crate) fn deviate(&mut self, kind: DeviationKind, at: &[u8]) {
+        let start = self.message.as_ptr() as usize;
+        let offset = (at.as_ptr() as usize).saturating_sub(start);
+        let offset = core::cmp::min(offset, self.message.len());
+        let line = self.message[..offset]
+            .iter()
+            .filter(|c| **c == b'\n')
+            .count()
+            + 1;
+        self.deviations.push(Deviation { kind, line });
+    }
+}
+
+/// Takes start line with its line terminator.
+/// Strict mode requires CRLF and single SP between elements of line.
+/// Lenient mode returns the line rewritten to this form if it has deviations.
+/// Line without LF is returned as is, the grammar parser reports it.
+pub(crate) fn take_start_line<'a>(
+    input: &'a [u8],
+    ctx: &mut ParseContext,
+    kind: ParseErrorKind,
+) -> nom::IResult<&'a [u8], Cow<'a, [u8]>, SipParseError<'a>> {
+    let lf = match input.iter().position(|c| *c == b'\n') {
+        Some(lf) => lf,
+        None => return Ok((&input[input.len()..], Cow::Borrowed(input))),
+    };
+    let (line, has_cr) = match input[..lf].last() {
+        Some(b'\r') => (&input[..lf - 1], true),
+        _ => (&input[..lf], false),
+    };
+    let start_line = normalize_start_line(line);
+    if has_cr && start_line == line {
+        return Ok((&input[lf + 1..], Cow::Borrowed(&input[..lf + 1])));
+    }
+
+    if !ctx.is_lenient() {
+        let err = if start_line != line {
+            let diff = line
+                .iter()
+                .zip(start_line.iter())
+                .take_while(|(a, b)| a == b)
+                .count();
+            SipParseError::new(
+                kind,
+                Some("Elements of start line must be separated by single SP"),
+            )
+            .at(&line[diff..])
+        } else {
+            SipParseError::new(ParseErrorKind::InvalidLineEnding, None).at(&input[lf..])
+        };
+        return Err(nom::Err::Error(err));
+    }
+
+    if start_line != line {
+        ctx.deviate(DeviationKind::StartLineWhitespace, line);
+    }
+    if !has_cr {
+        ctx.deviate(DeviationKind::BareLf, &input[lf..]);
+    }
+    let mut result = start_line;
+    result.extend_from_slice(b"\r\n");
+    Ok((&input[lf + 1..], Cow::Owned(result)))
+}
+
+/// Checks line endings of header line including its folded lines.
+/// Strict mode rejects bare LF, trailing whitespace is left to the parser of header value.
+/// Lenient mode returns the rest of input after the line and the line
+/// rewritten with CRLF line endings and without trailing whitespace if it has deviations.
+/// Input is not consumed if the line has no deviations or it is incomplete.
+pub(crate) fn take_header_line<'a>(
+    input: &'a [u8],
+    ctx: &mut ParseContext,
+) -> nom::IResult<&'a [u8], Option<Vec<u8>>, SipParseError<'a>> {
+    let mut bare_lfs = Vec::new();
+    let mut pos = 0;
+    let line_end = loop {
+        let lf = match input[pos..].iter().position(|c| *c == b'\n') {
+            Some(lf) => pos + lf,
+            None => return Ok((input, None)),
+        };
+        if lf == 0 || input[lf - 1] != b'\r' {
+            bare_lfs.push(lf);
+        }
+        pos = lf + 1;
+        if !matches!(input.get(pos), Some(c) if is_wsp(*c)) {
+            break lf;
+        }
+    };
+    let content_end = if bare_lfs.last() == Some(&line_end) {
+        line_end
+    } else {
+        line_end - 1
+    };
+    let mut trimmed_end = content_end;
+    while trimmed_end > 0 && is_wsp(input[trimmed_end - 1]) {
+        trimmed_end -= 1;
+    }
+    if !ctx.is_lenient() {
+        return match bare_lfs.first() {
+            Some(lf) => Err(nom::Err::Error(
+                SipParseError::new(ParseErrorKind::InvalidLineEnding, None).at(&input[*lf..]),
+            )),
+            None => Ok((input, None)),
+        };
+    }
+    if bare_lfs.is_empty() && trimmed_end == content_end {
+        return Ok((input, None));
+    }
+
+    let mut result = Vec::with_capacity(trimmed_end + bare_lfs.len() + 2);
+    let mut copied = 0;
+    for lf in bare_lfs.iter().filter(|lf| **lf < trimmed_end) {
+        ctx.deviate(DeviationKind::BareLf, &input[*lf..]);
+        result.extend_from_slice(&input[copied..*lf]);
+        result.push(b'\r');
+        copied = *lf;
+    }
+    result.extend_from_slice(&input[copied..trimmed_end]);
+    result.extend_from_slice(b"\r\n");
+    if trimmed_end != content_end {
+        ctx.deviate(DeviationKind::TrailingWhitespace, &input[trimmed_end..]);
+    }
+    if bare_lfs.last() == Some(&line_end) {
+        ctx.deviate(DeviationKind::BareLf, &input[line_end..]);
+    }
+    Ok((&input[line_end + 1..], Some(result)))
+}
+
+/// Takes CRLF that ends the header section, lenient mode accepts bare LF
+pub(crate) fn take_empty_line<'a>(
+    input: &'a [u8],
+    ctx: &mut ParseContext,
+) -> nom::IResult<&'a [u8], (), SipParseError<'a>> {
+    if is_crlf(input) {
+        return Ok((&input[2..], ()));
+    }
+    match input.first() {
+        Some(b'\n') if ctx.is_lenient() => {
+            ctx.deviate(DeviationKind::BareLf, input);
+            Ok((&input[1..], ()))
+        }
+        Some(b'\n') => Err(nom::Err::Error(
+            SipParseError::new(ParseErrorKind::InvalidLineEnding, None).at(input),
+        )),
+        _ => {
+            let (input, _) = tag("\r\n")(input)?;
+            Ok((input, ()))
+        }
+    }
+}
+
+/// Whether the header section ends at the beginning of `input`
+pub(crate) fn is_empty_line(input: &[u8], ctx: &ParseContext) -> bool {
+    is_crlf(input) || (ctx.is_lenient() && input.first() == Some(&b'\n'))
+}
+
+/// Position in `original` line that corresponds to `offset` in the copy
+/// rewritten by lenient parser
+pub(crate) fn original_position<'a>(
+    copy: &[u8],
+    offset: Option<usize>,
+    original: &'a [u8],
+) -> &'a [u8] {
+    let offset = match offset {
+        Some(offset) => offset,
+        None => return original,
+    };
+    let (mut copy_pos, mut original_pos) = (0, 0);
+    while copy_pos < offset && copy_pos < copy.len() && original_pos < original.len() {
+        // CR inserted before bare LF
+        if copy[copy_pos] != b'\r' || original[original_pos] != b'\n' {
+            original_pos += 1;
+        }
+        copy_pos += 1;
+    }
+    &original[original_pos..]
+}
+
+/// Rewrites start line to the form `Method SP Request-URI SP SIP-Version`
+/// or `SIP-Version SP Status-Code SP Reason-Phrase`:
+/// collapses runs of spaces and tabs and restores spaces around SIP-Version.
+/// Reason-Phrase may contain spaces and tabs, so it is kept as is.
+fn normalize_start_line(line: &[u8]) -> Vec<u8> {
+    let first = line.iter().take_while(|c| is_wsp(**c)).count();
+    let line = &line[first..];
+    if line.starts_with(b"SIP/") {
+        // SIP-Version SP Status-Code SP Reason-Phrase
+        let version_len = sip_version_len(line);
+        let rest = &line[version_len..];
+        let rest = &rest[rest.iter().take_while(|c| is_wsp(**c)).count()..];
+        let code_len = rest.iter().take(3).take_while(|c| is_digit(**c)).count();
+        if version_len == 0 || code_len != 3 {
+            // grammar parser reports it
+            return line.to_vec();
+        }
+        let mut result = Vec::with_capacity(line.len() + 2);
+        result.extend_from_slice(&line[..version_len]);
+        result.push(b' ');
+        result.extend_from_slice(&rest[..code_len]);
+        result.push(b' ');
+        let reason = &rest[code_len..];
+        match reason.first() {
+            Some(c) if is_wsp(*c) => result.extend_from_slice(&reason[1..]),
+            _ => result.extend_from_slice(reason),
+        }
+        return result;
+    }
+
+    // Method SP Request-URI SP SIP-Version
+    let mut result = Vec::with_capacity(line.len() + 2);
+    for c in line.iter() {
+        if is_wsp(*c) {
+            if !result.is_empty() && result.last() != Some(&b' ') {
+                result.push(b' ');
+            }
+        } else {
+            result.push(*c);
+        }
+    }
+    if result.last() == Some(&b' ') {
+        result.pop();
+    }
+    if let Some(version_pos) = find_sub(&result, b"SIP/") {
+        if version_pos > 0
+            && result[version_pos - 1] != b' '
+            && version_pos + sip_version_len(&result[version_pos..]) == result.len()
+        {
+            result.insert(version_pos, b' ');
+        }
+    }
+    result
+}
+
+/// Start line in the form checked by [`get_message_type`](crate::get_sip_message_type)
+pub(crate) fn message_type_line(input: &[u8]) -> Vec<u8> {
+    let line = match input.iter().position(|c| *c == b'\n') {
+        Some(lf) => &input[..lf],
+        None => input,
+    };
+    let line = match line.last() {
+        Some(b'\r') => &line[..line.len() - 1],
+        _ => line,
+    };
+    normalize_start_line(line)
+}
+
+/// Length of `SIP/1*DIGIT "." 1*DIGIT` at the beginning of input, 0 if it is absent
+fn sip_version_len(input: &[u8]) -> usize {
+    if !input.starts_with(b"SIP/") {
+        return 0;
+    }
+    let major = input[4..].iter().take_while(|c| is_digit(**c)).count();
+    if major == 0 || input.get(4 + major) != Some(&b'.') {
+        return 0;
+    }
+    let minor = input[5 + major..]
+        .iter()
+        .take_while(|c| is_digit(**c))
+        .count();
+    if minor == 0 {
+        return 0;
+    }
+    5 + major + minor
+}
+
+/// Position of the last occurrence of `needle`
+fn find_sub(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack.windows(needle.len()).rposition(|w| w == needle)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalize_start_line_test() {
+        assert_eq!(
+            normalize_start_line(b"INVITE  sip:bob@biloxi.com\tSIP/2.0 "),
+            b"INVITE sip:bob@biloxi.com SIP/2.0".to_vec()
+        );
+        assert_eq!(
+            normalize_start_line(b"INVITE sip:bob@biloxi.comSIP/2.0"),
+            b"INVITE sip:bob@biloxi.com SIP/2.0".to_vec()
+        );
+        assert_eq!(
+            normalize_start_line(b"SIP/2.0 200OK"),
+            b"SIP/2.0 200 OK".to_vec()
+        );
+        assert_eq!(
+            normalize_start_line(b"SIP/2.0  180 Ringing"),
+            b"SIP/2.0 180 Ringing".to_vec()
+        );
+        assert_eq!(
+            normalize_start_line(b"SIP/2.0 180  Ringing  now "),
+            b"SIP/2.0 180  Ringing  now ".to_vec()
+        );
+    }
+
+    #[test]
+    fn take_header_line_test() {
+        let config = ParserConfig::lenient();
+        let msg = b"Via: SIP/2.0/UDP 10.0.0.1 \n ;branch=z9hG4bK1\t\nTo: <sip:a@b>\r\n";
+        let mut ctx = ParseContext::new(&config, msg);
+        let (rest, line) = take_header_line(msg, &mut ctx).unwrap();
+        let line = line.unwrap();
+        assert_eq!(rest, b"To: <sip:a@b>\r\n");
+        assert_eq!(
+            line,
+            b"Via: SIP/2.0/UDP 10.0.0.1 \r\n ;branch=z9hG4bK1\r\n".to_vec()
+        );
+        assert_eq!(
+            ctx.deviations,
+            [
+                Deviation {
+                    kind: DeviationKind::BareLf,
+                    line: 1
+                },
+                Deviation {
+                    kind: DeviationKind::TrailingWhitespace,
+                    line: 2
+                },
+                Deviation {
+                    kind: DeviationKind::BareLf,
+                    line: 2
+                },
+            ]
+        );
+        assert_eq!(take_header_line(rest, &mut ctx).unwrap(), (rest, None));
+
+        let config = ParserConfig::strict();
+        let mut ctx = ParseContext::new(&config, msg);
+        assert!(take_header_line(msg, &mut ctx).is_err());
+        let line = b"To: <sip:a@b> \r\n";
+        assert_eq!(take_header_line(line, &mut ctx).unwrap(), (&line[..], None));
+    }
+}
@@ -1,11 +1,22 @@
-use crate::common::{errorparse::SipParseError, sip_method::*};
-use crate::{headers::*, message::*};
+use crate::common::{
+    bnfcore::is_token_char,
+    errorparse::{ParseErrorKind, SipParseError},
+    sip_method::*,
+};
+use crate::{
+    headers::*,
+    message::*,
+    parser_config::{
+        original_position, take_empty_line, take_start_line, ParseContext, ParserConfig,
+    },
+};
 use nom::{
     bytes::complete::{tag, take_while1},
-    character::{complete, is_alphabetic},
+    character::complete,
     sequence::tuple,
 };
 
+use alloc::borrow::Cow;
 use core::{str, u8};
 
 /// [rfc3261 section-7.1](https://tools.ietf.org/html/rfc3261#section-7.1)
@@ -15,7 +26,7 @@ pub struct Request<'a> {
     /// The request headers.
     pub headers: SipHeaders<'a>,
     /// The body of message
-    pub body: Option<&'a [u8]>,
+    pub body: Option<Cow<'a, [u8]>>,
 }
 
 impl<'a> Request<'a> {
@@ -23,39 +34,95 @@ impl<'a> Request<'a> {
         Request {
             rl: rl,
             headers: headers,
-            body: body,
+            body: body.map(Cow::Borrowed),
         }
     }
 
+    /// Copies all borrowed data, so request does not depend on input buffer anymore
+    pub fn into_owned(self) -> Request<'static> {
+        Request {
+            rl: self.rl.into_owned(),
+            headers: self.headers.into_owned(),
+            body: self.body.map(|b| Cow::Owned(b.into_owned())),
+        }
+    }
+
+    /// Offset and line of error are calculated from the beginning of `buf_input`
     pub fn parse(buf_input: &'a [u8]) -> nom::IResult<&[u8], Request, SipParseError> {
-        let (input, rl) = RequestLine::parse(buf_input)?;
+        let config = ParserConfig::strict();
+        let mut ctx = ParseContext::new(&config, buf_input);
+        Request::parse_with_context(buf_input, &mut ctx)
+    }
 
-        let (input, headers) = SipHeaders::parse(input)?;
-        // TODO check header Content-Length and fix buf_input return
-        let (body, _) = tag("\r\n")(input)?;
-        Ok((buf_input, Request::new(rl, headers, Some(body))))
+    pub(crate) fn parse_with_context(
+        buf_input: &'a [u8],
+        ctx: &mut ParseContext,
+    ) -> nom::IResult<&'a [u8], Request<'a>, SipParseError<'a>> {
+        Request::parse_parts(buf_input, ctx).map_err(|err| err.map(|e| e.locate(buf_input)))
+    }
+
+    fn parse_parts(
+        buf_input: &'a [u8],
+        ctx: &mut ParseContext,
+    ) -> nom::IResult<&'a [u8], Request<'a>, SipParseError<'a>> {
+        let (input, rl) = RequestLine::parse_with_context(buf_input, ctx)?;
+
+        let (input, headers) = SipHeaders::parse_with_context(input, ctx)?;
+        let (input, _) = take_empty_line(input, ctx)?;
+        let (input, body) = take_body(input, &headers)?;
+        Ok((input, Request::new(rl, headers, Some(body))))
     }
 }
 
 /// Ex: `INVITE sip:user@example.com SIP/2.0`
 /// The Request line and u8 buffer shoud have the same life time
 pub struct RequestLine<'a> {
-    pub method: SipMethod,
-    pub uri: SipUri<'a>,
+    pub method: SipMethod<'a>,
+    pub uri: Uri<'a>,
     pub sip_version: SipVersion,
     // Byte representation of request line that includes \r\n
-    pub raw: &'a [u8],
+    pub raw: Cow<'a, [u8]>,
 }
 
 impl<'a> RequestLine<'a> {
-    fn parse_method(method: &[u8]) -> Option<SipMethod> {
+    pub fn into_owned(self) -> RequestLine<'static> {
+        RequestLine {
+            method: self.method.into_owned(),
+            uri: self.uri.into_owned(),
+            sip_version: self.sip_version,
+            raw: Cow::Owned(self.raw.into_owned()),
+        }
+    }
+
+    fn parse_method(method: &'a [u8]) -> Option<SipMethod<'a>> {
         match str::from_utf8(method) {
             Ok(s) => SipMethod::from_str(s),
             Err(_) => None,
         }
     }
+    /// Parses start line according to configuration of `ctx`,
+    /// line with deviations is parsed from the fixed copy
+    pub(crate) fn parse_with_context(
+        source_input: &'a [u8],
+        ctx: &mut ParseContext,
+    ) -> nom::IResult<&'a [u8], RequestLine<'a>, SipParseError<'a>> {
+        match take_start_line(source_input, ctx, ParseErrorKind::InvalidRequestLine)? {
+            (_, Cow::Borrowed(_)) => RequestLine::parse(source_input),
+            (input, Cow::Owned(line)) => {
+                let (_, start_line) = RequestLine::parse(&line).map_err(|err| {
+                    err.map(|e| {
+                        let e = e.locate(&line);
+                        let at = original_position(&line, e.offset, source_input);
+                        SipParseError::new(e.kind, e.message).at(at)
+                    })
+                })?;
+                Ok((input, start_line.into_owned()))
+            }
+        }
+    }
+
     pub fn parse(source_input: &[u8]) -> nom::IResult<&[u8], RequestLine, SipParseError> {
-        let method = take_while1(is_alphabetic);
+        let method = take_while1(is_token_char);
         let uri = take_while1(|c| c != b' ' as u8);
         let (input, (method, _, uri, _, _, major_version, _, minor_version, _)) =
             tuple((
@@ -68,26 +135,36 @@ impl<'a> RequestLine<'a> {
                 complete::char('.'),
                 complete::digit1,
                 complete::crlf,
-            ))(source_input)?;
+            ))(source_input)
+            .map_err(|err| {
+                err.map(|e: SipParseError| {
+                    e.or_kind(ParseErrorKind::InvalidRequestLine)
+                        .at(source_input)
+                })
+            })?;
 
-        let (_, sip_uri) = SipUri::parse(uri)?;
+        let (_, request_uri) = Uri::parse(uri)
+            .map_err(|err| err.map(|e| e.or_kind(ParseErrorKind::InvalidUri).at(uri)))?;
 
-        let sip_version = SipVersion(
-            u8::from_str_radix(str::from_utf8(major_version).unwrap(), 10).unwrap(),
-            u8::from_str_radix(str::from_utf8(minor_version).unwrap(), 10).unwrap(),
-        );
+        let sip_version = SipVersion::from_digits(
+            major_version,
+            minor_version,
+            ParseErrorKind::InvalidRequestLine,
+        )?;
 
         match RequestLine::parse_method(method) {
             Some(m) => Ok((
                 input,
                 RequestLine {
                     method: m,
-                    uri: sip_uri,
+                    uri: request_uri,
                     sip_version: sip_version,
-                    raw: &source_input[..source_input.len() - input.len()],
+                    raw: Cow::Borrowed(&source_input[..source_input.len() - input.len()]),
                 },
             )),
-            None => return sip_parse_error!(1, "Error cast from_utf8"),
+            None => Err(nom::Err::Error(
+                SipParseError::new(ParseErrorKind::InvalidMethod, None).at(method),
+            )),
         }
     }
 }
@@ -1,7 +1,14 @@
-use crate::common::{errorparse::SipParseError, nom_wrappers::from_utf8_nom};
+use crate::common::{
+    errorparse::{ParseErrorKind, SipParseError},
+    nom_wrappers::from_utf8_nom,
+};
 use crate::headers::*;
-use crate::message::SipVersion;
+use crate::message::{take_body, SipVersion};
+use crate::parser_config::{
+    original_position, take_empty_line, take_start_line, ParseContext, ParserConfig,
+};
 
+use alloc::borrow::Cow;
 use core::str;
 use nom::{
     bytes::complete::{tag, take, take_until},
@@ -16,19 +23,49 @@ pub struct Response<'a> {
     /// The response headers.
     pub headers: SipHeaders<'a>,
     /// Body
-    pub body: Option<&'a [u8]>,
+    pub body: Option<Cow<'a, [u8]>>,
 }
 
 /// Ex: `SIP/2.0 401 Unauthorized`
 pub struct StatusLine<'a> {
     pub sip_version: SipVersion,
     pub status_code: StatusCode,
-    pub reason_phrase: &'a str,
+    pub reason_phrase: Cow<'a, str>,
     // Byte representation of request line that includes \r\n
-    pub raw: &'a [u8],
+    pub raw: Cow<'a, [u8]>,
 }
 
 impl<'a> StatusLine<'a> {
+    pub fn into_owned(self) -> StatusLine<'static> {
+        StatusLine {
+            sip_version: self.sip_version,
+            status_code: self.status_code,
+            reason_phrase: Cow::Owned(self.reason_phrase.into_owned()),
+            raw: Cow::Owned(self.raw.into_owned()),
+        }
+    }
+
+    /// Parses start line according to configuration of `ctx`,
+    /// line with deviations is parsed from the fixed copy
+    pub(crate) fn parse_with_context(
+        source_input: &'a [u8],
+        ctx: &mut ParseContext,
+    ) -> nom::IResult<&'a [u8], StatusLine<'a>, SipParseError<'a>> {
+        match take_start_line(source_input, ctx, ParseErrorKind::InvalidStatusLine)? {
+            (_, Cow::Borrowed(_)) => StatusLine::parse(source_input),
+            (input, Cow::Owned(line)) => {
+                let (_, start_line) = StatusLine::parse(&line).map_err(|err| {
+                    err.map(|e| {
+                        let e = e.locate(&line);
+                        let at = original_position(&line, e.offset, source_input);
+                        SipParseError::new(e.kind, e.message).at(at)
+                    })
+                })?;
+                Ok((input, start_line.into_owned()))
+            }
+        }
+    }
+
     pub fn parse(source_input: &'a [u8]) -> nom::IResult<&[u8], StatusLine<'a>, SipParseError> {
         let (input, (_, major_version, _, minor_version, _, status_code, _, reason_phrase, _)) =
             tuple((
@@ -41,22 +78,36 @@ impl<'a> StatusLine<'a> {
                 complete::space1,
                 take_until("\r\n"),
                 take(2usize), // skip /r/n
-            ))(source_input)?;
+            ))(source_input)
+            .map_err(|err| {
+                err.map(|e: SipParseError| {
+                    e.or_kind(ParseErrorKind::InvalidStatusLine)
+                        .at(source_input)
+                })
+            })?;
 
-        let sip_version = SipVersion(
-            u8::from_str_radix(str::from_utf8(major_version).unwrap(), 10).unwrap(),
-            u8::from_str_radix(str::from_utf8(minor_version).unwrap(), 10).unwrap(),
-        );
+        let sip_version = SipVersion::from_digits(
+            major_version,
+            minor_version,
+            ParseErrorKind::InvalidStatusLine,
+        )?;
 
-        let status_code = StatusCode::from_bytes_str(status_code);
+        let status_code = match StatusCode::from_bytes_str(status_code) {
+            Some(status_code) => status_code,
+            None => {
+                return Err(nom::Err::Error(
+                    SipParseError::new(ParseErrorKind::InvalidStatusCode, None).at(status_code),
+                ))
+            }
+        };
         let (_, reason_phrase_str) = from_utf8_nom(reason_phrase)?;
         Ok((
             input,
             StatusLine {
                 sip_version: sip_version,
                 status_code: status_code,
-                reason_phrase: reason_phrase_str,
-                raw: &source_input[..source_input.len() - input.len()],
+                reason_phrase: Cow::Borrowed(reason_phrase_str),
+                raw: Cow::Borrowed(&source_input[..source_input.len() - input.len()]),
             },
         ))
     }
@@ -68,254 +119,236 @@ impl<'a> Response<'a> {
         Response {
             sl: sl,
             headers: headers,
-            body: body,
+            body: body.map(Cow::Borrowed),
         }
     }
 
+    /// Copies all borrowed data, so response does not depend on input buffer anymore
+    pub fn into_owned(self) -> Response<'static> {
+        Response {
+            sl: self.sl.into_owned(),
+            headers: self.headers.into_owned(),
+            body: self.body.map(|b| Cow::Owned(b.into_owned())),
+        }
+    }
+
+    /// Offset and line of error are calculated from the beginning of `buf_input`
     pub fn parse(buf_input: &'a [u8]) -> nom::IResult<&[u8], Response<'a>, SipParseError> {
-        let (input, rl) = StatusLine::parse(buf_input)?;
+        let config = ParserConfig::strict();
+        let mut ctx = ParseContext::new(&config, buf_input);
+        Response::parse_with_context(buf_input, &mut ctx)
+    }
+
+    pub(crate) fn parse_with_context(
+        buf_input: &'a [u8],
+        ctx: &mut ParseContext,
+    ) -> nom::IResult<&'a [u8], Response<'a>, SipParseError<'a>> {
+        Response::parse_parts(buf_input, ctx).map_err(|err| err.map(|e| e.locate(buf_input)))
+    }
+
+    fn parse_parts(
+        buf_input: &'a [u8],
+        ctx: &mut ParseContext,
+    ) -> nom::IResult<&'a [u8], Response<'a>, SipParseError<'a>> {
+        let (input, rl) = StatusLine::parse_with_context(buf_input, ctx)?;
 
-        let (input, headers) = SipHeaders::parse(input)?;
-        // TODO check header Content-Length and fix buf_input return
-        let (body, _) = tag("\r\n")(input)?;
+        let (input, headers) = SipHeaders::parse_with_context(input, ctx)?;
+        let (input, _) = take_empty_line(input, ctx)?;
+        let (input, body) = take_body(input, &headers)?;
 
-        Ok((buf_input, Response::new(rl, headers, Some(body))))
+        Ok((input, Response::new(rl, headers, Some(body))))
     }
 }
 
+/// Not registered status code in range 100-699
 #[derive(Copy, Clone, PartialEq, Debug)]
-pub enum StatusCode {
+pub struct UnknownCode(u16);
+
+impl UnknownCode {
+    /// Returns numeric code
+    pub fn code(&self) -> u16 {
+        self.0
+    }
+}
+
+/// Generates `StatusCode` enum and conversions from the table of
+/// (code, variant, reason phrase)
+macro_rules! status_codes {
+    ($(($code:expr, $variant:ident, $reason_phrase:expr),)+) => {
+        /// Response codes from
+        /// [IANA registry](https://www.iana.org/assignments/sip-parameters/sip-parameters.xhtml#sip-parameters-7).
+        /// Codes in range 100-699 that are not registered are kept in `Unknown`,
+        /// which can be obtained only from `StatusCode::from_code`
+        #[derive(Copy, Clone, PartialEq, Debug)]
+        pub enum StatusCode {
+            $($variant,)+
+            Unknown(UnknownCode),
+        }
+
+        impl StatusCode {
+            /// Returns numeric code
+            pub fn code(&self) -> u16 {
+                match self {
+                    $(StatusCode::$variant => $code,)+
+                    StatusCode::Unknown(code) => code.0,
+                }
+            }
+
+            /// Returns None if code is not in range 100-699
+            pub fn from_code(code: u16) -> Option<StatusCode> {
+                match code {
+                    $($code => Some(StatusCode::$variant),)+
+                    100..=699 => Some(StatusCode::Unknown(UnknownCode(code))),
+                    _ => None,
+                }
+            }
+
+            /// Default reason phrase, "Unknown" for not registered code
+            pub fn reason_phrase(&self) -> &str {
+                match self {
+                    $(StatusCode::$variant => $reason_phrase,)+
+                    StatusCode::Unknown(_) => "Unknown",
+                }
+            }
+        }
+    };
+}
+
+status_codes! {
     // Provisional 1xx
-    Trying = 100,
-    Ringing = 180,
-    CallIsBeingForwarded = 181,
-    Queued = 182,
-    SessionProgress = 183,
+    (100, Trying, "Trying"),
+    (180, Ringing, "Ringing"),
+    (181, CallIsBeingForwarded, "Call Is Being Forwarded"),
+    (182, Queued, "Queued"),
+    (183, SessionProgress, "Session Progress"),
+    (199, EarlyDialogTerminated, "Early Dialog Terminated"),
 
     // Successful 2xx
-    OK = 200,
+    (200, OK, "OK"),
+    (202, Accepted, "Accepted"),
+    (204, NoNotification, "No Notification"),
 
     // Redirection 3xx
-    MultipleChoices = 300,
-    MovedPermanently = 301,
-    MovedTemporarily = 302,
-    UseProxy = 305,
-    AlternativeService = 380,
+    (300, MultipleChoices, "Multiple Choices"),
+    (301, MovedPermanently, "Moved Permanently"),
+    (302, MovedTemporarily, "Moved Temporarily"),
+    (305, UseProxy, "Use Proxy"),
+    (380, AlternativeService, "Alternative Service"),
 
     // Request Failure 4xx
-    BadRequest = 400,
-    Unauthorized = 401,
-    PaymentRequired = 402,
-    Forbidden = 403,
-    NotFound = 404,
-    MethodNotAllowed = 405,
-    NotAcceptableResourceContent = 406,
-    ProxyAuthenticationRequired = 407,
-    RequestTimeout = 408,
-    Gone = 410,
-    RequestEntityTooLarge = 413,
-    RequestUriTooLong = 414,
-    UnsupportedMediaType = 415,
-    UnsupportedUriScheme = 416,
-    BadExtension = 420,
-    ExtensionRequired = 421,
-    IntervalTooBrief = 423,
-    TemporarilyUnavailable = 480,
-    CallOrTransactionDoesNotExist = 481,
-    LoopDetected = 482,
-    TooManyHops = 483,
-    AddressIncomplete = 484,
-    Ambiguous = 485,
-    BusyHere = 486,
-    RequestTerminated = 487,
-    NotAcceptableHere = 488,
-    RequestPending = 491,
-    Undecipherable = 493,
+    (400, BadRequest, "Bad Request"),
+    (401, Unauthorized, "Unauthorized"),
+    (402, PaymentRequired, "Payment Required"),
+    (403, Forbidden, "Forbidden"),
+    (404, NotFound, "Not Found"),
+    (405, MethodNotAllowed, "Method Not Allowed"),
+    (406, NotAcceptableResourceContent, "Not Acceptable"),
+    (407, ProxyAuthenticationRequired, "Proxy Authentication Required"),
+    (408, RequestTimeout, "Request Timeout"),
+    (410, Gone, "Gone"),
+    (412, ConditionalRequestFailed, "Conditional Request Failed"),
+    (413, RequestEntityTooLarge, "Request Entity Too Large"),
+    (414, RequestUriTooLong, "Request-URI Too Long"),
+    (415, UnsupportedMediaType, "Unsupported Media Type"),
+    (416, UnsupportedUriScheme, "Unsupported URI Scheme"),
+    (417, UnknownResourcePriority, "Unknown Resource-Priority"),
+    (420, BadExtension, "Bad Extension"),
+    (421, ExtensionRequired, "Extension Required"),
+    (422, SessionIntervalTooSmall, "Session Interval Too Small"),
+    (423, IntervalTooBrief, "Interval Too Brief"),
+    (424, BadLocationInformation, "Bad Location Information"),
+    (425, BadAlertMessage, "Bad Alert Message"),
+    (428, UseIdentityHeader, "Use Identity Header"),
+    (429, ProvideReferrerIdentity, "Provide Referrer Identity"),
+    (430, FlowFailed, "Flow Failed"),
+    (433, AnonymityDisallowed, "Anonymity Disallowed"),
+    (436, BadIdentityInfo, "Bad Identity Info"),
+    (437, UnsupportedCredential, "Unsupported Credential"),
+    (438, InvalidIdentityHeader, "Invalid Identity Header"),
+    (439, FirstHopLacksOutboundSupport, "First Hop Lacks Outbound Support"),
+    (440, MaxBreadthExceeded, "Max-Breadth Exceeded"),
+    (469, BadInfoPackage, "Bad Info Package"),
+    (470, ConsentNeeded, "Consent Needed"),
+    (480, TemporarilyUnavailable, "Temporarily Unavailable"),
+    (481, CallOrTransactionDoesNotExist, "Call/Transaction Does Not Exist"),
+    (482, LoopDetected, "Loop Detected"),
+    (483, TooManyHops, "Too Many Hops"),
+    (484, AddressIncomplete, "Address Incomplete"),
+    (485, Ambiguous, "Ambiguous"),
+    (486, BusyHere, "Busy Here"),
+    (487, RequestTerminated, "Request Terminated"),
+    (488, NotAcceptableHere, "Not Acceptable Here"),
+    (489, BadEvent, "Bad Event"),
+    (491, RequestPending, "Request Pending"),
+    (493, Undecipherable, "Undecipherable"),
+    (494, SecurityAgreementRequired, "Security Agreement Required"),
 
     // Server Failure 5xx
-    ServerInternalError = 500,
-    NotImplemented = 501,
-    BadGateway = 502,
-    ServiceUnavailable = 503,
-    ServerTimeout = 504,
-    VersionNotSupported = 505,
-    MessageTooLarge = 513,
+    (500, ServerInternalError, "Server Internal Error"),
+    (501, NotImplemented, "Not Implemented"),
+    (502, BadGateway, "Bad Gateway"),
+    (503, ServiceUnavailable, "Service Unavailable"),
+    (504, ServerTimeout, "Server Time-out"),
+    (505, VersionNotSupported, "Version Not Supported"),
+    (513, MessageTooLarge, "Message Too Large"),
+    (555, PushNotificationServiceNotSupported, "Push Notification Service Not Supported"),
+    (580, PreconditionFailure, "Precondition Failure"),
 
     // Global Failures 6xx
-    BusyEverywhere = 600,
-    Decline = 603,
-    DoesNotExistAnywhere = 604,
-    NotAcceptable = 606,
-
-    // Unknown
-    Unknown = 999,
+    (600, BusyEverywhere, "Busy Everywhere"),
+    (603, Decline, "Decline"),
+    (604, DoesNotExistAnywhere, "Does Not Exist Anywhere"),
+    (606, NotAcceptable, "Not Acceptable"),
+    (607, Unwanted, "Unwanted"),
+    (608, Rejected, "Rejected"),
 }
 
-const BS_100: &'static [u8] = "100".as_bytes();
-const BS_180: &'static [u8] = "180".as_bytes();
-const BS_181: &'static [u8] = "181".as_bytes();
-const BS_182: &'static [u8] = "182".as_bytes();
-const BS_183: &'static [u8] = "183".as_bytes();
-const BS_200: &'static [u8] = "200".as_bytes();
-const BS_300: &'static [u8] = "300".as_bytes();
-const BS_301: &'static [u8] = "301".as_bytes();
-const BS_302: &'static [u8] = "302".as_bytes();
-const BS_305: &'static [u8] = "305".as_bytes();
-const BS_380: &'static [u8] = "380".as_bytes();
-const BS_400: &'static [u8] = "400".as_bytes();
-const BS_401: &'static [u8] = "401".as_bytes();
-const BS_402: &'static [u8] = "402".as_bytes();
-const BS_403: &'static [u8] = "403".as_bytes();
-const BS_404: &'static [u8] = "404".as_bytes();
-const BS_405: &'static [u8] = "405".as_bytes();
-const BS_406: &'static [u8] = "406".as_bytes();
-const BS_407: &'static [u8] = "407".as_bytes();
-const BS_408: &'static [u8] = "408".as_bytes();
-const BS_410: &'static [u8] = "410".as_bytes();
-const BS_413: &'static [u8] = "413".as_bytes();
-const BS_414: &'static [u8] = "414".as_bytes();
-const BS_415: &'static [u8] = "415".as_bytes();
-const BS_416: &'static [u8] = "416".as_bytes();
-const BS_420: &'static [u8] = "420".as_bytes();
-const BS_421: &'static [u8] = "421".as_bytes();
-const BS_423: &'static [u8] = "423".as_bytes();
-const BS_480: &'static [u8] = "480".as_bytes();
-const BS_481: &'static [u8] = "481".as_bytes();
-const BS_482: &'static [u8] = "482".as_bytes();
-const BS_483: &'static [u8] = "483".as_bytes();
-const BS_484: &'static [u8] = "484".as_bytes();
-const BS_485: &'static [u8] = "485".as_bytes();
-const BS_486: &'static [u8] = "486".as_bytes();
-const BS_487: &'static [u8] = "487".as_bytes();
-const BS_488: &'static [u8] = "488".as_bytes();
-const BS_491: &'static [u8] = "491".as_bytes();
-const BS_493: &'static [u8] = "493".as_bytes();
-const BS_500: &'static [u8] = "500".as_bytes();
-const BS_501: &'static [u8] = "501".as_bytes();
-const BS_502: &'static [u8] = "502".as_bytes();
-const BS_503: &'static [u8] = "503".as_bytes();
-const BS_504: &'static [u8] = "504".as_bytes();
-const BS_505: &'static [u8] = "505".as_bytes();
-const BS_513: &'static [u8] = "513".as_bytes();
-const BS_600: &'static [u8] = "600".as_bytes();
-const BS_603: &'static [u8] = "603".as_bytes();
-const BS_604: &'static [u8] = "604".as_bytes();
-const BS_606: &'static [u8] = "606".as_bytes();
-
 impl StatusCode {
-    pub fn from_str(s: &str) -> StatusCode {
+    /// Status-Code  =  3DIGIT
+    pub fn from_str(s: &str) -> Option<StatusCode> {
         StatusCode::from_bytes_str(s.as_bytes())
     }
 
-    pub fn from_bytes_str(s: &[u8]) -> StatusCode {
-        match s {
-            BS_100 => StatusCode::Trying,
-            BS_180 => StatusCode::Ringing,
-            BS_181 => StatusCode::CallIsBeingForwarded,
-            BS_182 => StatusCode::Queued,
-            BS_183 => StatusCode::SessionProgress,
-            BS_200 => StatusCode::OK,
-            BS_300 => StatusCode::MultipleChoices,
-            BS_301 => StatusCode::MovedPermanently,
-            BS_302 => StatusCode::MovedTemporarily,
-            BS_305 => StatusCode::UseProxy,
-            BS_380 => StatusCode::AlternativeService,
-            BS_400 => StatusCode::BadRequest,
-            BS_401 => StatusCode::Unauthorized,
-            BS_402 => StatusCode::PaymentRequired,
-            BS_403 => StatusCode::Forbidden,
-            BS_404 => StatusCode::NotFound,
-            BS_405 => StatusCode::MethodNotAllowed,
-            BS_406 => StatusCode::NotAcceptableResourceContent,
-            BS_407 => StatusCode::ProxyAuthenticationRequired,
-            BS_408 => StatusCode::RequestTimeout,
-            BS_410 => StatusCode::Gone,
-            BS_413 => StatusCode::RequestEntityTooLarge,
-            BS_414 => StatusCode::RequestUriTooLong,
-            BS_415 => StatusCode::UnsupportedMediaType,
-            BS_416 => StatusCode::UnsupportedUriScheme,
-            BS_420 => StatusCode::BadExtension,
-            BS_421 => StatusCode::ExtensionRequired,
-            BS_423 => StatusCode::IntervalTooBrief,
-            BS_480 => StatusCode::TemporarilyUnavailable,
-            BS_481 => StatusCode::CallOrTransactionDoesNotExist,
-            BS_482 => StatusCode::LoopDetected,
-            BS_483 => StatusCode::TooManyHops,
-            BS_484 => StatusCode::AddressIncomplete,
-            BS_485 => StatusCode::Ambiguous,
-            BS_486 => StatusCode::BusyHere,
-            BS_487 => StatusCode::RequestTerminated,
-            BS_488 => StatusCode::NotAcceptableHere,
-            BS_491 => StatusCode::RequestPending,
-            BS_493 => StatusCode::Undecipherable,
-            BS_500 => StatusCode::ServerInternalError,
-            BS_501 => StatusCode::NotImplemented,
-            BS_502 => StatusCode::BadGateway,
-            BS_503 => StatusCode::ServiceUnavailable,
-            BS_504 => StatusCode::ServerTimeout,
-            BS_505 => StatusCode::VersionNotSupported,
-            BS_513 => StatusCode::MessageTooLarge,
-            BS_600 => StatusCode::BusyEverywhere,
-            BS_603 => StatusCode::Decline,
-            BS_604 => StatusCode::DoesNotExistAnywhere,
-            BS_606 => StatusCode::NotAcceptable,
-            _ => StatusCode::Unknown,
+    pub fn from_bytes_str(s: &[u8]) -> Option<StatusCode> {
+        if s.len() != 3 || !s.iter().all(|c| c.is_ascii_digit()) {
+            return None;
         }
+        let code = s.iter().fold(0, |code, c| code * 10 + u16::from(c - b'0'));
+        StatusCode::from_code(code)
     }
 
-    pub fn reason_phrase(&self) -> &str {
-        match self {
-            &StatusCode::Trying => "Trying",
-            &StatusCode::Ringing => "Ringing",
-            &StatusCode::CallIsBeingForwarded => "Call Is Being Forwarded",
-            &StatusCode::Queued => "Queued",
-            &StatusCode::SessionProgress => "Session Progress",
-            &StatusCode::OK => "OK",
-            &StatusCode::MultipleChoices => "Multiple Choices",
-            &StatusCode::MovedPermanently => "Moved Permanently",
-            &StatusCode::MovedTemporarily => "Moved Temporarily",
-            &StatusCode::UseProxy => "Use Proxy",
-            &StatusCode::AlternativeService => "Alternative Service",
-            &StatusCode::BadRequest => "Bad Request",
-            &StatusCode::Unauthorized => "Unauthorized",
-            &StatusCode::PaymentRequired => "Payment Required",
-            &StatusCode::Forbidden => "Forbidden",
-            &StatusCode::NotFound => "Not Found",
-            &StatusCode::MethodNotAllowed => "Method Not Allowed",
-            &StatusCode::NotAcceptableResourceContent => "Not Acceptable",
-            &StatusCode::ProxyAuthenticationRequired => "Proxy Authentication Required",
-            &StatusCode::RequestTimeout => "Request Timeout",
-            &StatusCode::Gone => "Gone",
-            &StatusCode::RequestEntityTooLarge => "Request Entity Too Large",
-            &StatusCode::RequestUriTooLong => "Request-URI Too Long",
-            &StatusCode::UnsupportedMediaType => "Unsupported Media Type",
-            &StatusCode::UnsupportedUriScheme => "Unsupported URI Scheme",
-            &StatusCode::BadExtension => "Bad Extension",
-            &StatusCode::ExtensionRequired => "Extension Required",
-            &StatusCode::IntervalTooBrief => "Interval Too Brief",
-            &StatusCode::TemporarilyUnavailable => "Temporarily Unavailable",
-            &StatusCode::CallOrTransactionDoesNotExist => "Call/Transaction Does Not Exist",
-            &StatusCode::LoopDetected => "Loop Detected",
-            &StatusCode::TooManyHops => "Too Many Hops",
-            &StatusCode::AddressIncomplete => "Address Incomplete",
-            &StatusCode::Ambiguous => "Ambiguous",
-            &StatusCode::BusyHere => "Busy Here",
-            &StatusCode::RequestTerminated => "Request Terminated",
-            &StatusCode::NotAcceptableHere => "Not Acceptable Here",
-            &StatusCode::RequestPending => "Request Pending",
-            &StatusCode::Undecipherable => "Undecipherable",
-            &StatusCode::ServerInternalError => "Server Internal Error",
-            &StatusCode::NotImplemented => "Not Implemented",
-            &StatusCode::BadGateway => "Bad Gateway",
-            &StatusCode::ServiceUnavailable => "Service Unavailable",
-            &StatusCode::ServerTimeout => "Server Time-out",
-            &StatusCode::VersionNotSupported => "Version Not Supported",
-            &StatusCode::MessageTooLarge => "Message Too Large",
-            &StatusCode::BusyEverywhere => "Busy Everywhere",
-            &StatusCode::Decline => "Decline",
-            &StatusCode::DoesNotExistAnywhere => "Does Not Exist Anywhere",
-            &StatusCode::NotAcceptable => "Not Acceptable",
-            &StatusCode::Unknown => "Unknown",
-        }
+    /// 1xx
+    pub fn is_provisional(&self) -> bool {
+        self.code() / 100 == 1
+    }
+
+    /// 2xx
+    pub fn is_success(&self) -> bool {
+        self.code() / 100 == 2
+    }
+
+    /// 3xx
+    pub fn is_redirection(&self) -> bool {
+        self.code() / 100 == 3
+    }
+
+    /// 4xx
+    pub fn is_client_error(&self) -> bool {
+        self.code() / 100 == 4
+    }
+
+    /// 5xx
+    pub fn is_server_error(&self) -> bool {
+        self.code() / 100 == 5
+    }
+
+    /// 6xx
+    pub fn is_global_failure(&self) -> bool {
+        self.code() / 100 == 6
+    }
+
+    /// Final response is any response except 1xx
+    pub fn is_final(&self) -> bool {
+        !self.is_provisional()
     }
 }
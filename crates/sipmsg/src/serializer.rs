@@ -1,132 +1,195 @@
-use crate::{SipHeader, SipHeaders, SipMessage, SipRFCHeader, SipRequest, SipResponse};
+use crate::{SipHeader, SipHeaders, SipMessage, SipRequest, SipResponse};
+use alloc::vec::Vec;
+use core::{fmt, str};
 
-struct SipMsgSerializer {
-    internal_buffer: [u8; 5000],
+#[derive(PartialEq, Debug)]
+pub enum SerializeError {
+    /// Message does not fit into the buffer
+    BufferOverflow,
+    /// Message contains bytes that can't be written to `fmt::Write` (e.g. binary body)
+    NonUtf8Data,
+    /// Error returned by `fmt::Write`
+    Fmt,
 }
 
-/*
-https://tools.ietf.org/html/rfc3261#section-7.3.1
-The relative order of header fields with different field names is not
-   significant.  However, it is RECOMMENDED that header fields which are
-   needed for proxy processing (Via, Route, Record-Route, Proxy-Require,
-   Max-Forwards, and Proxy-Authorization, for example)
-*/
-
-// First element - highest priority
-static PRIORITY_HEADERS: &'static [SipRFCHeader] = &[
-    SipRFCHeader::Via,
-    SipRFCHeader::Route,
-    SipRFCHeader::RecordRoute,
-    SipRFCHeader::MaxForwards,
-    SipRFCHeader::ProxyRequire,
-    SipRFCHeader::ProxyAuthorization,
-];
-
-/// The default maximum SIP message size on Cisco UCM Release 8.6.1
-/// and earlier versions is 5000 bytes. The default on Cisco UCM Release 8.6.2
-/// and later versions is 11000 bytes.
-const MAX_SIP_MESSAGE_SIZE: usize = 5000;
-
-impl SipMsgSerializer {
-    pub fn new() -> SipMsgSerializer {
-        let ib: [u8; MAX_SIP_MESSAGE_SIZE] = [0; MAX_SIP_MESSAGE_SIZE];
-        SipMsgSerializer {
-            internal_buffer: ib,
+impl fmt::Display for SerializeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SerializeError::BufferOverflow => {
+                f.write_str("Buffer overflow. Sip message is too long")
+            }
+            SerializeError::NonUtf8Data => f.write_str("Sip message contains non utf-8 data"),
+            SerializeError::Fmt => f.write_str("Formatter error"),
         }
     }
+}
 
-    pub fn serialize_msg(&mut self, msg: &SipMessage) -> &[u8] {
-        match msg {
-            SipMessage::Request(r) => return self.serialize_req(r),
-            SipMessage::Response(r) => return self.serialize_resp(r),
-        };
+/// Destination of serialized message
+pub trait SerializerOutput {
+    fn write_bytes(&mut self, data: &[u8]) -> Result<(), SerializeError>;
+}
+
+/// Growable output
+impl SerializerOutput for Vec<u8> {
+    fn write_bytes(&mut self, data: &[u8]) -> Result<(), SerializeError> {
+        self.extend_from_slice(data);
+        Ok(())
+    }
+}
+
+impl<W: SerializerOutput + ?Sized> SerializerOutput for &mut W {
+    fn write_bytes(&mut self, data: &[u8]) -> Result<(), SerializeError> {
+        (**self).write_bytes(data)
     }
+}
 
-    pub fn serialize_req(&mut self, req: &SipRequest) -> &[u8] {
-        let buf_offset = self.append_data_to_ib(0, &req.rl.raw);
-        self.serialize(buf_offset, &req.headers, req.body)
+/// Output to the caller buffer, returns `SerializeError::BufferOverflow`
+/// if message does not fit into it
+pub struct SliceOutput<'b> {
+    buf: &'b mut [u8],
+    len: usize,
+}
+
+impl<'b> SliceOutput<'b> {
+    pub fn new(buf: &'b mut [u8]) -> SliceOutput<'b> {
+        SliceOutput { buf, len: 0 }
     }
 
-    pub fn serialize_resp(&mut self, resp: &SipResponse) -> &[u8] {
-        let buf_offset = self.append_data_to_ib(0, &resp.sl.raw);
-        self.serialize(buf_offset, &resp.headers, resp.body)
+    /// Count of written bytes
+    pub fn len(&self) -> usize {
+        self.len
     }
 
-    fn append_data_to_ib(&mut self, offset: usize, src: &[u8]) -> usize /* count written data */ {
-        let new_offset = offset + src.len();
-        if new_offset > MAX_SIP_MESSAGE_SIZE {
-            panic!("Internal buffer overflow. Sip message is too long");
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Written bytes
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.buf[..self.len]
+    }
+}
+
+impl<'b> SerializerOutput for SliceOutput<'b> {
+    fn write_bytes(&mut self, data: &[u8]) -> Result<(), SerializeError> {
+        let new_len = self.len + data.len();
+        if new_len > self.buf.len() {
+            return Err(SerializeError::BufferOverflow);
         }
-        &self.internal_buffer[offset..new_offset].copy_from_slice(src);
-        return new_offset;
+        self.buf[self.len..new_len].copy_from_slice(data);
+        self.len = new_len;
+        Ok(())
     }
+}
+
+/// Output to `core::fmt::Write` (e.g. `String` or `fmt::Formatter`).
+/// Returns `SerializeError::NonUtf8Data` if message contains binary data
+pub struct FmtOutput<W: fmt::Write>(pub W);
+
+impl<W: fmt::Write> SerializerOutput for FmtOutput<W> {
+    fn write_bytes(&mut self, data: &[u8]) -> Result<(), SerializeError> {
+        let s = str::from_utf8(data).map_err(|_| SerializeError::NonUtf8Data)?;
+        self.0.write_str(s).map_err(|_| SerializeError::Fmt)
+    }
+}
+
+/// Writes message (start line, headers in original order and body) to the output.
+/// Header lines that were not changed are written as they were received,
+/// including comma-separated lists of values.
+///
+/// ```rust
+/// use sipmsg::{SipMessage, SipMsgSerializer, SipSerializeError, SipSliceOutput};
+///
+/// let msg_buf = b"OPTIONS sip:user@example.com SIP/2.0\r\nCSeq: 1 OPTIONS\r\n\r\n";
+/// let (_, msg) = SipMessage::parse(msg_buf).unwrap();
+///
+/// let mut serializer = SipMsgSerializer::new(Vec::new());
+/// serializer.serialize_msg(&msg).unwrap();
+/// assert_eq!(serializer.output().as_slice(), &msg_buf[..]);
+///
+/// let mut buf = [0u8; 16];
+/// let mut serializer = SipMsgSerializer::new(SipSliceOutput::new(&mut buf));
+/// assert_eq!(serializer.serialize_msg(&msg), Err(SipSerializeError::BufferOverflow));
+/// ```
+pub struct SipMsgSerializer<W: SerializerOutput> {
+    output: W,
+}
+
+impl<W: SerializerOutput> SipMsgSerializer<W> {
+    pub fn new(output: W) -> SipMsgSerializer<W> {
+        SipMsgSerializer { output }
+    }
+
+    pub fn output(&self) -> &W {
+        &self.output
+    }
+
+    pub fn into_output(self) -> W {
+        self.output
+    }
+
+    pub fn serialize_msg(&mut self, msg: &SipMessage) -> Result<(), SerializeError> {
+        match msg {
+            SipMessage::Request(r) => self.serialize_req(r),
+            SipMessage::Response(r) => self.serialize_resp(r),
+        }
+    }
+
+    pub fn serialize_req(&mut self, req: &SipRequest) -> Result<(), SerializeError> {
+        self.output.write_bytes(&req.rl.raw)?;
+        self.serialize(&req.headers, req.body.as_deref())
+    }
+
+    pub fn serialize_resp(&mut self, resp: &SipResponse) -> Result<(), SerializeError> {
+        self.output.write_bytes(&resp.sl.raw)?;
+        self.serialize(&resp.headers, resp.body.as_deref())
+    }
+
     /// Serialize headers and body
-    fn serialize(&mut self, buf_offset: usize, headers: &SipHeaders, body: Option<&[u8]>) -> &[u8] {
-        let mut new_offset = buf_offset;
-        new_offset = self.serialize_headers(&headers, new_offset);
-        if body != None {
-            new_offset = self.append_data_to_ib(new_offset, body.unwrap());
+    fn serialize(
+        &mut self,
+        headers: &SipHeaders,
+        body: Option<&[u8]>,
+    ) -> Result<(), SerializeError> {
+        self.serialize_headers(headers)?;
+        match body {
+            Some(body) => self.output.write_bytes(body),
+            None => Ok(()),
         }
-        &self.internal_buffer[..new_offset]
     }
 
-    fn serialize_header(&mut self, hdr: &SipHeader, buf_offset: usize) -> usize {
-        let mut new_offset = self.append_data_to_ib(buf_offset, hdr.name.as_ref().as_bytes());
-        new_offset = self.append_data_to_ib(new_offset, b": ");
-        self.append_data_to_ib(new_offset, hdr.raw_value_param)
+    fn serialize_header(&mut self, hdr: &SipHeader) -> Result<(), SerializeError> {
+        self.output.write_bytes(hdr.name.as_ref().as_bytes())?;
+        self.output.write_bytes(b": ")?;
+        self.output.write_bytes(&hdr.raw_value_param)?;
+        self.output.write_bytes(b"\r\n")
     }
 
-    fn serialize_headers(&mut self, sip_headers: &SipHeaders, buf_offset: usize) -> usize {
-        let mut new_offset = buf_offset;
-        // STEP 1
-        // Serialize RFC priority headers
-        for hdr in PRIORITY_HEADERS {
-            let hdrs = sip_headers.get_rfc(*hdr);
-            if hdrs == None {
+    fn serialize_headers(&mut self, sip_headers: &SipHeaders) -> Result<(), SerializeError> {
+        // Headers are written in the original order,
+        // unchanged lines are written as they were received
+        let headers = sip_headers.iter().as_slice();
+        let mut pos = 0;
+        while pos < headers.len() {
+            if let Some((raw_line, count)) = SipHeader::unchanged_line(&headers[pos..]) {
+                self.output.write_bytes(raw_line)?;
+                self.output.write_bytes(b"\r\n")?;
+                pos += count;
                 continue;
             }
-            for hdr in hdrs.unwrap() {
-                new_offset = self.serialize_header(hdr, new_offset);
-                new_offset = self.append_data_to_ib(new_offset, b"\r\n");
-            }
-        }
-        // STEP 2
-        // Serialize other RFC headers
-        let other_rfs_headers_iter = sip_headers
-            .get_rfc_headers_keys()
-            .filter(|x| !PRIORITY_HEADERS.contains(x));
-        for hdrs_iter in other_rfs_headers_iter {
-            let hdrs = sip_headers.get_rfc(*hdrs_iter);
-            for hdr in hdrs.unwrap() {
-                new_offset = self.serialize_header(hdr, new_offset);
-                new_offset = self.append_data_to_ib(new_offset, b"\r\n");
-            }
-        }
-        // STEP 3
-        // Serialize extention headers
-        let ext_headers_iter = sip_headers.get_ext_headers_keys();
-        match ext_headers_iter {
-            Some(ext_hdrs) => {
-                for header_name in ext_hdrs {
-                    // One header name can contain multiple header value
-                    let hdrs = sip_headers.get_ext(header_name).unwrap();
-                    for hdr in hdrs {
-                        new_offset = self.serialize_header(hdr, new_offset);
-                        new_offset = self.append_data_to_ib(new_offset, b"\r\n");
-                    }
-                }
-            }
-            None => { /* There is no ext. headers. Do nothing */ }
+            self.serialize_header(&headers[pos])?;
+            pos += 1;
         }
 
         // Mark and headers by double "\r\n\r\n"
-        self.append_data_to_ib(new_offset, b"\r\n")
+        self.output.write_bytes(b"\r\n")
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::SipRFCHeader;
     #[test]
     fn test_serializator_parse_resp() {
         let resp_msg_buf = "SIP/2.0 180 Ringing\r\n\
@@ -139,8 +202,9 @@ mod tests {
         Content-Length: 0\r\n\r\n"
             .as_bytes();
         let (_, resp) = SipResponse::parse(resp_msg_buf).unwrap();
-        let mut s = SipMsgSerializer::new();
-        let serialized_buf = s.serialize_resp(&resp);
+        let mut s = SipMsgSerializer::new(Vec::new());
+        s.serialize_resp(&resp).unwrap();
+        let serialized_buf = s.output();
 
         let (_, msg2) = SipMessage::parse(serialized_buf).unwrap();
         let new_resp = msg2.response().unwrap();
@@ -217,8 +281,9 @@ mod tests {
         Content-Length: 4\r\n\r\nbody".as_bytes();
 
         let (_, msg) = SipMessage::parse(invite_msg_buf).unwrap();
-        let mut s = SipMsgSerializer::new();
-        let serialized_buf = s.serialize_msg(&msg);
+        let mut s = SipMsgSerializer::new(Vec::new());
+        s.serialize_msg(&msg).unwrap();
+        let serialized_buf = s.output();
         let (_, msg2) = SipMessage::parse(serialized_buf).unwrap();
         let new_req = msg2.request().unwrap();
         assert_eq!(
@@ -315,11 +380,11 @@ mod tests {
 
         assert_eq!(
             new_req.headers.get_rfc(SipRFCHeader::Supported).unwrap()[0].raw_value_param,
-            b"replaces"
+            "replaces".as_bytes()
         );
         assert_eq!(
             new_req.headers.get_rfc(SipRFCHeader::Supported).unwrap()[1].raw_value_param,
-            b"100rel"
+            "100rel".as_bytes()
         );
 
         assert_eq!(
@@ -328,7 +393,7 @@ mod tests {
                 .get_rfc_s(SipRFCHeader::ContentType)
                 .unwrap()
                 .raw_value_param,
-            b"application/sdp"
+            "application/sdp".as_bytes()
         );
 
         assert_eq!(
@@ -337,16 +402,153 @@ mod tests {
                 .get_rfc_s(SipRFCHeader::ContentLength)
                 .unwrap()
                 .raw_value_param,
-            b"4"
+            "4".as_bytes()
         );
 
         let extension_headers = new_req.headers.get_ext("ExtensionHeader").unwrap();
-        assert_eq!(extension_headers[0].raw_value_param, b"value1;param");
+        assert_eq!(
+            extension_headers[0].raw_value_param,
+            "value1;param".as_bytes()
+        );
         assert_eq!(
             extension_headers[1].raw_value_param,
-            b"value2;param1=value1"
+            "value2;param1=value1".as_bytes()
         );
 
-        assert_eq!(new_req.body.unwrap(), b"body");
+        assert_eq!(new_req.body.as_deref().unwrap(), "body".as_bytes());
+    }
+
+    #[test]
+    fn test_serializator_edited_headers() {
+        let invite_msg_buf = "INVITE sip:bob@biloxi.com SIP/2.0\r\n\
+        Via: SIP/2.0/UDP 10.0.0.2;branch=z9hG4bK2\r\n\
+        Route: <sip:p1.example.com;lr>\r\n\
+        Max-Forwards: 70\r\n\
+        CSeq: 1 INVITE\r\n\
+        Content-Length: 0\r\n\r\n"
+            .as_bytes();
+
+        let (_, mut req) = SipRequest::parse(invite_msg_buf).unwrap();
+        req.headers.prepend(
+            SipHeader::from_name_value("Via", "SIP/2.0/UDP 10.0.0.1;branch=z9hG4bK1").unwrap(),
+        );
+        req.headers.pop_rfc(SipRFCHeader::Route);
+        req.headers
+            .replace(SipHeader::from_name_value("Max-Forwards", "69").unwrap());
+
+        let mut s = SipMsgSerializer::new(Vec::new());
+        s.serialize_req(&req).unwrap();
+        let serialized_buf = s.output();
+        let (_, new_req) = SipRequest::parse(serialized_buf).unwrap();
+        let vias = new_req.headers.get_rfc(SipRFCHeader::Via).unwrap();
+        assert_eq!(
+            vias[0].raw_value_param,
+            "SIP/2.0/UDP 10.0.0.1;branch=z9hG4bK1".as_bytes()
+        );
+        assert_eq!(
+            vias[1].raw_value_param,
+            "SIP/2.0/UDP 10.0.0.2;branch=z9hG4bK2".as_bytes()
+        );
+        assert!(new_req.headers.get_rfc(SipRFCHeader::Route).is_none());
+        assert_eq!(
+            new_req
+                .headers
+                .get_rfc_s(SipRFCHeader::MaxForwards)
+                .unwrap()
+                .raw_value_param,
+            "69".as_bytes()
+        );
+    }
+
+    #[test]
+    fn test_serializator_keeps_headers_order() {
+        let msg_buf = "SIP/2.0 200 OK\r\n\
+        X-First: 1\r\n\
+        CSeq: 1 OPTIONS\r\n\
+        Via: SIP/2.0/UDP 10.0.0.1;branch=z9hG4bK1\r\n\
+        X-Second: 2\r\n\
+        Call-ID: a84b4c76e66710\r\n\
+        Via: SIP/2.0/UDP 10.0.0.2;branch=z9hG4bK2\r\n\
+        X-First: 3\r\n\
+        Content-Length: 4\r\n\r\nbody"
+            .as_bytes();
+        let (_, msg) = SipMessage::parse(msg_buf).unwrap();
+        let mut s = SipMsgSerializer::new(Vec::new());
+        s.serialize_msg(&msg).unwrap();
+        assert_eq!(s.output().as_slice(), msg_buf);
+    }
+
+    #[test]
+    fn test_serializator_keeps_header_lists() {
+        let msg_buf = "INVITE sip:bob@biloxi.com SIP/2.0\r\n\
+        Via: SIP/2.0/UDP 10.0.0.1;branch=z9hG4bK1, SIP/2.0/UDP 10.0.0.2;branch=z9hG4bK2\r\n\
+        Route: <sip:p1.example.com;lr>,<sip:p2.example.com;lr>\r\n\
+        Allow: INVITE, ACK, BYE\r\n\
+        Supported:replaces ,100rel,\r\n timer\r\n\
+        Route: <sip:p3.example.com;lr>\r\n\
+        Allow : CANCEL , OPTIONS\r\n\
+        Content-Length: 0\r\n\r\n"
+            .as_bytes();
+        let (_, mut req) = SipRequest::parse(msg_buf).unwrap();
+        assert_eq!(req.headers.get_rfc(SipRFCHeader::Allow).unwrap().len(), 5);
+        let mut s = SipMsgSerializer::new(Vec::new());
+        s.serialize_req(&req).unwrap();
+        assert_eq!(s.output().as_slice(), msg_buf);
+
+        // changed list is written as separate headers
+        req.headers.pop_rfc(SipRFCHeader::Route);
+        req.headers
+            .top_rfc_mut(SipRFCHeader::Via)
+            .unwrap()
+            .set_param("received", Some("192.0.2.1"))
+            .unwrap();
+        let mut s = SipMsgSerializer::new(Vec::new());
+        s.serialize_req(&req).unwrap();
+        assert_eq!(
+            str::from_utf8(s.output()).unwrap(),
+            "INVITE sip:bob@biloxi.com SIP/2.0\r\n\
+            Via: SIP/2.0/UDP 10.0.0.1;branch=z9hG4bK1;received=192.0.2.1\r\n\
+            Via: SIP/2.0/UDP 10.0.0.2;branch=z9hG4bK2\r\n\
+            Route: <sip:p2.example.com;lr>\r\n\
+            Allow: INVITE, ACK, BYE\r\n\
+            Supported:replaces ,100rel,\r\n timer\r\n\
+            Route: <sip:p3.example.com;lr>\r\n\
+            Allow : CANCEL , OPTIONS\r\n\
+            Content-Length: 0\r\n\r\n"
+        );
+    }
+
+    #[test]
+    fn test_serializator_outputs() {
+        let mut msg_buf = Vec::new();
+        msg_buf.extend_from_slice(
+            b"MESSAGE sip:bob@biloxi.com SIP/2.0\r\nCSeq: 1 MESSAGE\r\nContent-Length: 10000\r\n\r\n",
+        );
+        msg_buf.resize(msg_buf.len() + 10000, b'a');
+        let (_, msg) = SipMessage::parse(&msg_buf).unwrap();
+
+        // message is bigger than 5000 bytes
+        let mut s = SipMsgSerializer::new(Vec::new());
+        s.serialize_msg(&msg).unwrap();
+        assert_eq!(s.into_output(), msg_buf);
+
+        let mut buf = [0u8; 11000];
+        let mut s = SipMsgSerializer::new(SliceOutput::new(&mut buf));
+        s.serialize_msg(&msg).unwrap();
+        assert_eq!(s.output().len(), msg_buf.len());
+        assert_eq!(s.output().as_bytes(), msg_buf.as_slice());
+
+        let mut buf = [0u8; 5000];
+        let mut s = SipMsgSerializer::new(SliceOutput::new(&mut buf));
+        assert_eq!(s.serialize_msg(&msg), Err(SerializeError::BufferOverflow));
+
+        let mut out = FmtOutput(alloc::string::String::new());
+        SipMsgSerializer::new(&mut out).serialize_msg(&msg).unwrap();
+        assert_eq!(out.0.as_bytes(), msg_buf.as_slice());
+
+        let (_, msg) =
+            SipMessage::parse(b"SIP/2.0 200 OK\r\nContent-Length: 2\r\n\r\n\xff\xfe").unwrap();
+        let mut s = SipMsgSerializer::new(FmtOutput(alloc::string::String::new()));
+        assert_eq!(s.serialize_msg(&msg), Err(SerializeError::NonUtf8Data));
     }
 }
@@ -0,0 +1,354 @@
+use crate::common::{
+    bnfcore::{is_crlf, is_wsp},
+    errorparse::{ParseErrorKind, SipParseError},
+};
+use crate::{message::parse_content_length, SipRFCHeader};
+use alloc::vec::Vec;
+use core::str;
+
+/// Result of [`StreamParser::next_message`]
+#[derive(PartialEq, Debug)]
+pub enum StreamParserStatus<'a> {
+    /// Buffered data does not contain a complete message yet
+    NeedMoreData,
+    /// Bytes of one complete message (start line, headers and body).
+    /// It can be passed to `SipMessage::parse` as is.
+    Message(&'a [u8]),
+}
+
+/// Incremental message framer for stream transports (TCP/TLS).
+///
+/// Chunks are appended by `feed` as they arrive from the socket,
+/// `next_message` returns message boundaries according to
+/// [rfc3261 section-18.3](https://tools.ietf.org/html/rfc3261#section-18.3):
+/// the header section ends with an empty line and the body length
+/// is taken from Content-Length (0 if the header is absent).
+/// CRLF keep-alives between messages
+/// ([rfc5626 section-3.5.1](https://tools.ietf.org/html/rfc5626#section-3.5.1)) are skipped.
+/// Sizes of the header section and the body are limited, so a peer can't make
+/// the buffer grow without bound; exceeding a limit is an error of `next_message`.
+///
+/// ```rust
+/// use sipmsg::{SipMessage, SipStreamParser, SipStreamParserStatus};
+///
+/// let mut parser = SipStreamParser::new();
+/// parser.feed(b"\r\n\r\nOPTIONS sip:user@example.com SIP/2.0\r\nContent-Len");
+/// assert_eq!(parser.next_message().unwrap(), SipStreamParserStatus::NeedMoreData);
+///
+/// parser.feed(b"gth: 4\r\n\r\nbody");
+/// match parser.next_message().unwrap() {
+///     SipStreamParserStatus::Message(msg) => {
+///         let (_, sip_msg) = SipMessage::parse(msg).unwrap();
+///         assert_eq!(sip_msg.request().unwrap().body.as_deref().unwrap(), b"body");
+///     }
+///     SipStreamParserStatus::NeedMoreData => panic!(),
+/// }
+/// assert_eq!(parser.next_message().unwrap(), SipStreamParserStatus::NeedMoreData);
+///
+/// let mut parser = SipStreamParser::new().max_body_len(1024);
+/// parser.feed(b"SIP/2.0 200 OK\r\nContent-Length: 4096\r\n\r\n");
+/// assert_eq!(
+///     parser.next_message().unwrap_err().kind,
+///     sipmsg::SipParseErrorKind::BodyTooLarge
+/// );
+/// ```
+pub struct StreamParser {
+    buffer: Vec<u8>,
+    /// Length of message returned by last `next_message` call.
+    /// It is removed from buffer on the next call to `feed` or `next_message`.
+    returned_len: usize,
+    /// Position of buffer from which search of the empty line is continued
+    scanned_len: usize,
+    /// Length of the next message when its header section is received
+    message_len: Option<usize>,
+    max_headers_len: usize,
+    max_body_len: usize,
+}
+
+impl Default for StreamParser {
+    fn default() -> StreamParser {
+        StreamParser::new()
+    }
+}
+
+impl StreamParser {
+    /// Default limit of start line and headers including the empty line
+    pub const DEFAULT_MAX_HEADERS_LEN: usize = 64 * 1024;
+    /// Default limit of Content-Length
+    pub const DEFAULT_MAX_BODY_LEN: usize = 1024 * 1024;
+
+    pub fn new() -> StreamParser {
+        StreamParser {
+            buffer: Vec::new(),
+            returned_len: 0,
+            scanned_len: 0,
+            message_len: None,
+            max_headers_len: StreamParser::DEFAULT_MAX_HEADERS_LEN,
+            max_body_len: StreamParser::DEFAULT_MAX_BODY_LEN,
+        }
+    }
+
+    /// Set limit of start line and headers including the empty line
+    pub fn max_headers_len(mut self, len: usize) -> StreamParser {
+        self.max_headers_len = len;
+        self
+    }
+
+    /// Set limit of Content-Length
+    pub fn max_body_len(mut self, len: usize) -> StreamParser {
+        self.max_body_len = len;
+        self
+    }
+
+    /// Append received bytes
+    pub fn feed(&mut self, chunk: &[u8]) {
+        self.drain_returned();
+        self.buffer.extend_from_slice(chunk);
+    }
+
+    /// Count of buffered bytes that are not returned as message yet
+    pub fn buffered_len(&self) -> usize {
+        self.buffer.len() - self.returned_len
+    }
+
+    /// Returns next complete message or `NeedMoreData`.
+    /// Error means that the stream is corrupted or exceeds the limits
+    /// and connection should be closed.
+    pub fn next_message(&mut self) -> Result<StreamParserStatus<'_>, SipParseError<'static>> {
+        self.drain_returned();
+
+        let message_len = match self.message_len {
+            Some(len) => len,
+            None => {
+                self.skip_keep_alives();
+                let headers_len = match self.find_headers_end() {
+                    Some(len) => len,
+                    None if self.buffer.len() > self.max_headers_len => {
+                        return Err(SipParseError::new(
+                            ParseErrorKind::HeaderSectionTooLarge,
+                            None,
+                        ))
+                    }
+                    None => return Ok(StreamParserStatus::NeedMoreData),
+                };
+                if headers_len > self.max_headers_len {
+                    return Err(SipParseError::new(
+                        ParseErrorKind::HeaderSectionTooLarge,
+                        None,
+                    ));
+                }
+                let body_len = StreamParser::content_length(&self.buffer[..headers_len])?;
+                if body_len > self.max_body_len {
+                    return Err(SipParseError::new(ParseErrorKind::BodyTooLarge, None));
+                }
+                self.message_len = Some(headers_len + body_len);
+                headers_len + body_len
+            }
+        };
+        if self.buffer.len() < message_len {
+            return Ok(StreamParserStatus::NeedMoreData);
+        }
+
+        self.returned_len = message_len;
+        self.message_len = None;
+        self.scanned_len = 0;
+        Ok(StreamParserStatus::Message(&self.buffer[..message_len]))
+    }
+
+    fn drain_returned(&mut self) {
+        if self.returned_len != 0 {
+            self.buffer.drain(..self.returned_len);
+            self.returned_len = 0;
+        }
+    }
+
+    fn skip_keep_alives(&mut self) {
+        let mut idx = 0;
+        while is_crlf(&self.buffer[idx..]) {
+            idx += 2;
+        }
+        if idx != 0 {
+            self.buffer.drain(..idx);
+            self.scanned_len = self.scanned_len.saturating_sub(idx);
+        }
+    }
+
+    /// Returns length of start line and headers including the empty line.
+    /// Search is continued from the end of the previous one,
+    /// so bytes of a long header section are not scanned again on each call
+    fn find_headers_end(&mut self) -> Option<usize> {
+        let found = self.buffer[self.scanned_len..]
+            .windows(4)
+            .position(|w| w == b"\r\n\r\n")
+            .map(|pos| self.scanned_len + pos + 4);
+        if found.is_none() {
+            // the empty line can start in the last 3 bytes
+            self.scanned_len = self.buffer.len().saturating_sub(3);
+        }
+        found
+    }
+
+    /// Light scan of header section to find Content-Length value.
+    /// Full validation of headers is made by `SipMessage::parse`.
+    fn content_length(header_section: &[u8]) -> Result<usize, SipParseError<'static>> {
+        let lines = header_section.split(|c| *c == b'\n').skip(1); // skip start line
+        let mut content_length = None;
+        for line in lines {
+            if line.is_empty() || is_wsp(line[0]) {
+                // folded value line
+                continue;
+            }
+            let colon_pos = match line.iter().position(|c| *c == b':') {
+                Some(pos) => pos,
+                None => continue,
+            };
+            let name = match str::from_utf8(&line[..colon_pos]) {
+                Ok(name) => name.trim_end(),
+                Err(_) => continue,
+            };
+            if SipRFCHeader::from_str(name) != Some(SipRFCHeader::ContentLength) {
+                continue;
+            }
+            if content_length.is_some() {
+                return Err(SipParseError::new(
+                    ParseErrorKind::MultipleContentLength,
+                    Some("Content-Length header must be present only once"),
+                ));
+            }
+            let value = str::from_utf8(&line[colon_pos + 1..]).unwrap_or("");
+            match parse_content_length(value.trim()) {
+                Some(len) => content_length = Some(len),
+                None => {
+                    return Err(SipParseError::new(
+                        ParseErrorKind::InvalidContentLength,
+                        Some("Invalid Content-Length value"),
+                    ))
+                }
+            }
+        }
+        Ok(content_length.unwrap_or(0))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const OPTIONS_MSG: &[u8] = b"OPTIONS sip:user@example.com SIP/2.0\r\n\
+        Via: SIP/2.0/TCP 10.135.0.12:5060;branch=z9hG4bKhye0bem20x.nx8hnt\r\n\
+        Call-ID: ud04chatv9q@10.135.0.1\r\n\
+        CSeq: 1 OPTIONS\r\n\
+        l: 4\r\n\r\nbody";
+
+    #[test]
+    fn stream_parser_byte_by_byte() {
+        let mut parser = StreamParser::new();
+        for b in &OPTIONS_MSG[..OPTIONS_MSG.len() - 1] {
+            parser.feed(&[*b]);
+            assert_eq!(
+                parser.next_message().unwrap(),
+                StreamParserStatus::NeedMoreData
+            );
+        }
+        parser.feed(&OPTIONS_MSG[OPTIONS_MSG.len() - 1..]);
+        assert_eq!(
+            parser.next_message().unwrap(),
+            StreamParserStatus::Message(OPTIONS_MSG)
+        );
+        assert_eq!(
+            parser.next_message().unwrap(),
+            StreamParserStatus::NeedMoreData
+        );
+        assert_eq!(parser.buffered_len(), 0);
+    }
+
+    #[test]
+    fn stream_parser_several_messages() {
+        let mut parser = StreamParser::new();
+        parser.feed(b"\r\n\r\n");
+        parser.feed(OPTIONS_MSG);
+        parser.feed(b"\r\n");
+        parser.feed(b"SIP/2.0 200 OK\r\nCSeq: 1 OPTIONS\r\n\r\nSIP/2.0");
+        assert_eq!(
+            parser.next_message().unwrap(),
+            StreamParserStatus::Message(OPTIONS_MSG)
+        );
+        assert_eq!(
+            parser.next_message().unwrap(),
+            StreamParserStatus::Message(b"SIP/2.0 200 OK\r\nCSeq: 1 OPTIONS\r\n\r\n")
+        );
+        assert_eq!(
+            parser.next_message().unwrap(),
+            StreamParserStatus::NeedMoreData
+        );
+        assert_eq!(parser.buffered_len(), 7);
+    }
+
+    #[test]
+    fn stream_parser_invalid_content_length() {
+        let mut parser = StreamParser::new();
+        parser.feed(b"SIP/2.0 200 OK\r\nContent-Length: abc\r\n\r\n");
+        assert!(parser.next_message().is_err());
+
+        let mut parser = StreamParser::new();
+        parser.feed(b"SIP/2.0 200 OK\r\nContent-Length: 1\r\nl: 1\r\n\r\nb");
+        assert!(parser.next_message().is_err());
+    }
+
+    #[test]
+    fn stream_parser_limits() {
+        let mut parser = StreamParser::new().max_headers_len(OPTIONS_MSG.len() - 5);
+        parser.feed(&OPTIONS_MSG[..OPTIONS_MSG.len() - 20]);
+        assert_eq!(
+            parser.next_message().unwrap(),
+            StreamParserStatus::NeedMoreData
+        );
+        parser.feed(&OPTIONS_MSG[OPTIONS_MSG.len() - 20..]);
+        assert_eq!(
+            parser.next_message().unwrap_err().kind,
+            ParseErrorKind::HeaderSectionTooLarge
+        );
+
+        let mut parser = StreamParser::new().max_headers_len(16);
+        parser.feed(b"OPTIONS sip:user@example.com SIP/2.0\r\nVia: ");
+        assert_eq!(
+            parser.next_message().unwrap_err().kind,
+            ParseErrorKind::HeaderSectionTooLarge
+        );
+
+        let mut parser = StreamParser::new().max_body_len(3);
+        parser.feed(OPTIONS_MSG);
+        assert_eq!(
+            parser.next_message().unwrap_err().kind,
+            ParseErrorKind::BodyTooLarge
+        );
+    }
+
+    #[test]
+    fn stream_parser_continues_scan() {
+        let mut parser = StreamParser::new();
+        parser.feed(b"\r\nSIP/2.0 200 OK\r\nCSeq: 1 OPTIONS\r\nl: 2\r");
+        assert_eq!(
+            parser.next_message().unwrap(),
+            StreamParserStatus::NeedMoreData
+        );
+        assert_eq!(parser.scanned_len, parser.buffer.len() - 3);
+        parser.feed(b"\n\r");
+        assert_eq!(
+            parser.next_message().unwrap(),
+            StreamParserStatus::NeedMoreData
+        );
+        parser.feed(b"\no");
+        assert_eq!(
+            parser.next_message().unwrap(),
+            StreamParserStatus::NeedMoreData
+        );
+        assert_eq!(parser.message_len, Some(parser.buffer.len() + 1));
+        parser.feed(b"k");
+        assert_eq!(
+            parser.next_message().unwrap(),
+            StreamParserStatus::Message(b"SIP/2.0 200 OK\r\nCSeq: 1 OPTIONS\r\nl: 2\r\n\r\nok")
+        );
+        assert_eq!(parser.message_len, None);
+    }
+}
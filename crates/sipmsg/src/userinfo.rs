@@ -1,16 +1,18 @@
 use crate::common::{
     bnfcore::*,
     errorparse::SipParseError,
+    escape::unescape_str,
     nom_wrappers::{from_utf8_nom, take_while_with_escaped},
 };
-use core::str;
+use alloc::borrow::Cow;
+use core::{fmt, str};
 
 /// userinfo =  ( user / telephone-subscriber ) [ ":" password ] "@"
 /// user     =  1*( unreserved / escaped / user-unreserved )
-#[derive(PartialEq, Debug)]
+#[derive(Clone, PartialEq, Debug)]
 pub struct UserInfo<'a> {
-    pub value: &'a str, // ( user / telephone-subscriber )
-    pub password: Option<&'a str>,
+    pub value: Cow<'a, str>, // ( user / telephone-subscriber )
+    pub password: Option<Cow<'a, str>>,
     // TODO add boolean or enum about detect is it user or telefon-subscriber
 }
 
@@ -20,6 +22,23 @@ fn is_userinfo_char(c: u8) -> bool {
 }
 
 impl<'a> UserInfo<'a> {
+    /// User with escaped characters decoded, e.g. `alice%40atlanta.com` -> `alice@atlanta.com`
+    pub fn decoded_user(&self) -> Cow<'_, str> {
+        unescape_str(&self.value)
+    }
+
+    /// Password with escaped characters decoded
+    pub fn decoded_password(&self) -> Option<Cow<'_, str>> {
+        self.password.as_deref().map(unescape_str)
+    }
+
+    pub fn into_owned(self) -> UserInfo<'static> {
+        UserInfo {
+            value: Cow::Owned(self.value.into_owned()),
+            password: self.password.map(|p| Cow::Owned(p.into_owned())),
+        }
+    }
+
     fn take_user(input: &'a [u8]) -> nom::IResult<&'a [u8], &'a [u8], SipParseError> {
         take_while_with_escaped(input, is_userinfo_char)
     }
@@ -29,38 +48,54 @@ impl<'a> UserInfo<'a> {
     }
 
     pub fn from_bytes(input: &'a [u8]) -> Result<UserInfo, nom::Err<SipParseError>> {
-        if input.len() <= 1 {
-            return sip_parse_error!(1);
+        // "@" is optional
+        let input = match input.last() {
+            Some(b'@') => &input[..input.len() - 1],
+            _ => input,
+        };
+        if input.is_empty() {
+            return sip_parse_error!(InvalidUserInfo);
         }
 
-        if !is_userinfo_char(input[0]) {
-            return sip_parse_error!(2);
+        if !is_userinfo_char(input[0]) && !is_escaped(input) {
+            return sip_parse_error!(InvalidUserInfo);
         }
 
         let (input, user) = UserInfo::take_user(input)?;
-        if input.len() == 0 || (input.len() == 1 && input[0] == b'@') {
+        if input.len() == 0 {
             let (_, user_str) = from_utf8_nom(user)?;
             return Ok(UserInfo {
-                value: user_str,
+                value: Cow::Borrowed(user_str),
                 password: None,
             });
         } else {
-            if input[0] != b':' || input.len() == 2 {
-                // input.len() == 2 it is ":@" ( emptypass )
-                return sip_parse_error!(3, "Empty password");
+            if input[0] != b':' || input.len() == 1 {
+                // input.len() == 1 it is ":" ( emptypass )
+                return sip_parse_error!(InvalidUserInfo, "Empty password");
             }
 
             let (_, pswd) = UserInfo::take_password(&input[1..])?;
             let (_, user_str) = from_utf8_nom(user)?;
             let (_, pswd_str) = from_utf8_nom(pswd)?;
             return Ok(UserInfo {
-                value: user_str,
-                password: Some(pswd_str),
+                value: Cow::Borrowed(user_str),
+                password: Some(Cow::Borrowed(pswd_str)),
             });
         }
     }
 }
 
+/// Writes userinfo without trailing "@"
+impl<'a> fmt::Display for UserInfo<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.value)?;
+        match &self.password {
+            Some(password) => write!(f, ":{}", password),
+            None => Ok(()),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -96,7 +131,7 @@ mod tests {
         match UserInfo::from_bytes(input.as_bytes()) {
             Ok(userinfo) => {
                 assert_eq!(userinfo.value, expexted_value);
-                assert_eq!(userinfo.password, expected_password);
+                assert_eq!(userinfo.password.as_deref(), expected_password);
             }
             Err(_) => panic!(),
         }
@@ -108,6 +143,8 @@ mod tests {
         test_case_from_bytes("+1-212-555-1212:1234@", "+1-212-555-1212", Some("1234"));
         test_case_from_bytes("a:b@", "a", Some("b"));
         test_case_from_bytes("a@", "a", None);
+        test_case_from_bytes("bob:a", "bob", Some("a"));
+        test_case_from_bytes("%61lice@", "%61lice", None);
 
         parse_should_fail("alice:@");
         parse_should_fail(":@");
@@ -115,4 +152,16 @@ mod tests {
         parse_should_fail("@");
         parse_should_fail("");
     }
+
+    #[test]
+    fn user_info_decoded() {
+        let userinfo = UserInfo::from_bytes(b"alice%40atlanta.com:p%40ss@").unwrap();
+        assert_eq!(userinfo.value, "alice%40atlanta.com");
+        assert_eq!(userinfo.decoded_user(), "alice@atlanta.com");
+        assert_eq!(userinfo.decoded_password().unwrap(), "p@ss");
+
+        let userinfo = UserInfo::from_bytes(b"bob@").unwrap();
+        assert!(matches!(userinfo.decoded_user(), Cow::Borrowed("bob")));
+        assert_eq!(userinfo.decoded_password(), None);
+    }
 }
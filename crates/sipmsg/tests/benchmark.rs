@@ -37,7 +37,7 @@ Content-Length: 0\r\n\r\n"
                 .params()
                 .unwrap()
                 .get(&"branch"),
-            Some(&Some("z9hG4bKnashds8"))
+            Some(Some("z9hG4bKnashds8"))
         );
         counter += 1;
         if now.elapsed().as_secs() == 1 {
@@ -76,13 +76,13 @@ fn accept_encoding_header() {
     assert_eq!(hdrs[0].value.vstr, "compress");
     assert_eq!(
         hdrs[0].params().unwrap().get("q").unwrap(),
-        &Some("0.5")
+        Some("0.5")
     );
     assert_eq!(hdrs[1].name, "Accept-Encoding");
     assert_eq!(hdrs[1].value.vstr, "gzip");
     assert_eq!(
         hdrs[1].params().unwrap().get("q").unwrap(),
-        &Some("1.0")
+        Some("1.0")
     );
     assert_eq!(input.len(), 2);
 
@@ -94,21 +94,21 @@ fn accept_encoding_header() {
     assert_eq!(hdrs[0].raw_value_param, "gzip;q=1.0".as_bytes());
     assert_eq!(
         hdrs[0].params().unwrap().get("q").unwrap(),
-        &Some("1.0")
+        Some("1.0")
     );
     assert_eq!(hdrs[1].name, "Accept-Encoding");
     assert_eq!(hdrs[1].raw_value_param, "identity; q=0.5".as_bytes());
     assert_eq!(hdrs[1].value.vstr, "identity");
     assert_eq!(
         hdrs[1].params().unwrap().get("q").unwrap(),
-        &Some("0.5")
+        Some("0.5")
     );
 
     assert_eq!(hdrs[2].name, "Accept-Encoding");
     assert_eq!(hdrs[2].value.vstr, "*");
     assert_eq!(
         hdrs[2].params().unwrap().get("q").unwrap(),
-        &Some("0")
+        Some("0")
     );
     assert_eq!(input.len(), 2);
 
@@ -155,9 +155,50 @@ fn alert_info_header() {
         hdrs[0].value.tags().unwrap()[&SipHeaderTagType::AbsoluteURI],
         "http://www.example.com/sounds/moo.wav".as_bytes()
     );
+    match hdrs[0].value.uri().unwrap() {
+        Uri::Http(uri) => {
+            assert_eq!(uri.host(), Some("www.example.com"));
+            assert_eq!(uri.path, "/sounds/moo.wav");
+        }
+        _ => panic!(),
+    }
     assert_eq!(input, "\r\n".as_bytes());
 }
 
+#[test]
+fn name_addr_absolute_uri() {
+    let (_, (_, hdrs)) = SipHeader::parse("To: urn:service:sos;tag=123\r\n".as_bytes()).unwrap();
+    assert_eq!(hdrs[0].value.vstr, "urn:service:sos");
+    assert_eq!(hdrs[0].params().unwrap().get("tag"), Some(Some("123")));
+    match hdrs[0].value.uri().unwrap() {
+        Uri::Urn(uri) => assert_eq!(uri.path, "service:sos"),
+        _ => panic!(),
+    }
+
+    let (_, (_, hdrs)) = SipHeader::parse(
+        "Contact: \"Alice\" <im:alice@atlanta.com>;q=0.5, <mailto:alice@atlanta.com>\r\n"
+            .as_bytes(),
+    )
+    .unwrap();
+    assert_eq!(hdrs.len(), 2);
+    assert!(matches!(hdrs[0].value.uri(), Some(Uri::Im(_))));
+    assert_eq!(
+        hdrs[0].value.uri().unwrap().to_string(),
+        "im:alice@atlanta.com"
+    );
+    assert_eq!(hdrs[0].params().unwrap().get("q"), Some(Some("0.5")));
+    assert!(matches!(hdrs[1].value.uri(), Some(Uri::Mailto(_))));
+    assert_eq!(
+        hdrs[1].value.tags().unwrap()[&SipHeaderTagType::AbsoluteURI],
+        "mailto:alice@atlanta.com".as_bytes()
+    );
+
+    let (_, (_, hdrs)) =
+        SipHeader::parse("Route: <pres:alice@atlanta.com>\r\n".as_bytes()).unwrap();
+    assert!(matches!(hdrs[0].value.uri(), Some(Uri::Pres(_))));
+    assert_eq!(hdrs[0].value.sip_uri(), None);
+}
+
 #[test]
 fn accept_language_header() {
     let (input, (_, hdrs)) =
@@ -168,13 +209,13 @@ fn accept_language_header() {
     assert_eq!(hdrs[1].value.vstr, "en-gb");
     assert_eq!(
         hdrs[1].params().unwrap().get("q").unwrap(),
-        &Some("0.8")
+        Some("0.8")
     );
 
     assert_eq!(hdrs[2].value.vstr, "en");
     assert_eq!(
         hdrs[2].params().unwrap().get("q").unwrap(),
-        &Some("0.7")
+        Some("0.7")
     );
 
     assert_eq!(input.len(), 2)
@@ -192,23 +233,23 @@ fn authorization_header() {
     );
     assert_eq!(
         hdrs[0].value.tags().unwrap()[&SipHeaderTagType::Username],
-        b"bob"
+        "bob".as_bytes()
     );
     assert_eq!(
         hdrs[0].value.tags().unwrap()[&SipHeaderTagType::Realm],
-        b"atlanta.example.com"
+        "atlanta.example.com".as_bytes()
     );
     assert_eq!(
         hdrs[0].value.tags().unwrap()[&SipHeaderTagType::Nonce],
-        b"ea9c8e88df84f1cec4341ae6cbe5a359"
+        "ea9c8e88df84f1cec4341ae6cbe5a359".as_bytes()
     );
     assert_eq!(
         hdrs[0].value.tags().unwrap()[&SipHeaderTagType::Opaque],
-        b""
+        "".as_bytes()
     );
     assert_eq!(
         hdrs[0].value.tags().unwrap()[&SipHeaderTagType::DigestUri],
-        b"sips:ss2.biloxi.example.com"
+        "sips:ss2.biloxi.example.com".as_bytes()
     );
 
     assert_eq!(input, b"\r\n");
@@ -224,11 +265,11 @@ fn call_id_header() {
     );
     assert_eq!(
         hdrs[0].value.tags().unwrap()[&SipHeaderTagType::ID],
-        b"3848276298220188511"
+        "3848276298220188511".as_bytes()
     );
     assert_eq!(
         hdrs[0].value.tags().unwrap()[&SipHeaderTagType::Host],
-        b"atlanta.example.com"
+        "atlanta.example.com".as_bytes()
     );
     assert_eq!(input, b"\r\n");
 
@@ -237,7 +278,7 @@ fn call_id_header() {
     assert_eq!(hdrs[0].value.vstr, "3848276298220188511");
     assert_eq!(
         hdrs[0].value.tags().unwrap()[&SipHeaderTagType::ID],
-        b"3848276298220188511"
+        "3848276298220188511".as_bytes()
     );
     assert_eq!(
         hdrs[0].value.tags().unwrap().get(&SipHeaderTagType::Host),
@@ -265,7 +306,7 @@ fn callinfo_test() {
 
     assert_eq!(
         hdrs[0].params().unwrap().get("purpose"),
-        Some(&Some("icon"))
+        Some(Some("icon"))
     );
 
     assert_eq!(hdrs[1].value.vstr, "<http://www.example.com/alice/>");
@@ -276,7 +317,7 @@ fn callinfo_test() {
 
     assert_eq!(
         hdrs[1].params().unwrap().get("purpose"),
-        Some(&Some("info"))
+        Some(Some("info"))
     );
 
     assert_eq!(input, b"\r\n");
@@ -294,11 +335,11 @@ fn content_disposition_header() {
     assert_eq!(hdrs[0].raw_value_param, "attachment; filename=smime.p7s; handling=required".as_bytes());
     assert_eq!(
         hdrs[0].params().unwrap().get("filename").unwrap(),
-        &Some("smime.p7s")
+        Some("smime.p7s")
     );
     assert_eq!(
         hdrs[0].params().unwrap().get("handling").unwrap(),
-        &Some("required")
+        Some("required")
     );
     assert_eq!(input.len(), 2)
 }
@@ -59,7 +59,7 @@ fn parse_headers() {
 
     let to_hdr = hdrs.get_rfc_s(SipRFCHeader::To).unwrap();
     assert_eq!(to_hdr.value.vstr, "David <sip:davidko@biloxi.com>");
-    assert_eq!(to_hdr.params().unwrap().get(&"tag"), Some(&Some("99sa0xk")));
+    assert_eq!(to_hdr.params().unwrap().get(&"tag"), Some(Some("99sa0xk")));
     assert_eq!(
         to_hdr.value.sip_uri().unwrap().scheme,
         sipuri::RequestUriScheme::SIP
@@ -73,11 +73,11 @@ fn parse_headers() {
 
     let from_hdr = hdrs.get_rfc_s(SipRFCHeader::From).unwrap();
     assert_eq!(from_hdr.value.vstr, "caller <sip:caller2@example.com>");
-    assert_eq!(from_hdr.params().unwrap().get(&"tag"), Some(&Some("323")));
+    assert_eq!(from_hdr.params().unwrap().get(&"tag"), Some(Some("323")));
 
     assert_eq!(
         from_hdr.value.tags().unwrap()[&SipHeaderTagType::DisplayName],
-        b"caller"
+        "caller".as_bytes()
     );
     assert_eq!(
         from_hdr.value.sip_uri().unwrap().scheme,
@@ -108,37 +108,40 @@ fn parse_headers() {
     assert_eq!(hdrs.get_rfc_s(SipRFCHeader::CSeq).unwrap().params(), None);
     assert_eq!(
         cseq_header.value.tags().unwrap()[&SipHeaderTagType::Number],
-        b"60"
+        "60".as_bytes()
     );
     assert_eq!(
         cseq_header.value.tags().unwrap()[&SipHeaderTagType::Method],
-        b"OPTIONS"
+        "OPTIONS".as_bytes()
     );
 
-    assert_eq!(hdrs.get_ext_s("ExtensionHeader").unwrap().value.vstr, "value;param=false");
+    assert_eq!(
+        hdrs.get_ext_s("ExtensionHeader").unwrap().value.vstr,
+        "value;param=false"
+    );
 
     let via_hdr = hdrs.get_rfc_s(SipRFCHeader::Via).unwrap();
     assert_eq!(via_hdr.value.vstr, "SIP/2.0/UDP funky.example.com");
     assert_eq!(
         via_hdr.params().unwrap().get(&"branch"),
-        Some(&Some("z9hG4bKkdjuw"))
+        Some(Some("z9hG4bKkdjuw"))
     );
 
     assert_eq!(
         via_hdr.value.tags().unwrap()[&SipHeaderTagType::ProtocolName],
-        b"SIP"
+        "SIP".as_bytes()
     );
     assert_eq!(
         via_hdr.value.tags().unwrap()[&SipHeaderTagType::ProtocolVersion],
-        b"2.0"
+        "2.0".as_bytes()
     );
     assert_eq!(
         via_hdr.value.tags().unwrap()[&SipHeaderTagType::ProtocolTransport],
-        b"UDP"
+        "UDP".as_bytes()
     );
     assert_eq!(
         via_hdr.value.tags().unwrap()[&SipHeaderTagType::Host],
-        b"funky.example.com"
+        "funky.example.com".as_bytes()
     );
     let auth_val = &hdrs.get_rfc_s(SipRFCHeader::Authorization).unwrap().value;
     assert_eq!(
@@ -149,15 +152,15 @@ fn parse_headers() {
     );
     assert_eq!(
         auth_val.tags().unwrap()[&SipHeaderTagType::Username],
-        b"Alice"
+        "Alice".as_bytes()
     );
     assert_eq!(
         auth_val.tags().unwrap()[&SipHeaderTagType::Realm],
-        b"atlanta.com"
+        "atlanta.com".as_bytes()
     );
     assert_eq!(
         auth_val.tags().unwrap()[&SipHeaderTagType::Nonce],
-        b"84a4cc6f3082121f32b42a2187831a9e"
+        "84a4cc6f3082121f32b42a2187831a9e".as_bytes()
     );
     assert_eq!(
         auth_val.tags().unwrap()[&SipHeaderTagType::Dresponse],
@@ -169,11 +172,11 @@ fn parse_headers() {
     assert_eq!(content_disp_hdr.value.vstr, "attachment");
     assert_eq!(
         content_disp_hdr.params().unwrap().get("filename").unwrap(),
-        &Some("smime.p7s")
+        Some("smime.p7s")
     );
     assert_eq!(
         content_disp_hdr.params().unwrap().get("handling").unwrap(),
-        &Some("required")
+        Some("required")
     );
 
     let content_language = &hdrs.get_rfc_s(SipRFCHeader::ContentLanguage).unwrap();
@@ -189,7 +192,7 @@ fn parse_headers() {
     assert_eq!(content_type.value.vstr, "text/html");
     assert_eq!(
         content_type.params().unwrap().get("charset").unwrap(),
-        &Some("ISO-8859-4")
+        Some("ISO-8859-4")
     );
 
     let date_hdr = &hdrs.get_rfc_s(SipRFCHeader::Date).unwrap();
@@ -212,20 +215,20 @@ fn parse_headers() {
     assert_eq!(in_reply_hdrs[0].value.vstr, "70710@saturn.bell-tel.com");
     assert_eq!(
         in_reply_hdrs[0].value.tags().unwrap()[&SipHeaderTagType::ID],
-        b"70710"
+        "70710".as_bytes()
     );
     assert_eq!(
         in_reply_hdrs[0].value.tags().unwrap()[&SipHeaderTagType::Host],
-        b"saturn.bell-tel.com"
+        "saturn.bell-tel.com".as_bytes()
     );
     assert_eq!(in_reply_hdrs[1].value.vstr, "17320@saturn.bell-tel.com");
     assert_eq!(
         in_reply_hdrs[1].value.tags().unwrap()[&SipHeaderTagType::ID],
-        b"17320"
+        "17320".as_bytes()
     );
     assert_eq!(
         in_reply_hdrs[1].value.tags().unwrap()[&SipHeaderTagType::Host],
-        b"saturn.bell-tel.com"
+        "saturn.bell-tel.com".as_bytes()
     );
 
     let organization_header = &hdrs.get_rfc_s(SipRFCHeader::Organization).unwrap();
@@ -244,35 +247,35 @@ fn parse_headers() {
     );
     assert_eq!(
         proxy_auth.value.tags().unwrap()[&SipHeaderTagType::AuthSchema],
-        b"Digest"
+        "Digest".as_bytes()
     );
     assert_eq!(
         proxy_auth.value.tags().unwrap()[&SipHeaderTagType::Realm],
-        b"atlanta.com"
+        "atlanta.com".as_bytes()
     );
     assert_eq!(
         proxy_auth.value.tags().unwrap()[&SipHeaderTagType::Domain],
-        b"sip:ss1.carrier.com"
+        "sip:ss1.carrier.com".as_bytes()
     );
     assert_eq!(
         proxy_auth.value.tags().unwrap()[&SipHeaderTagType::QopValue],
-        b"auth"
+        "auth".as_bytes()
     );
     assert_eq!(
         proxy_auth.value.tags().unwrap()[&SipHeaderTagType::Nonce],
-        b"f84f1cec41e6cbe5aea9c8e88d359"
+        "f84f1cec41e6cbe5aea9c8e88d359".as_bytes()
     );
     assert_eq!(
         proxy_auth.value.tags().unwrap()[&SipHeaderTagType::Opaque],
-        b""
+        "".as_bytes()
     );
     assert_eq!(
         proxy_auth.value.tags().unwrap()[&SipHeaderTagType::Stale],
-        b"FALSE"
+        "FALSE".as_bytes()
     );
     assert_eq!(
         proxy_auth.value.tags().unwrap()[&SipHeaderTagType::Algorithm],
-        b"MD5"
+        "MD5".as_bytes()
     );
 
     let proxy_auth = &hdrs.get_rfc_s(SipRFCHeader::ProxyAuthorization).unwrap();
@@ -284,24 +287,24 @@ fn parse_headers() {
     );
     assert_eq!(
         proxy_auth.value.tags().unwrap()[&SipHeaderTagType::AuthSchema],
-        b"Digest"
+        "Digest".as_bytes()
     );
     assert_eq!(
         proxy_auth.value.tags().unwrap()[&SipHeaderTagType::Username],
-        b"Alice"
+        "Alice".as_bytes()
     );
     assert_eq!(
         proxy_auth.value.tags().unwrap()[&SipHeaderTagType::Nonce],
-        b"c60f3082ee1212b402a21831ae"
+        "c60f3082ee1212b402a21831ae".as_bytes()
     );
     assert_eq!(
         proxy_auth.value.tags().unwrap()[&SipHeaderTagType::Dresponse],
-        b"245f23415f11432b3434341c022"
+        "245f23415f11432b3434341c022".as_bytes()
     );
 
     let proxy_require_hdr = &hdrs.get_rfc_s(SipRFCHeader::ProxyRequire).unwrap();
     assert_eq!(proxy_require_hdr.value.vstr, "foo");
-    assert_eq!(proxy_require_hdr.params().unwrap().get("boo"), Some(&None));
+    assert_eq!(proxy_require_hdr.params().unwrap().get("boo"), Some(None));
 
     let record_route_headers = &hdrs.get_rfc(SipRFCHeader::RecordRoute).unwrap();
 
@@ -330,7 +333,7 @@ fn parse_headers() {
             .params()
             .unwrap()
             .get("lr"),
-        Some(&None)
+        Some(None)
     );
     assert_eq!(
         record_route_headers[1].value.vstr,
@@ -357,7 +360,7 @@ fn parse_headers() {
             .params()
             .unwrap()
             .get("lr"),
-        Some(&None)
+        Some(None)
     );
 
     let route_headers = &hdrs.get_rfc(SipRFCHeader::Route).unwrap();
@@ -406,7 +409,7 @@ fn parse_headers() {
     assert_eq!(reply_to_header.value.vstr, "Bob <sip:bob@biloxi.com>");
     assert_eq!(
         reply_to_header.value.tags().unwrap()[&SipHeaderTagType::DisplayName],
-        b"Bob"
+        "Bob".as_bytes()
     );
     assert_eq!(
         reply_to_header.value.sip_uri().unwrap().scheme,
@@ -443,7 +446,7 @@ fn parse_headers() {
 
     assert_eq!(
         retry_after_hdr.params().unwrap().get(&"duration"),
-        Some(&Some("3600"))
+        Some(Some("3600"))
     );
 
     let server_hdr = &hdrs.get_rfc_s(SipRFCHeader::Server).unwrap();
@@ -472,11 +475,11 @@ fn parse_headers() {
 
     assert_eq!(
         supported_hdr.value.tags().unwrap()[&SipHeaderTagType::Major],
-        b"1"
+        "1".as_bytes()
     );
     assert_eq!(
         supported_hdr.value.tags().unwrap()[&SipHeaderTagType::Minor],
-        b"0"
+        "0".as_bytes()
     );
 
     let min_exp_hdr = &hdrs.get_rfc_s(SipRFCHeader::MinExpires).unwrap();
@@ -486,7 +489,7 @@ fn parse_headers() {
     assert_eq!(timestamp_hdr.value.vstr, "54");
     assert_eq!(
         timestamp_hdr.value.tags().unwrap()[&SipHeaderTagType::TimveVal],
-        b"54"
+        "54".as_bytes()
     );
 
     let warn_hdr = &hdrs.get_rfc_s(SipRFCHeader::Warning).unwrap();
@@ -501,11 +504,11 @@ fn parse_headers() {
     );
     assert_eq!(
         warn_hdr.value.tags().unwrap()[&SipHeaderTagType::WarnCode],
-        b"301"
+        "301".as_bytes()
     );
     assert_eq!(
         warn_hdr.value.tags().unwrap()[&SipHeaderTagType::WarnAgent],
-        b"isi.edu"
+        "isi.edu".as_bytes()
     );
     assert_eq!(
         warn_hdr.value.tags().unwrap()[&SipHeaderTagType::WarnText],
@@ -522,36 +525,259 @@ fn parse_headers() {
     );
     assert_eq!(
         www_auth.value.tags().unwrap()[&SipHeaderTagType::AuthSchema],
-        b"Digest"
+        "Digest".as_bytes()
     );
     assert_eq!(
         www_auth.value.tags().unwrap()[&SipHeaderTagType::Realm],
-        b"atlanta.com"
+        "atlanta.com".as_bytes()
     );
     assert_eq!(
         www_auth.value.tags().unwrap()[&SipHeaderTagType::Domain],
-        b"sip:boxesbybob.com"
+        "sip:boxesbybob.com".as_bytes()
     );
     assert_eq!(
         www_auth.value.tags().unwrap()[&SipHeaderTagType::QopValue],
-        b"auth"
+        "auth".as_bytes()
     );
     assert_eq!(
         www_auth.value.tags().unwrap()[&SipHeaderTagType::Nonce],
-        b"f84f1cec41e6cbe5aea9c8e88d359"
+        "f84f1cec41e6cbe5aea9c8e88d359".as_bytes()
     );
     assert_eq!(
         www_auth.value.tags().unwrap()[&SipHeaderTagType::Opaque],
-        b""
+        "".as_bytes()
     );
     assert_eq!(
         www_auth.value.tags().unwrap()[&SipHeaderTagType::Stale],
-        b"FALSE"
+        "FALSE".as_bytes()
     );
     assert_eq!(
         www_auth.value.tags().unwrap()[&SipHeaderTagType::Algorithm],
-        b"MD5"
+        "MD5".as_bytes()
     );
 
     assert_eq!(input, "\r\nsomebody".as_bytes());
 }
+
+#[test]
+fn edit_headers() {
+    let (_, mut hdrs) = SipHeaders::parse(
+        "Via: SIP/2.0/UDP 10.0.0.2;branch=z9hG4bK2\r\n\
+         Route: <sip:p1.example.com;lr>, <sip:p2.example.com;lr>\r\n\
+         Max-Forwards: 70\r\n\
+         X-Header: first\r\n\r\n"
+            .as_bytes(),
+    )
+    .unwrap();
+
+    // push Via on top
+    hdrs.prepend(
+        SipHeader::from_name_value("Via", "SIP/2.0/UDP 10.0.0.1;branch=z9hG4bK1").unwrap(),
+    );
+    let vias = hdrs.get_rfc(SipRFCHeader::Via).unwrap();
+    assert_eq!(vias.len(), 2);
+    assert_eq!(vias[0].value.vstr, "SIP/2.0/UDP 10.0.0.1");
+    assert_eq!(
+        vias[0].params().unwrap().get("branch"),
+        Some(Some("z9hG4bK1"))
+    );
+    assert_eq!(vias[1].value.vstr, "SIP/2.0/UDP 10.0.0.2");
+
+    // pop topmost Route
+    let route = hdrs.pop_rfc(SipRFCHeader::Route).unwrap();
+    assert_eq!(route.value.vstr, "<sip:p1.example.com;lr>");
+    assert_eq!(hdrs.get_rfc(SipRFCHeader::Route).unwrap().len(), 1);
+    hdrs.pop_rfc(SipRFCHeader::Route).unwrap();
+    assert!(hdrs.get_rfc(SipRFCHeader::Route).is_none());
+    assert!(hdrs.pop_rfc(SipRFCHeader::Route).is_none());
+
+    // rewrite Max-Forwards
+    let removed = hdrs
+        .replace(SipHeader::from_name_value("Max-Forwards", "69").unwrap())
+        .unwrap();
+    assert_eq!(removed[0].value.vstr, "70");
+    assert_eq!(
+        hdrs.get_rfc_s(SipRFCHeader::MaxForwards)
+            .unwrap()
+            .value
+            .vstr,
+        "69"
+    );
+
+    // extension headers
+    hdrs.insert(SipHeader::from_name_value("x-header", "second").unwrap());
+    hdrs.prepend(SipHeader::from_name_value("X-Other", "other").unwrap());
+    let ext_hdrs = hdrs.get_ext("X-HEADER").unwrap();
+    assert_eq!(ext_hdrs.len(), 2);
+    assert_eq!(ext_hdrs[1].value.vstr, "second");
+    assert_eq!(hdrs.pop_ext("x-header").unwrap().value.vstr, "first");
+    assert_eq!(hdrs.remove_ext("X-Header").unwrap().len(), 1);
+    assert!(hdrs.get_ext("X-Header").is_none());
+    assert_eq!(hdrs.get_ext_s("x-other").unwrap().value.vstr, "other");
+
+    // short header name is stored as RFC header
+    hdrs.insert(SipHeader::from_name_value("l", "0").unwrap());
+    assert!(hdrs.remove_rfc(SipRFCHeader::ContentLength).is_some());
+
+    assert!(SipHeader::from_name_value("Allow", "INVITE, ACK").is_err());
+    assert!(SipHeader::from_name_value("Max-Forwards", "seventy").is_err());
+}
+
+#[test]
+fn headers_edit_in_place() {
+    let (_, mut hdrs) = SipHeaders::parse(
+        "v: SIP/2.0/UDP 10.0.0.1;branch=z9hG4bK1\r\n\
+         Max-Forwards: 70\r\n\
+         X-Header: a\r\n\r\n"
+            .as_bytes(),
+    )
+    .unwrap();
+
+    let mut max_forwards = hdrs.top_rfc_mut(SipRFCHeader::MaxForwards).unwrap();
+    max_forwards.set_value("69").unwrap();
+    assert!(max_forwards.set_value("seventy").is_err());
+    assert_eq!(max_forwards.value.vstr, "69");
+
+    let mut via = hdrs.top_rfc_mut(SipRFCHeader::Via).unwrap();
+    via.set_value("SIP/2.0/TCP 10.0.0.2;branch=z9hG4bK2")
+        .unwrap();
+    assert_eq!(via.name, "v");
+    assert_eq!(via.params().unwrap().get("branch"), Some(Some("z9hG4bK2")));
+
+    let mut via = hdrs.top_rfc_mut(SipRFCHeader::Via).unwrap();
+    assert!(via.remove_param("branch"));
+    via.set_param("rport", None).unwrap();
+    assert_eq!(via.raw_value_param.as_ref(), b"SIP/2.0/TCP 10.0.0.2;rport");
+
+    let mut ext = hdrs.top_ext_mut("x-header").unwrap();
+    ext.set_value("b").unwrap();
+    assert_eq!(ext.name, "X-Header");
+
+    assert_eq!(
+        hdrs.get_rfc_s(SipRFCHeader::MaxForwards)
+            .unwrap()
+            .value
+            .vstr,
+        "69"
+    );
+    assert_eq!(
+        hdrs.get_rfc_s(SipRFCHeader::Via).unwrap().value.vstr,
+        "SIP/2.0/TCP 10.0.0.2"
+    );
+    assert_eq!(hdrs.get_ext_s("X-Header").unwrap().value.vstr, "b");
+    assert!(hdrs.top_ext_mut("X-Other").is_none());
+}
+
+#[test]
+fn headers_wire_order() {
+    let (_, mut hdrs) = SipHeaders::parse(
+        "X-First: 1\r\n\
+         Route: <sip:p1.example.com;lr>\r\n\
+         Max-Forwards: 70\r\n\
+         x-first: 2\r\n\
+         Route: <sip:p2.example.com;lr>\r\n\
+         CSeq: 1 INVITE\r\n\r\n"
+            .as_bytes(),
+    )
+    .unwrap();
+
+    let wire_order = |hdrs: &SipHeaders| {
+        hdrs.iter()
+            .map(|h| h.value.vstr.to_string())
+            .collect::<Vec<String>>()
+    };
+    assert_eq!(
+        wire_order(&hdrs),
+        [
+            "1",
+            "<sip:p1.example.com;lr>",
+            "70",
+            "2",
+            "<sip:p2.example.com;lr>",
+            "1 INVITE"
+        ]
+    );
+    let routes: Vec<&str> = hdrs
+        .get_rfc(SipRFCHeader::Route)
+        .unwrap()
+        .iter()
+        .map(|h| h.value.vstr.as_ref())
+        .collect();
+    assert_eq!(
+        routes,
+        ["<sip:p1.example.com;lr>", "<sip:p2.example.com;lr>"]
+    );
+    assert_eq!(hdrs.get_ext("X-FIRST").unwrap().len(), 2);
+
+    hdrs.replace(SipHeader::from_name_value("Max-Forwards", "69").unwrap());
+    hdrs.prepend(SipHeader::from_name_value("Route", "<sip:p0.example.com;lr>").unwrap());
+    hdrs.insert(SipHeader::from_name_value("X-First", "3").unwrap());
+    hdrs.pop_ext("x-first");
+    assert_eq!(
+        wire_order(&hdrs),
+        [
+            "<sip:p0.example.com;lr>",
+            "<sip:p1.example.com;lr>",
+            "69",
+            "2",
+            "3",
+            "<sip:p2.example.com;lr>",
+            "1 INVITE"
+        ]
+    );
+    assert_eq!(
+        hdrs.get_rfc(SipRFCHeader::Route).unwrap()[2].value.vstr,
+        "<sip:p2.example.com;lr>"
+    );
+    assert_eq!(hdrs.get_ext("x-first").unwrap()[1].value.vstr, "3");
+}
+
+#[test]
+fn typed_headers() {
+    let (_, hdrs) = SipHeaders::parse(
+        "Via: SIP/2.0/UDP pc33.atlanta.com;branch=z9hG4bK776asdhds;received=192.0.2.1\r\n\
+         Via: SIP/2.0/TCP 10.0.0.1:5070;branch=z9hG4bK1\r\n\
+         Route: <sip:p1.example.com;lr>, <sip:p2.example.com;lr>\r\n\
+         From: Alice <sip:alice@atlanta.com>;tag=1928301774\r\n\
+         To: \"Bob\" <sip:bob@biloxi.com>\r\n\
+         Max-Forwards: 70\r\n\
+         Expires: 7200\r\n\
+         Content-Type: application/sdp\r\n\r\n"
+            .as_bytes(),
+    )
+    .unwrap();
+
+    let vias: Vec<ViaHeader> = hdrs.get_typed_all(SipRFCHeader::Via).unwrap().unwrap();
+    assert_eq!(vias.len(), 2);
+    assert_eq!(vias[0].received, Some("192.0.2.1".parse().unwrap()));
+    assert_eq!(vias[1].transport, SipTransport::Tcp);
+    assert_eq!(vias[1].sent_by.port, Some(5070));
+
+    let routes: Vec<NameAddrHeader> = hdrs.get_typed_all(SipRFCHeader::Route).unwrap().unwrap();
+    assert_eq!(routes[1].uri.sip().unwrap().hostport.host, "p2.example.com");
+    assert!(routes[1].uri.sip().unwrap().lr());
+
+    let from: NameAddrHeader = hdrs.get_typed(SipRFCHeader::From).unwrap().unwrap();
+    assert_eq!(from.display_name.as_deref(), Some("Alice"));
+    assert_eq!(from.tag.as_deref(), Some("1928301774"));
+    let to = hdrs
+        .get_rfc_s(SipRFCHeader::To)
+        .unwrap()
+        .typed::<NameAddrHeader>()
+        .unwrap();
+    assert_eq!(to.display_name.as_deref(), Some("Bob"));
+    assert_eq!(to.tag, None);
+
+    let max_forwards: MaxForwards = hdrs.get_typed(SipRFCHeader::MaxForwards).unwrap().unwrap();
+    assert_eq!(max_forwards, MaxForwards(70));
+    let expires: Expires = hdrs.get_typed(SipRFCHeader::Expires).unwrap().unwrap();
+    assert_eq!(expires, Expires(7200));
+    let content_type: ContentType = hdrs.get_typed(SipRFCHeader::ContentType).unwrap().unwrap();
+    assert!(content_type.is("application", "sdp"));
+
+    assert!(hdrs.get_typed::<CSeqHeader>(SipRFCHeader::CSeq).is_none());
+    assert!(hdrs
+        .get_typed::<CSeqHeader>(SipRFCHeader::Expires)
+        .unwrap()
+        .is_err());
+}
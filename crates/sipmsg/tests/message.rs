@@ -18,13 +18,16 @@ Content-Type: application/sdp\r\n\
 Content-Length: 0\r\n\r\n".as_bytes();
     let (_, sip_msg) = SipMessage::parse(invite_msg_buf).unwrap();
     let sip_req = sip_msg.request().unwrap();
-    assert_eq!(sip_req.rl.uri.user_info().unwrap().value, "001234567890");
+    assert_eq!(
+        sip_req.rl.uri.sip().unwrap().user_info().unwrap().value,
+        "001234567890"
+    );
 }
 
 #[test]
 fn get_message_type() {
     assert_eq!(
-        sipmsg::get_sip_message_type("SIP".as_bytes()),
+        sipmsg::get_sip_message_type("SIP/2.0 200 OK".as_bytes()),
         SipMessageType::Response
     );
     assert_eq!(
@@ -43,6 +46,493 @@ fn get_message_type() {
     );
     assert_eq!(
         sipmsg::get_sip_message_type("NEWMETHOD sip:user@example.com SIP/2.0".as_bytes()),
+        SipMessageType::Request
+    );
+    assert_eq!(
+        sipmsg::get_sip_message_type("SIP".as_bytes()),
+        SipMessageType::Unknown
+    );
+    assert_eq!(
+        sipmsg::get_sip_message_type("NEW(METHOD sip:user@example.com SIP/2.0".as_bytes()),
+        SipMessageType::Unknown
+    );
+    assert_eq!(
+        sipmsg::get_sip_message_type("INVITE sip:user@example.com\r\n SIP/2.0".as_bytes()),
+        SipMessageType::Unknown
+    );
+    assert_eq!(
+        sipmsg::get_sip_message_type("INVITE sip:user@example.com HTTP/1.1".as_bytes()),
         SipMessageType::Unknown
     );
 }
+
+#[test]
+fn extension_method_round_trip() {
+    let buf = "PING sip:user@example.com SIP/2.0\r\n\
+Via: SIP/2.0/UDP 10.135.0.12:5060;branch=z9hG4bKhye0bem20x.nx8hnt\r\n\
+Call-ID: ud04chatv9q@10.135.0.1\r\n\
+CSeq: 7 PING\r\n\
+Content-Length: 0\r\n\r\n"
+        .as_bytes();
+    let (_, msg) = SipMessage::parse(buf).unwrap();
+    let request = msg.request().unwrap();
+    assert_eq!(request.rl.method, SipMethod::extension("PING").unwrap());
+    assert!(matches!(request.rl.method, SipMethod::Extension(_)));
+    assert_eq!(request.rl.method.as_str(), "PING");
+
+    let cseq = request.headers.get_rfc_s(SipRFCHeader::CSeq).unwrap();
+    let cseq_method =
+        std::str::from_utf8(&cseq.value.tags().unwrap()[&SipHeaderTagType::Method]).unwrap();
+    assert_eq!(SipMethod::from_str(cseq_method).unwrap(), request.rl.method);
+
+    let mut serializer = SipMsgSerializer::new(Vec::new());
+    serializer.serialize_msg(&msg).unwrap();
+    assert_eq!(serializer.output().as_slice(), buf);
+
+    let (_, uri) = SipUri::parse(b"sip:user@example.com").unwrap();
+    let built = SipRequestBuilder::new(SipMethod::extension("PING").unwrap(), uri)
+        .header(SipRFCHeader::CSeq, "8 PING")
+        .build()
+        .unwrap();
+    let (_, msg) = SipMessage::parse(&built).unwrap();
+    let request = msg.request().unwrap();
+    assert_eq!(request.rl.method.as_str(), "PING");
+    assert_eq!(
+        request
+            .headers
+            .get_rfc_s(SipRFCHeader::CSeq)
+            .unwrap()
+            .value
+            .vstr,
+        "8 PING"
+    );
+
+    assert_eq!(SipMethod::from_str("INVITE"), Some(SipMethod::INVITE));
+    match SipMethod::from_str("invite") {
+        Some(SipMethod::Extension(method)) => assert_eq!(method.as_str(), "invite"),
+        _ => panic!(),
+    }
+    assert_eq!(SipMethod::extension("INVITE"), Some(SipMethod::INVITE));
+    assert_ne!(SipMethod::from_str("Bye"), Some(SipMethod::BYE));
+    assert_eq!(SipMethod::extension("PI NG"), None);
+    assert_eq!(SipMethod::from_str(""), None);
+}
+
+#[test]
+fn parse_messages_by_content_length() {
+    let buf = "MESSAGE sip:kumiko@example.org SIP/2.0\r\n\
+Via: SIP/2.0/UDP 10.135.0.12:5060;branch=z9hG4bKhye0bem20x.nx8hnt\r\n\
+Call-ID: ud04chatv9q@10.135.0.1\r\n\
+CSeq: 1 MESSAGE\r\n\
+Content-Length: 5\r\n\r\n\
+HelloSIP/2.0 200 OK\r\n\
+Via: SIP/2.0/UDP 10.135.0.12:5060;branch=z9hG4bKhye0bem20x.nx8hnt\r\n\
+Call-ID: ud04chatv9q@10.135.0.1\r\n\
+CSeq: 1 MESSAGE\r\n\
+l: 0\r\n\r\n\
+OPTIONS"
+        .as_bytes();
+
+    let (rest, sip_msg) = SipMessage::parse(buf).unwrap();
+    assert_eq!(
+        sip_msg.request().unwrap().body.as_deref().unwrap(),
+        "Hello".as_bytes()
+    );
+
+    let (rest, sip_msg) = SipMessage::parse(rest).unwrap();
+    let response = sip_msg.response().unwrap();
+    assert_eq!(response.sl.status_code, SipResponseStatusCode::OK);
+    assert_eq!(response.body.as_deref().unwrap(), "".as_bytes());
+    assert_eq!(rest, b"OPTIONS");
+}
+
+#[test]
+fn parse_message_without_content_length() {
+    let buf = "SIP/2.0 200 OK\r\n\
+Call-ID: ud04chatv9q@10.135.0.1\r\n\
+CSeq: 1 MESSAGE\r\n\r\nbody"
+        .as_bytes();
+    let (rest, sip_msg) = SipMessage::parse(buf).unwrap();
+    assert_eq!(
+        sip_msg.response().unwrap().body.as_deref().unwrap(),
+        "body".as_bytes()
+    );
+    assert_eq!(rest.len(), 0);
+}
+
+#[test]
+fn parse_message_content_length_exceeds_body() {
+    let buf = "SIP/2.0 200 OK\r\n\
+Call-ID: ud04chatv9q@10.135.0.1\r\n\
+CSeq: 1 MESSAGE\r\n\
+Content-Length: 10\r\n\r\nbody"
+        .as_bytes();
+    match SipMessage::parse(buf) {
+        Err(nom::Err::Error(e)) => {
+            assert_eq!(
+                e.kind,
+                SipParseErrorKind::BodyTruncated {
+                    declared: 10,
+                    available: 4
+                }
+            );
+            assert_eq!(e.offset, Some(buf.len()));
+        }
+        _ => panic!(),
+    }
+}
+
+#[test]
+fn parse_message_signed_content_length() {
+    for value in ["+4", "-4", "0x4", "4 4"].iter() {
+        let buf = format!(
+            "SIP/2.0 200 OK\r\nCSeq: 1 MESSAGE\r\nContent-Length: {}\r\n\r\nbody",
+            value
+        );
+        match SipMessage::parse(buf.as_bytes()) {
+            Err(nom::Err::Error(e)) => {
+                assert_eq!(e.kind, SipParseErrorKind::InvalidHeaderValue);
+                assert_eq!(e.header, Some("Content-Length"));
+            }
+            _ => panic!("{} is accepted", value),
+        }
+    }
+}
+
+#[test]
+fn message_into_owned() {
+    let owned_msg = {
+        let buf = "INVITE sip:bob@biloxi.com;user=phone?to=alice%40atlanta.com SIP/2.0\r\n\
+Via: SIP/2.0/UDP pc33.atlanta.com;branch=z9hG4bKkjshdyff\r\n\
+From: Alice <sip:alice@atlanta.com>;tag=88sja8x\r\n\
+Call-ID: f81d4fae-7dec-11d0-a765-00a0c91e6bf6@foo.bar.com\r\n\
+Extention-Header: extention header value\r\n\
+CSeq: 986759 INVITE\r\n\
+Content-Length: 4\r\n\r\nbody"
+            .to_string();
+        let (_, sip_msg) = SipMessage::parse(buf.as_bytes()).unwrap();
+        sip_msg.into_owned()
+    };
+
+    let handle = std::thread::spawn(move || {
+        let request = owned_msg.request().unwrap();
+        assert_eq!(request.rl.method, SipMethod::INVITE);
+        assert_eq!(
+            request.rl.uri.sip().unwrap().user_info().unwrap().value,
+            "bob"
+        );
+        assert_eq!(request.rl.uri.sip().unwrap().hostport.host, "biloxi.com");
+        assert_eq!(
+            request.rl.uri.sip().unwrap().params().unwrap().get("user"),
+            Some(Some("phone"))
+        );
+        assert_eq!(
+            request.rl.uri.sip().unwrap().headers().unwrap()["to"],
+            "alice%40atlanta.com"
+        );
+
+        let from = request.headers.get_rfc_s(SipRFCHeader::From).unwrap();
+        assert_eq!(from.value.vstr, "Alice <sip:alice@atlanta.com>");
+        assert_eq!(
+            from.value.tags().unwrap()[&SipHeaderTagType::DisplayName],
+            "Alice".as_bytes()
+        );
+        assert_eq!(from.params().unwrap().get("tag"), Some(Some("88sja8x")));
+
+        let ext = request.headers.get_ext_s("extention-header").unwrap();
+        assert_eq!(ext.value.vstr, "extention header value");
+        assert_eq!(request.body.as_deref().unwrap(), b"body");
+    });
+    handle.join().unwrap();
+}
+
+#[test]
+fn parse_error_position_and_header() {
+    let buf = "INVITE sip:bob@biloxi.com SIP/2.0\r\n\
+Via: SIP/2.0/UDP pc33.atlanta.com;branch=z9hG4bKkjshdyff\r\n\
+CSeq: abc INVITE\r\n\
+Content-Length: 0\r\n\r\n"
+        .as_bytes();
+    let err = match SipMessage::parse(buf) {
+        Err(nom::Err::Error(e)) => e,
+        _ => panic!(),
+    };
+    assert_eq!(err.kind, SipParseErrorKind::InvalidHeaderValue);
+    assert_eq!(err.header, Some("CSeq"));
+    assert_eq!(err.line, Some(3));
+    assert_eq!(err.offset, Some(99));
+    assert_eq!(
+        err.to_string(),
+        "Invalid header value in CSeq header at line 3 (offset 99)"
+    );
+}
+
+#[test]
+fn parse_error_of_large_sip_version() {
+    let err = match SipMessage::parse(b"SIP/300.0 200 OK\r\n\r\n") {
+        Err(nom::Err::Error(e)) => e,
+        _ => panic!(),
+    };
+    assert_eq!(err.kind, SipParseErrorKind::InvalidStatusLine);
+    assert_eq!(err.offset, Some(4));
+
+    let err = match SipMessage::parse(b"INVITE sip:bob@biloxi.com SIP/2.256\r\n\r\n") {
+        Err(nom::Err::Error(e)) => e,
+        _ => panic!(),
+    };
+    assert_eq!(err.kind, SipParseErrorKind::InvalidRequestLine);
+    assert_eq!(err.offset, Some(32));
+}
+
+#[test]
+fn parse_error_of_start_line() {
+    let err = match SipMessage::parse(b"SIP/2.0 abc OK\r\n\r\n") {
+        Err(nom::Err::Error(e)) => e,
+        _ => panic!(),
+    };
+    assert_eq!(err.kind, SipParseErrorKind::InvalidStatusCode);
+    assert_eq!(err.header, None);
+    assert_eq!(err.line, Some(1));
+    assert_eq!(err.offset, Some(8));
+
+    let err = match SipMessage::parse(b"INVITE sip:bob@biloxi.com SIP/2.0\r\nBad Header: 1\r\n\r\n")
+    {
+        Err(nom::Err::Error(e)) => e,
+        _ => panic!(),
+    };
+    assert_eq!(err.kind, SipParseErrorKind::InvalidHeaderName);
+    assert_eq!(err.line, Some(2));
+    assert_eq!(err.offset, Some(39));
+
+    let err = match SipMessage::parse(b"\x01 garbage\r\n\r\n") {
+        Err(nom::Err::Error(e)) => e,
+        _ => panic!(),
+    };
+    assert_eq!(err.kind, SipParseErrorKind::UnknownMessageType);
+    assert_eq!(
+        err.to_string(),
+        "Unknown message type at line 1 (offset 0): Can't predict type of message"
+    );
+}
+
+#[test]
+fn parse_lenient_message() {
+    let buf = b"INVITE  sip:bob@biloxi.com\tSIP/2.0\n\
+Via: SIP/2.0/UDP pc33.atlanta.com;branch=z9hG4bKkjshdyff \r\n\
+From: \"Al\xe9x\" <sip:alice@atlanta.com>;tag=88sja8x\n\
+To: Bob <sip:bob@biloxi.com>\r\n\
+CSeq: 1 INVITE\r\n\
+Content-Length: 5\n\
+\nbody\n";
+
+    assert!(SipMessage::parse(buf).is_err());
+    assert!(SipMessage::parse_with_config(buf, &SipParserConfig::strict()).is_err());
+
+    let (rest, (msg, deviations)) =
+        SipMessage::parse_with_config(buf, &SipParserConfig::lenient()).unwrap();
+    assert_eq!(rest.len(), 0);
+    let request = msg.request().unwrap();
+    assert_eq!(request.rl.method, SipMethod::INVITE);
+    assert_eq!(request.rl.uri.sip().unwrap().hostport.host, "biloxi.com");
+    assert_eq!(request.body.as_deref().unwrap(), b"body\n");
+    let from = request.headers.get_rfc_s(SipRFCHeader::From).unwrap();
+    assert_eq!(
+        from.value.tags().unwrap()[&SipHeaderTagType::DisplayName],
+        "Al\u{FFFD}x".as_bytes()
+    );
+
+    let kinds: Vec<(SipParseDeviationKind, usize)> =
+        deviations.iter().map(|d| (d.kind, d.line)).collect();
+    assert_eq!(
+        kinds,
+        vec![
+            (SipParseDeviationKind::StartLineWhitespace, 1),
+            (SipParseDeviationKind::BareLf, 1),
+            (SipParseDeviationKind::TrailingWhitespace, 2),
+            (SipParseDeviationKind::BareLf, 3),
+            (SipParseDeviationKind::NonUtf8, 3),
+            (SipParseDeviationKind::BareLf, 6),
+            (SipParseDeviationKind::BareLf, 7),
+        ]
+    );
+}
+
+#[test]
+fn parse_strict_rejects_tolerated_deviations() {
+    let cases: Vec<(&[u8], SipParseErrorKind, usize, SipParseDeviationKind)> = vec![
+        (
+            b"INVITE sip:bob@biloxi.com SIP/2.0\nCall-ID: a@b\r\n\r\n",
+            SipParseErrorKind::InvalidLineEnding,
+            33,
+            SipParseDeviationKind::BareLf,
+        ),
+        (
+            b"INVITE sip:bob@biloxi.com SIP/2.0\r\nCall-ID: a@b\n\r\n",
+            SipParseErrorKind::InvalidLineEnding,
+            47,
+            SipParseDeviationKind::BareLf,
+        ),
+        (
+            b"INVITE sip:bob@biloxi.com SIP/2.0\r\nCall-ID: a@b\r\n\n",
+            SipParseErrorKind::InvalidLineEnding,
+            49,
+            SipParseDeviationKind::BareLf,
+        ),
+        (
+            b"INVITE sip:bob@biloxi.comSIP/2.0\r\nCall-ID: a@b\r\n\r\n",
+            SipParseErrorKind::InvalidRequestLine,
+            25,
+            SipParseDeviationKind::StartLineWhitespace,
+        ),
+        (
+            b"SIP/2.0 200OK\r\nCall-ID: a@b\r\n\r\n",
+            SipParseErrorKind::InvalidStatusLine,
+            11,
+            SipParseDeviationKind::StartLineWhitespace,
+        ),
+        (
+            b"SIP/2.0 200 OK\r\nFrom: \"Al\xe9x\" <sip:alice@atlanta.com>\r\n\r\n",
+            SipParseErrorKind::InvalidUtf8,
+            22,
+            SipParseDeviationKind::NonUtf8,
+        ),
+    ];
+    for (buf, error_kind, offset, deviation_kind) in cases {
+        let err = match SipMessage::parse_with_config(buf, &SipParserConfig::strict()) {
+            Err(nom::Err::Error(e)) => e,
+            _ => panic!(),
+        };
+        assert_eq!(err.kind, error_kind);
+        assert_eq!(err.offset, Some(offset));
+
+        let (rest, (_, deviations)) =
+            SipMessage::parse_with_config(buf, &SipParserConfig::lenient()).unwrap();
+        assert!(rest.is_empty());
+        assert_eq!(deviations.len(), 1);
+        assert_eq!(deviations[0].kind, deviation_kind);
+    }
+}
+
+#[test]
+fn parse_header_with_config() {
+    let line = b"To: \"B\xf6b\" <sip:bob@biloxi.com>;tag=1\r\n";
+    assert!(SipHeader::parse(line).is_err());
+    let (rest, (_, headers)) =
+        SipHeader::parse_with_config(line, &SipParserConfig::lenient()).unwrap();
+    assert_eq!(rest, b"\r\n");
+    assert_eq!(headers[0].value.vstr, "\"B\u{FFFD}b\" <sip:bob@biloxi.com>");
+    assert_eq!(
+        headers[0].raw_value_param.as_ref(),
+        &line[4..line.len() - 2]
+    );
+}
+
+#[test]
+fn parse_lenient_valid_message_is_borrowed() {
+    let buf = b"SIP/2.0 200 OK\r\nCSeq: 1 INVITE\r\n\r\nrest";
+    let (rest, (msg, deviations)) =
+        SipMessage::parse_with_config(buf, &SipParserConfig::lenient()).unwrap();
+    assert_eq!(rest, b"");
+    assert!(deviations.is_empty());
+    let response = msg.response().unwrap();
+    assert_eq!(response.body.as_deref().unwrap(), b"rest");
+    assert!(matches!(
+        response.sl.reason_phrase,
+        std::borrow::Cow::Borrowed(_)
+    ));
+}
+
+#[test]
+fn parse_lenient_error_points_to_original_input() {
+    let buf = b"OPTIONS sip:bob@biloxi.com  SIP/2.0\n\
+Via: SIP/2.0/UDP pc33.atlanta.com\n\
+CSeq: abc OPTIONS\n\n";
+    let err = match SipMessage::parse_with_config(buf, &SipParserConfig::lenient()) {
+        Err(nom::Err::Error(e)) => e,
+        _ => panic!(),
+    };
+    assert_eq!(err.kind, SipParseErrorKind::InvalidHeaderValue);
+    assert_eq!(err.header, Some("CSeq"));
+    assert_eq!(err.line, Some(3));
+    assert_eq!(err.offset, Some(76));
+}
+
+#[test]
+fn via_received_rport_round_trip() {
+    let buf = "INVITE sip:bob@biloxi.com SIP/2.0\r\n\
+        Via: SIP/2.0/UDP pc33.atlanta.com ;branch=z9hG4bK776asdhds ;rport;x=\"a\\\"b\"\r\n\
+        Via: SIP/2.0/UDP 10.0.0.1;branch=z9hG4bK1\r\n\
+        Max-Forwards: 70\r\n\
+        Content-Length: 0\r\n\r\n"
+        .as_bytes();
+    let (_, mut msg) = SipMessage::parse(buf).unwrap();
+    let request = match &mut msg {
+        SipMessage::Request(request) => request,
+        _ => panic!(),
+    };
+    let mut via = request.headers.top_rfc_mut(SipRFCHeader::Via).unwrap();
+    via.set_param("received", Some("192.0.2.1")).unwrap();
+    via.set_param("rport", Some("5062")).unwrap();
+    assert!(via.remove_param("x"));
+    assert_eq!(via.params().unwrap().get("rport"), Some(Some("5062")));
+
+    let mut serializer = SipMsgSerializer::new(Vec::new());
+    serializer.serialize_msg(&msg).unwrap();
+    assert_eq!(
+        std::str::from_utf8(serializer.output()).unwrap(),
+        "INVITE sip:bob@biloxi.com SIP/2.0\r\n\
+        Via: SIP/2.0/UDP pc33.atlanta.com ;branch=z9hG4bK776asdhds ;rport=5062;received=192.0.2.1\r\n\
+        Via: SIP/2.0/UDP 10.0.0.1;branch=z9hG4bK1\r\n\
+        Max-Forwards: 70\r\n\
+        Content-Length: 0\r\n\r\n"
+    );
+}
+
+#[test]
+fn several_challenges_round_trip() {
+    let buf = "SIP/2.0 401 Unauthorized\r\n\
+        WWW-Authenticate: Digest realm=\"atlanta.com\", nonce=\"84a4cc6f\", algorithm=SHA-256,\r\n \
+        Digest realm=\"atlanta.com\", nonce=\"84a4cc6f\", algorithm=MD5\r\n\
+        Proxy-Authenticate: Bearer realm=\"atlanta.com\", scope=\"sip\"\r\n\
+        Content-Length: 0\r\n\r\n"
+        .as_bytes();
+    let (_, msg) = SipMessage::parse(buf).unwrap();
+    let response = match &msg {
+        SipMessage::Response(response) => response,
+        _ => panic!(),
+    };
+    let challenges: Vec<Challenge> = response
+        .headers
+        .get_typed_all(SipRFCHeader::WWWAuthenticate)
+        .unwrap()
+        .unwrap();
+    assert_eq!(challenges.len(), 2);
+    assert_eq!(challenges[0].algorithm.as_deref(), Some("SHA-256"));
+    assert_eq!(challenges[1].algorithm.as_deref(), Some("MD5"));
+    let proxy: Challenge = response
+        .headers
+        .get_typed(SipRFCHeader::ProxyAuthenticate)
+        .unwrap()
+        .unwrap();
+    assert_eq!(proxy.scheme, AuthScheme::Bearer);
+    assert_eq!(proxy.param("scope"), Some("sip"));
+
+    let mut serializer = SipMsgSerializer::new(Vec::new());
+    serializer.serialize_msg(&msg).unwrap();
+    assert_eq!(serializer.output().as_slice(), buf);
+
+    // the rest of the line is written as a separate header
+    let mut msg = msg;
+    if let SipMessage::Response(response) = &mut msg {
+        response.headers.pop_rfc(SipRFCHeader::WWWAuthenticate);
+    }
+    let mut serializer = SipMsgSerializer::new(Vec::new());
+    serializer.serialize_msg(&msg).unwrap();
+    assert_eq!(
+        std::str::from_utf8(serializer.output()).unwrap(),
+        "SIP/2.0 401 Unauthorized\r\n\
+        WWW-Authenticate: Digest realm=\"atlanta.com\", nonce=\"84a4cc6f\", algorithm=MD5\r\n\
+        Proxy-Authenticate: Bearer realm=\"atlanta.com\", scope=\"sip\"\r\n\
+        Content-Length: 0\r\n\r\n"
+    );
+}
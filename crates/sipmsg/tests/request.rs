@@ -18,11 +18,20 @@ fn parse_request() {
     let res = SipRequest::parse(invite_msg_buf);
     let (_, parsed_req) = res.unwrap();
 
-    assert_eq!(parsed_req.rl.raw, "INVITE sip:bob@biloxi.com SIP/2.0\r\n".as_bytes());
+    assert_eq!(
+        parsed_req.rl.raw,
+        "INVITE sip:bob@biloxi.com SIP/2.0\r\n".as_bytes()
+    );
     assert_eq!(parsed_req.rl.method, SipMethod::INVITE);
-    assert_eq!(parsed_req.rl.uri.scheme, SipRequestUriScheme::SIP);
-    assert_eq!(parsed_req.rl.uri.user_info().unwrap().value, "bob");
-    assert_eq!(parsed_req.rl.uri.hostport.host, "biloxi.com");
+    assert_eq!(
+        parsed_req.rl.uri.sip().unwrap().scheme,
+        SipRequestUriScheme::SIP
+    );
+    assert_eq!(
+        parsed_req.rl.uri.sip().unwrap().user_info().unwrap().value,
+        "bob"
+    );
+    assert_eq!(parsed_req.rl.uri.sip().unwrap().hostport.host, "biloxi.com");
     assert_eq!(parsed_req.rl.sip_version, SipVersion(2, 0));
 
     assert_eq!(parsed_req.headers.len(), 9);
@@ -43,7 +52,7 @@ fn parse_request() {
             .params()
             .unwrap()
             .get(&"branch"),
-        Some(&Some("z9hG4bKkjshdyff"))
+        Some(Some("z9hG4bKkjshdyff"))
     );
     assert_eq!(
         parsed_req
@@ -71,7 +80,7 @@ fn parse_request() {
             .params()
             .unwrap()
             .get(&"tag"),
-        Some(&Some("88sja8x"))
+        Some(Some("88sja8x"))
     );
     assert_eq!(
         parsed_req
@@ -81,7 +90,7 @@ fn parse_request() {
             .params()
             .unwrap()
             .get(&"onemore"),
-        Some(&None)
+        Some(None)
     );
 
     assert_eq!(
@@ -132,7 +141,7 @@ fn parse_request() {
             .params()
             .unwrap()
             .get(&"q"),
-        Some(&Some("0.1"))
+        Some(Some("0.1"))
     );
 
     let callinfo_headers = parsed_req.headers.get_rfc(SipRFCHeader::CallInfo).unwrap();
@@ -148,7 +157,7 @@ fn parse_request() {
 
     assert_eq!(
         callinfo_headers[0].params().unwrap().get("purpose"),
-        Some(&Some("icon"))
+        Some(Some("icon"))
     );
 
     assert_eq!(
@@ -162,13 +171,13 @@ fn parse_request() {
 
     assert_eq!(
         callinfo_headers[1].params().unwrap().get("purpose"),
-        Some(&Some("info"))
+        Some(Some("info"))
     );
 
     let contact_header = parsed_req.headers.get_rfc_s(SipRFCHeader::Contact).unwrap();
     assert_eq!(
         contact_header.value.tags().unwrap()[&SipHeaderTagType::DisplayName],
-        b"Caller"
+        "Caller".as_bytes()
     );
     assert_eq!(
         contact_header
@@ -192,7 +201,7 @@ fn parse_request() {
             .params()
             .unwrap()
             .get(&"transport"),
-        Some(&Some("tcp"))
+        Some(Some("tcp"))
     );
 
     assert_eq!(parsed_req.body.unwrap(), "body_stuff".as_bytes())
@@ -204,10 +213,10 @@ fn get_method_type() {
     let (_, rl) = res.unwrap();
 
     assert_eq!(rl.method, SipMethod::OPTIONS);
-    assert_eq!(rl.uri.scheme, SipRequestUriScheme::SIP);
+    assert_eq!(rl.uri.sip().unwrap().scheme, SipRequestUriScheme::SIP);
     assert_eq!(rl.sip_version, SipVersion(2, 0));
-    assert_eq!(rl.uri.user_info().unwrap().value, "user");
-    assert_eq!(rl.uri.hostport.host, "example.com");
+    assert_eq!(rl.uri.sip().unwrap().user_info().unwrap().value, "user");
+    assert_eq!(rl.uri.sip().unwrap().hostport.host, "example.com");
 
     let res = SipRequestLine::parse(
         "INVITE sips:vivekg@chair-dnrc.example.com;unknownparam SIP/2.0\r\n".as_bytes(),
@@ -215,26 +224,226 @@ fn get_method_type() {
     let (_, rl) = res.unwrap();
 
     assert_eq!(rl.method, SipMethod::INVITE);
-    assert_eq!(rl.uri.scheme, SipRequestUriScheme::SIPS);
+    assert_eq!(rl.uri.sip().unwrap().scheme, SipRequestUriScheme::SIPS);
     assert_eq!(rl.sip_version, SipVersion(2, 0));
-    assert_eq!(rl.uri.user_info().unwrap().value, "vivekg");
-    assert_eq!(rl.uri.hostport.host, "chair-dnrc.example.com");
-    assert_eq!(rl.uri.params().unwrap().get(&"unknownparam"), Some(&None));
+    assert_eq!(rl.uri.sip().unwrap().user_info().unwrap().value, "vivekg");
+    assert_eq!(
+        rl.uri.sip().unwrap().hostport.host,
+        "chair-dnrc.example.com"
+    );
+    assert_eq!(
+        rl.uri.sip().unwrap().params().unwrap().get(&"unknownparam"),
+        Some(None)
+    );
 
     let res = SipRequestLine::parse("REGISTER sip:[2001:db8::10]:9999 SIP/3.1\r\n".as_bytes());
     let (_, rl) = res.unwrap();
 
     assert_eq!(rl.method, SipMethod::REGISTER);
-    assert_eq!(rl.uri.scheme, SipRequestUriScheme::SIP);
+    assert_eq!(rl.uri.sip().unwrap().scheme, SipRequestUriScheme::SIP);
     assert_eq!(rl.sip_version, SipVersion(3, 1));
-    assert_eq!(rl.uri.hostport.host, "2001:db8::10");
-    assert_eq!(rl.uri.hostport.port.unwrap(), 9999);
+    assert_eq!(rl.uri.sip().unwrap().hostport.host, "2001:db8::10");
+    assert_eq!(rl.uri.sip().unwrap().hostport.port.unwrap(), 9999);
 }
 
 #[test]
 fn get_method_type_fail() {
-    match SipRequestLine::parse("OPTI2ONS sip:user@example.com SIP/2.0\r\n".as_bytes()) {
+    match SipRequestLine::parse("OPTI(ONS sip:user@example.com SIP/2.0\r\n".as_bytes()) {
         Ok((_, _)) => panic!(),
         Err(_e) => (),
     }
 }
+
+#[test]
+fn get_extension_method_type() {
+    let (_, rl) =
+        SipRequestLine::parse("OPTI2ONS sip:user@example.com SIP/2.0\r\n".as_bytes()).unwrap();
+    assert_eq!(rl.method.as_str(), "OPTI2ONS");
+    assert_eq!(rl.method.to_string(), "OPTI2ONS");
+    let rl = rl.into_owned();
+    assert_eq!(rl.method, SipMethod::extension("OPTI2ONS").unwrap());
+}
+
+#[test]
+fn parse_request_with_uppercase_scheme() {
+    let (_, rl) = SipRequestLine::parse(b"INVITE SIP:bob@biloxi.com SIP/2.0\r\n").unwrap();
+    let uri = rl.uri.sip().unwrap();
+    assert_eq!(uri.scheme, SipRequestUriScheme::SIP);
+    assert_eq!(uri.user_info().unwrap().value, "bob");
+    assert_eq!(uri.hostport.host, "biloxi.com");
+}
+
+#[test]
+fn parse_request_with_tel_uri() {
+    let buf = "INVITE tel:+1-201-555-0123;phone-context=example.com SIP/2.0\r\n\
+Via: SIP/2.0/UDP pc33.atlanta.com;branch=z9hG4bKkjshdyff\r\n\
+To: <tel:+1-201-555-0123>\r\n\
+From: tel:7042;phone-context=example.com;tag=88sja8x\r\n\
+Contact: \"Alice\" <tel:+44-20-7946-0958;ext=12>\r\n\
+CSeq: 986759 INVITE\r\n\r\n"
+        .as_bytes();
+    let (_, request) = SipRequest::parse(buf).unwrap();
+
+    let tel = request.rl.uri.tel().unwrap();
+    assert_eq!(request.rl.uri.sip(), None);
+    assert_eq!(tel.number, "+1-201-555-0123");
+    assert_eq!(tel.phone_context(), Some("example.com"));
+    assert_eq!(tel.to_e164().unwrap(), "+12015550123");
+
+    let to = request.headers.get_rfc_s(SipRFCHeader::To).unwrap();
+    assert_eq!(to.value.tel_uri().unwrap().number, "+1-201-555-0123");
+    assert_eq!(to.value.sip_uri(), None);
+
+    let from = request.headers.get_rfc_s(SipRFCHeader::From).unwrap();
+    assert_eq!(from.value.tel_uri().unwrap().number, "7042");
+    assert_eq!(from.params().unwrap().get("tag"), Some(Some("88sja8x")));
+
+    let contact = request.headers.get_rfc_s(SipRFCHeader::Contact).unwrap();
+    assert_eq!(
+        contact.value.tags().unwrap()[&SipHeaderTagType::DisplayName],
+        "Alice".as_bytes()
+    );
+    let tel = contact.value.tel_uri().unwrap();
+    assert_eq!(tel.ext(), Some("12"));
+    assert!(tel.is_e164());
+}
+
+#[test]
+fn build_request_with_tel_uri() {
+    let (_, uri) = TelUri::parse(b"tel:+1-201-555-0123").unwrap();
+    let msg_buf = SipRequestBuilder::new(SipMethod::MESSAGE, uri)
+        .header(SipRFCHeader::CSeq, "1 MESSAGE")
+        .build()
+        .unwrap();
+    assert!(msg_buf.starts_with(b"MESSAGE tel:+1-201-555-0123 SIP/2.0\r\n"));
+    let (_, request) = SipRequest::parse(&msg_buf).unwrap();
+    assert_eq!(request.rl.uri.to_string(), "tel:+1-201-555-0123");
+}
+
+#[test]
+fn parse_request_with_urn() {
+    let (_, rl) = SipRequestLine::parse("INVITE urn:service:sos SIP/2.0\r\n".as_bytes()).unwrap();
+    assert_eq!(rl.uri.scheme(), "urn");
+    assert_eq!(rl.uri.absolute().unwrap().path, "service:sos");
+    assert_eq!(rl.uri.to_string(), "urn:service:sos");
+}
+
+#[test]
+fn parse_notify_event_headers() {
+    let buf = "NOTIFY sip:alice@pc33.atlanta.com SIP/2.0\r\n\
+        Via: SIP/2.0/UDP server.biloxi.com;branch=z9hG4bK4b43c2ff8.1\r\n\
+        Max-Forwards: 70\r\n\
+        CSeq: 2 NOTIFY\r\n\
+        o: dialog;id=sub1\r\n\
+        Allow-Events: presence, dialog\r\n\
+        Subscription-State: active;expires=599\r\n\
+        Content-Length: 0\r\n\r\n"
+        .as_bytes();
+    let (_, msg) = SipMessage::parse(buf).unwrap();
+    let request = msg.request().unwrap();
+    assert_eq!(request.rl.method, SipMethod::NOTIFY);
+
+    let event: EventHeader = request
+        .headers
+        .get_typed(SipRFCHeader::Event)
+        .unwrap()
+        .unwrap();
+    assert!(event.is("dialog"));
+    assert_eq!(event.id.as_deref(), Some("sub1"));
+    assert_eq!(
+        request
+            .headers
+            .get_rfc(SipRFCHeader::AllowEvents)
+            .unwrap()
+            .len(),
+        2
+    );
+    let state: SubscriptionState = request
+        .headers
+        .get_typed(SipRFCHeader::SubscriptionState)
+        .unwrap()
+        .unwrap();
+    assert_eq!(state.state, SubState::Active);
+    assert_eq!(state.expires, Some(599));
+}
+
+#[test]
+fn parse_refer_with_replaces() {
+    let buf = "REFER sip:bob@biloxi.com SIP/2.0\r\n\
+        Via: SIP/2.0/UDP pc33.atlanta.com;branch=z9hG4bKnashds8\r\n\
+        Max-Forwards: 70\r\n\
+        CSeq: 93809823 REFER\r\n\
+        r: <sip:carol@chicago.com?Replaces=a7%40pc.chicago.com%3Bto-tag%3D1%3Bfrom-tag%3D2%3Bearly-only>\r\n\
+        b: <sip:alice@atlanta.com>;cid=\"20398823.2UWQFN309shb3@atlanta.com\"\r\n\
+        Content-Length: 0\r\n\r\n"
+        .as_bytes();
+    let (_, msg) = SipMessage::parse(buf).unwrap();
+    let request = msg.request().unwrap();
+    assert_eq!(request.rl.method, SipMethod::REFER);
+
+    let refer_to: NameAddrHeader = request
+        .headers
+        .get_typed(SipRFCHeader::ReferTo)
+        .unwrap()
+        .unwrap();
+    let target = refer_to.uri.sip().unwrap();
+    assert_eq!(target.hostport.host, "chicago.com");
+    let replaces = ReplacesHeader::from_uri(target).unwrap().unwrap();
+    assert_eq!(replaces.call_id, "a7@pc.chicago.com");
+    assert_eq!(replaces.to_tag, "1");
+    assert_eq!(replaces.from_tag, "2");
+    assert!(replaces.early_only);
+
+    let referred_by: NameAddrHeader = request
+        .headers
+        .get_typed(SipRFCHeader::ReferredBy)
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        referred_by.params.get("cid"),
+        Some(Some("20398823.2UWQFN309shb3@atlanta.com"))
+    );
+}
+
+#[test]
+fn parse_asserted_identity_and_privacy() {
+    let buf = "INVITE sip:bob@biloxi.com SIP/2.0\r\n\
+        Via: SIP/2.0/UDP proxy.cisco.com;branch=z9hG4bK77ef4c2312983.1\r\n\
+        Max-Forwards: 69\r\n\
+        CSeq: 1 INVITE\r\n\
+        P-Asserted-Identity: \"Cullen Jennings\" <sip:fluffy@cisco.com>\r\n\
+        P-Asserted-Identity: tel:+14085264000\r\n\
+        P-Preferred-Identity: <sip:fluffy@cisco.com>, <tel:+1-408-526-4000>\r\n\
+        Privacy: id ; critical\r\n\
+        Content-Length: 0\r\n\r\n"
+        .as_bytes();
+    let (_, msg) = SipMessage::parse(buf).unwrap();
+    let request = msg.request().unwrap();
+
+    let asserted: Vec<NameAddrHeader> = request
+        .headers
+        .get_typed_all(SipRFCHeader::PAssertedIdentity)
+        .unwrap()
+        .unwrap();
+    assert_eq!(asserted.len(), 2);
+    assert_eq!(asserted[0].display_name.as_deref(), Some("Cullen Jennings"));
+    assert_eq!(asserted[0].uri.sip().unwrap().hostport.host, "cisco.com");
+    assert_eq!(asserted[1].uri.tel().unwrap().number, "+14085264000");
+
+    let preferred: Vec<NameAddrHeader> = request
+        .headers
+        .get_typed_all(SipRFCHeader::PPreferredIdentity)
+        .unwrap()
+        .unwrap();
+    assert_eq!(preferred.len(), 2);
+    assert!(preferred[0].uri.sip().is_some());
+    assert_eq!(preferred[1].uri.tel().unwrap().number, "+1-408-526-4000");
+
+    let privacy: PrivacyHeader = request
+        .headers
+        .get_typed(SipRFCHeader::Privacy)
+        .unwrap()
+        .unwrap();
+    assert_eq!(privacy.values, [PrivacyValue::Id, PrivacyValue::Critical]);
+    assert_eq!(privacy.to_string(), "id;critical");
+}
@@ -4,12 +4,12 @@ use sipmsg::*;
 fn status_code_from_bytes_str() {
     assert_eq!(
         SipResponseStatusCode::from_bytes_str("100".as_bytes()),
-        SipResponseStatusCode::Trying
+        Some(SipResponseStatusCode::Trying)
     );
 
     assert_eq!(
         SipResponseStatusCode::from_bytes_str("181".as_bytes()),
-        SipResponseStatusCode::CallIsBeingForwarded
+        Some(SipResponseStatusCode::CallIsBeingForwarded)
     );
 }
 
@@ -17,8 +17,75 @@ fn status_code_from_bytes_str() {
 fn status_code_from_str() {
     assert_eq!(
         SipResponseStatusCode::from_str("500"),
-        SipResponseStatusCode::ServerInternalError
+        Some(SipResponseStatusCode::ServerInternalError)
     );
+    assert_eq!(
+        SipResponseStatusCode::from_str("608"),
+        Some(SipResponseStatusCode::Rejected)
+    );
+    assert_eq!(
+        SipResponseStatusCode::from_str("299"),
+        SipResponseStatusCode::from_code(299)
+    );
+    assert_eq!(SipResponseStatusCode::from_str("099"), None);
+    assert_eq!(SipResponseStatusCode::from_str("700"), None);
+    assert_eq!(SipResponseStatusCode::from_str("20"), None);
+    assert_eq!(SipResponseStatusCode::from_str("2a0"), None);
+}
+
+#[test]
+fn status_code_numeric_value() {
+    for code in 100..700 {
+        let status_code = SipResponseStatusCode::from_code(code).unwrap();
+        assert_eq!(status_code.code(), code);
+    }
+    assert_eq!(SipResponseStatusCode::from_code(1000), None);
+    assert_eq!(SipResponseStatusCode::from_code(700), None);
+    assert_eq!(SipResponseStatusCode::from_code(99), None);
+    assert_eq!(
+        SipResponseStatusCode::from_code(200),
+        Some(SipResponseStatusCode::OK)
+    );
+    assert_eq!(SipResponseStatusCode::EarlyDialogTerminated.code(), 199);
+    assert_eq!(
+        SipResponseStatusCode::SessionIntervalTooSmall.reason_phrase(),
+        "Session Interval Too Small"
+    );
+    assert_eq!(
+        SipResponseStatusCode::from_code(599)
+            .unwrap()
+            .reason_phrase(),
+        "Unknown"
+    );
+}
+
+#[test]
+fn status_code_class() {
+    assert!(SipResponseStatusCode::Trying.is_provisional());
+    assert!(!SipResponseStatusCode::Trying.is_final());
+    assert!(SipResponseStatusCode::NoNotification.is_success());
+    assert!(SipResponseStatusCode::UseProxy.is_redirection());
+    assert!(SipResponseStatusCode::ConsentNeeded.is_client_error());
+    assert!(SipResponseStatusCode::from_code(599)
+        .unwrap()
+        .is_server_error());
+    assert!(SipResponseStatusCode::Unwanted.is_global_failure());
+    assert!(SipResponseStatusCode::Unwanted.is_final());
+    assert!(!SipResponseStatusCode::OK.is_client_error());
+}
+
+#[test]
+fn status_line_unregistered_code() {
+    let (_, status_line) = SipResponseStatusLine::parse(b"SIP/2.0 299 Custom\r\n").unwrap();
+    match status_line.status_code {
+        SipResponseStatusCode::Unknown(code) => assert_eq!(code.code(), 299),
+        _ => panic!("299 is not registered"),
+    }
+    assert_eq!(status_line.status_code.code(), 299);
+    assert_eq!(status_line.reason_phrase, "Custom");
+
+    assert!(SipResponseStatusLine::parse(b"SIP/2.0 999 Custom\r\n").is_err());
+    assert!(SipResponseStatusLine::parse(b"SIP/2.0 2x0 Custom\r\n").is_err());
 }
 
 #[test]
@@ -48,7 +115,7 @@ fn parse_response() {
     Content-Length: 0\r\n\r\n";
     match SipResponse::parse(response_msg.as_bytes()) {
         Ok((_, response)) => {
-            assert_eq!(response.sl.raw, b"SIP/2.0 401 Unauthorized\r\n");
+            assert_eq!(response.sl.raw, "SIP/2.0 401 Unauthorized\r\n".as_bytes());
             assert_eq!(response.sl.sip_version, SipVersion(2, 0));
             assert_eq!(response.sl.status_code, SipResponseStatusCode::Unauthorized);
             assert_eq!(response.sl.reason_phrase, "Unauthorized");
@@ -71,7 +138,7 @@ fn parse_response() {
                     .params()
                     .unwrap()
                     .get(&"branch"),
-                Some(&Some("z9hG4bKPj7IVefnk0j6Wn9oUM78ubmcURGDehvKEc"))
+                Some(Some("z9hG4bKPj7IVefnk0j6Wn9oUM78ubmcURGDehvKEc"))
             );
 
             assert_eq!(
@@ -82,7 +149,7 @@ fn parse_response() {
                     .params()
                     .unwrap()
                     .get(&"received"),
-                Some(&Some("192.168.178.69"))
+                Some(Some("192.168.178.69"))
             );
 
             assert_eq!(
@@ -93,7 +160,7 @@ fn parse_response() {
                     .params()
                     .unwrap()
                     .get(&"rport"),
-                Some(&Some("60686"))
+                Some(Some("60686"))
             );
 
             assert_eq!(
@@ -114,7 +181,7 @@ fn parse_response() {
                     .params()
                     .unwrap()
                     .get(&"tag"),
-                Some(&Some("XOO-LeGIwZmwa2UROKMXEhZGA5mKcY0b"))
+                Some(Some("XOO-LeGIwZmwa2UROKMXEhZGA5mKcY0b"))
             );
 
             assert_eq!(
@@ -135,7 +202,7 @@ fn parse_response() {
                     .params()
                     .unwrap()
                     .get(&"tag"),
-                Some(&Some("as68275e50"))
+                Some(Some("as68275e50"))
             );
 
             assert_eq!(
@@ -188,3 +255,34 @@ fn parse_response() {
         Err(_e) => panic!(),
     }
 }
+
+#[test]
+fn reliable_provisional_response() {
+    let msg_buf = SipResponseBuilder::new(SipResponseStatusCode::SessionProgress)
+        .header(SipRFCHeader::Require, "100rel")
+        .header(SipRFCHeader::RSeq, RSeq(988789).to_string())
+        .header(SipRFCHeader::CSeq, "1 INVITE")
+        .build()
+        .unwrap();
+    let (_, msg) = SipMessage::parse(&msg_buf).unwrap();
+    let response = msg.response().unwrap();
+    let rseq: RSeq = response
+        .headers
+        .get_typed(SipRFCHeader::RSeq)
+        .unwrap()
+        .unwrap();
+    let cseq: CSeqHeader = response
+        .headers
+        .get_typed(SipRFCHeader::CSeq)
+        .unwrap()
+        .unwrap();
+
+    let rack = RAckHeader {
+        response_num: rseq.0,
+        cseq_num: cseq.seq,
+        method: cseq.method,
+    };
+    assert_eq!(rack.to_string(), "988789 1 INVITE");
+    let header = SipHeader::from_name_value("RAck", &rack.to_string()).unwrap();
+    assert_eq!(header.typed::<RAckHeader>().unwrap(), rack);
+}
@@ -31,34 +31,50 @@ fn parse_request() {
           newvalue ;\r\n \
           secondparam ; q = 0.33\r\n\
     \r\n\
-    v=0\
-    o=mhandley 29739 7272939 IN IP4 192.0.2.3\
-    s=-\
-    c=IN IP4 192.0.2.4\
-    t=0 0\
-    m=audio 49217 RTP/AVP 0 12\
-    m=video 3227 RTP/AVP 31\
-    a=rtpmap:31 LPC"
+    v=0\r\n\
+    o=mhandley 29739 7272939 IN IP4 192.0.2.3\r\n\
+    s=-\r\n\
+    c=IN IP4 192.0.2.4\r\n\
+    t=0 0\r\n\
+    m=audio 49217 RTP/AVP 0 12\r\n\
+    m=video 3227 RTP/AVP 31\r\n\
+    a=rtpmap:31 LPC\r\n"
         .as_bytes();
 
     let res = SipRequest::parse(invite_msg_buf);
-    let (_, parsed_req) = res.unwrap();
+    let (rest, parsed_req) = res.unwrap();
+    assert_eq!(rest.len(), 0);
     let request_line = &parsed_req.rl;
     let headers = &parsed_req.headers;
     assert_eq!(request_line.method, SipMethod::INVITE);
-    assert_eq!(request_line.uri.scheme, SipRequestUriScheme::SIP);
-    assert_eq!(request_line.uri.user_info().unwrap().value, "vivekg");
-    assert_eq!(request_line.uri.hostport.host, "chair-dnrc.example.com");
+    assert_eq!(
+        request_line.uri.sip().unwrap().scheme,
+        SipRequestUriScheme::SIP
+    );
+    assert_eq!(
+        request_line.uri.sip().unwrap().user_info().unwrap().value,
+        "vivekg"
+    );
+    assert_eq!(
+        request_line.uri.sip().unwrap().hostport.host,
+        "chair-dnrc.example.com"
+    );
     assert_eq!(request_line.sip_version, SipVersion(2, 0));
     assert_eq!(
-        request_line.uri.params().unwrap().get(&"unknownparam"),
-        Some(&None)
+        request_line
+            .uri
+            .sip()
+            .unwrap()
+            .params()
+            .unwrap()
+            .get(&"unknownparam"),
+        Some(None)
     );
 
     let to_hdr = headers.get_rfc_s(SipRFCHeader::To).unwrap();
     assert_eq!(
         to_hdr.params().unwrap().get(&"tag"),
-        Some(&Some("1918181833n"))
+        Some(Some("1918181833n"))
     );
     assert_eq!(to_hdr.value.vstr, "sip:vivekg@chair-dnrc.example.com");
 
@@ -69,7 +85,7 @@ fn parse_request() {
     );
     assert_eq!(
         from_hdr.value.tags().unwrap()[&SipHeaderTagType::DisplayName],
-        b"J Rosenberg \\\""
+        "J Rosenberg \\\"".as_bytes()
     );
     assert_eq!(
         from_hdr.value.sip_uri().unwrap().user_info().unwrap().value,
@@ -81,7 +97,7 @@ fn parse_request() {
     );
     assert_eq!(
         from_hdr.params().unwrap().get(&"tag"),
-        Some(&Some("98asjd8"))
+        Some(Some("98asjd8"))
     );
 
     let max_forwards = parsed_req
@@ -96,11 +112,11 @@ fn parse_request() {
     assert_eq!(call_id.value.vstr, "wsinv.ndaksdj@192.0.2.1");
     assert_eq!(
         call_id.value.tags().unwrap()[&SipHeaderTagType::ID],
-        b"wsinv.ndaksdj"
+        "wsinv.ndaksdj".as_bytes()
     );
     assert_eq!(
         call_id.value.tags().unwrap()[&SipHeaderTagType::Host],
-        b"192.0.2.1"
+        "192.0.2.1".as_bytes()
     );
 
     let content_length = &parsed_req
@@ -114,11 +130,11 @@ fn parse_request() {
     assert_eq!(cseq_header.params(), None);
     assert_eq!(
         cseq_header.value.tags().unwrap()[&SipHeaderTagType::Number],
-        b"0009"
+        "0009".as_bytes()
     );
     assert_eq!(
         cseq_header.value.tags().unwrap()[&SipHeaderTagType::Method],
-        b"INVITE"
+        "INVITE".as_bytes()
     );
 
     let via_hdrs = headers.get_rfc(SipRFCHeader::Via).unwrap();
@@ -126,30 +142,30 @@ fn parse_request() {
     assert_eq!(first_via.value.vstr, "SIP  /   2.0\r\n /UDP\r\n 192.0.2.2");
     assert_eq!(
         first_via.params().unwrap().get(&"branch"),
-        Some(&Some("390skdjuw"))
+        Some(Some("390skdjuw"))
     );
 
     assert_eq!(
         first_via.value.tags().unwrap()[&SipHeaderTagType::ProtocolName],
-        b"SIP"
+        "SIP".as_bytes()
     );
     assert_eq!(
         first_via.value.tags().unwrap()[&SipHeaderTagType::ProtocolVersion],
-        b"2.0"
+        "2.0".as_bytes()
     );
     assert_eq!(
         first_via.value.tags().unwrap()[&SipHeaderTagType::ProtocolTransport],
-        b"UDP"
+        "UDP".as_bytes()
     );
     assert_eq!(
         first_via.value.tags().unwrap()[&SipHeaderTagType::Host],
-        b"192.0.2.2"
+        "192.0.2.2".as_bytes()
     );
 
     let seond_via = &via_hdrs[1];
     assert_eq!(
         seond_via.value.tags().unwrap()[&SipHeaderTagType::ProtocolTransport],
-        b"TCP"
+        "TCP".as_bytes()
     );
     assert_eq!(
         seond_via.value.vstr,
@@ -157,11 +173,11 @@ fn parse_request() {
     );
     assert_eq!(
         seond_via.value.tags().unwrap()[&SipHeaderTagType::Host],
-        b"spindle.example.com"
+        "spindle.example.com".as_bytes()
     );
     assert_eq!(
         seond_via.params().unwrap().get(&"branch"),
-        Some(&Some("z9hG4bK9ikj8"))
+        Some(Some("z9hG4bK9ikj8"))
     );
 
     let subject_hdr = &headers.get_rfc_s(SipRFCHeader::Subject).unwrap();
@@ -189,9 +205,9 @@ fn parse_request() {
     let route_uri_params = &route_uri.params().unwrap();
     assert_eq!(route_uri.scheme, sipuri::RequestUriScheme::SIP);
     assert_eq!(route_uri.hostport.host, "services.example.com");
-    assert_eq!(route_uri_params.get(&"lr"), Some(&None));
-    assert_eq!(route_uri_params.get(&"unknownwith"), Some(&Some("value")));
-    assert_eq!(route_uri_params.get(&"unknown-no-value"), Some(&None));
+    assert_eq!(route_uri_params.get(&"lr"), Some(None));
+    assert_eq!(route_uri_params.get(&"unknownwith"), Some(Some("value")));
+    assert_eq!(route_uri_params.get(&"unknown-no-value"), Some(None));
     assert_eq!(route_uri_params.get(&"missing_param"), None);
 
     let contact = &headers.get_rfc_s(SipRFCHeader::Contact).unwrap();
@@ -201,12 +217,12 @@ fn parse_request() {
     );
     assert_eq!(
         contact.value.tags().unwrap()[&SipHeaderTagType::DisplayName],
-        b"Quoted string \\\"\\\""
+        "Quoted string \\\"\\\"".as_bytes()
     );
     let contact_params = contact.params().unwrap();
-    assert_eq!(contact_params.get(&"newparam"), Some(&Some("newvalue")));
-    assert_eq!(contact_params.get(&"secondparam"), Some(&None));
-    assert_eq!(contact_params.get(&"q"), Some(&Some("0.33")));
+    assert_eq!(contact_params.get(&"newparam"), Some(Some("newvalue")));
+    assert_eq!(contact_params.get(&"secondparam"), Some(None));
+    assert_eq!(contact_params.get(&"q"), Some(Some("0.33")));
 
     let contact_uri = &contact.value.sip_uri().unwrap();
     assert_eq!(contact_uri.scheme, sipuri::RequestUriScheme::SIP);
@@ -216,14 +232,14 @@ fn parse_request() {
     /*********************************************************/
     assert_eq!(
         parsed_req.body.unwrap(),
-        "v=0\
-    o=mhandley 29739 7272939 IN IP4 192.0.2.3\
-    s=-\
-    c=IN IP4 192.0.2.4\
-    t=0 0\
-    m=audio 49217 RTP/AVP 0 12\
-    m=video 3227 RTP/AVP 31\
-    a=rtpmap:31 LPC"
+        "v=0\r\n\
+    o=mhandley 29739 7272939 IN IP4 192.0.2.3\r\n\
+    s=-\r\n\
+    c=IN IP4 192.0.2.4\r\n\
+    t=0 0\r\n\
+    m=audio 49217 RTP/AVP 0 12\r\n\
+    m=video 3227 RTP/AVP 31\r\n\
+    a=rtpmap:31 LPC\r\n"
             .as_bytes()
     );
 }